@@ -0,0 +1,63 @@
+// OBI_BATCH bench - Array-of-Structs snapshots vs Struct-of-Arrays columns
+// Run: cargo bench --bench obi_batch
+//
+// The crate is a cdylib whose N-API symbols only resolve inside Node,
+// so the physics modules are compiled straight into the bench binary.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use physics::obi_columns::OrderBookColumns;
+use physics::obi_engine::{EngineConfig, ObiConfig, OrderBookSnapshot, PhysicsEngine};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+#[allow(dead_code, unused_imports)]
+#[path = "../src/physics/mod.rs"]
+mod physics;
+
+fn snapshots(n: usize) -> Vec<OrderBookSnapshot> {
+    let mut rng = StdRng::seed_from_u64(42);
+    (0..n)
+        .map(|i| {
+            let mid = 100.0 + rng.gen_range(-1.0..1.0);
+            let half_spread = rng.gen_range(0.005..0.05);
+            OrderBookSnapshot {
+                timestamp: i as u64,
+                bid_volume: rng.gen_range(0.0..1_000.0),
+                ask_volume: rng.gen_range(0.0..1_000.0),
+                bid_price: mid - half_spread,
+                ask_price: mid + half_spread,
+                last_trade_price: None,
+                symbol: None,
+                venue: None,
+            }
+        })
+        .collect()
+}
+
+fn bench_obi(c: &mut Criterion) {
+    let engine = PhysicsEngine::new(EngineConfig::default()).unwrap();
+    let config = ObiConfig::default();
+    let mut group = c.benchmark_group("obi_batch");
+
+    for n in [10_000usize, 1_000_000] {
+        let books = snapshots(n);
+        let bid_price: Vec<f64> = books.iter().map(|s| s.bid_price).collect();
+        let bid_volume: Vec<f64> = books.iter().map(|s| s.bid_volume).collect();
+        let ask_price: Vec<f64> = books.iter().map(|s| s.ask_price).collect();
+        let ask_volume: Vec<f64> = books.iter().map(|s| s.ask_volume).collect();
+        let columns = OrderBookColumns::new(&bid_price, &bid_volume, &ask_price, &ask_volume).unwrap();
+
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("aos", n), &books, |b, books| {
+            b.iter(|| engine.calculate_obi_batch(black_box(books), &config).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("soa", n), &columns, |b, columns| {
+            b.iter(|| engine.calculate_obi_columns(black_box(*columns)).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_obi);
+criterion_main!(benches);
@@ -1,5 +1,3 @@
-use serde::{Deserialize, Serialize};
-
 // GAME_THEORY.rs - Competitor Behavior Analysis (Nash Equilibrium)
 // COMPLEXITY: O(1) - Constant time analysis
 // DETERMINISTIC: Rule-based decision making
@@ -1,4 +1,4 @@
-/**
+/*
  * ═══════════════════════════════════════════════════════════════════════════════
  * QANTUM PHYSICS CORE - NAPI-RS BRIDGE (Rust ↔ TypeScript)
  * ═══════════════════════════════════════════════════════════════════════════════
@@ -18,10 +18,35 @@ mod physics;
 mod omega;
 mod intelligence;
 
-use napi::{bindgen_prelude::*, JsObject};
-use physics::obi_engine::{self, OrderBookSnapshot, ObiResult as RustObiResult};
-use physics::tda::TopologicalAnalyzer;
-use omega::mempool::{MempoolListener, MempoolTransaction};
+use napi::bindgen_prelude::*;
+use physics::obi_engine::{
+    ArithmeticMode, DepthObiConfig, DepthSnapshot, EngineConfig, EngineError, EngineHandle,
+    ObiConfig, ObiResult as RustObiResult, OfiConfig, OfiResult, OrderBookSnapshot, PhysicsEngine,
+    PriceLevel, SignalThresholds, StreamingObiConfig, StreamingObiEngine, ValidatedObiBatch,
+    ValidatedObiColumns,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use physics::backend::BackendRegistry;
+use physics::entropy;
+use physics::consolidated::{ConsolidatedLevel, ConsolidatedResult};
+use physics::instrument::ObiGroup;
+use physics::obi_columns::{ObiColumns, OrderBookColumns};
+use physics::validation::{ValidationConfig, ValidationCounts, ValidationMode};
+use physics::volatility::{self, BarSeries, OhlcBar, VolatilityConfig, VolatilityEstimate};
+use physics::vpin::{self, VpinConfig, VpinPoint};
+use physics::kalman::{self, FairValueFilter, KalmanConfig, KalmanModel, KalmanPoint, LinearKalman};
+use physics::regime::{GaussianHmm, HmmConfig, HmmFilter};
+use physics::persistence::PersistencePair;
+use physics::dimension::{self, DimensionConfig, DimensionEstimate};
+use physics::mapper::MapperConfig;
+use physics::streaming_tda::{StreamingTdaConfig, StreamingTdaMetrics, StreamingTdaState, StreamingTopology};
+use physics::takens::{DimensionMethod, TakensConfig, TakensEmbedding};
+use physics::tda::{
+    DimensionPoint, DimensionSeriesConfig, EmbeddingSource, LiquiditySurfaceConfig, MapperLens, LiquiditySurfaceCurvature, LiquidityVoid, TdaConfig, TopologicalAnalyzer, TopologyPoint, TopologySeries, TopologySeriesConfig, TopologySummary,
+};
+use physics::trades::{ClassificationMethod, Trade, TradeClassifierConfig};
+use omega::mempool::{MempoolConfig, MempoolListener, MempoolStats, MempoolTransaction};
 use intelligence::game_theory;
 use sysinfo::{System, SystemExt, CpuExt};
 
@@ -56,11 +81,90 @@ pub fn get_hardware_telemetry() -> HardwareMetrics {
     }
 }
 
-/// Initialize the Physics Engine (call once on startup)
+impl From<EngineError> for Error {
+    fn from(e: EngineError) -> Self {
+        Error::new(Status::GenericFailure, e.to_string())
+    }
+}
+
+/// Engine options from TypeScript (all optional)
+#[napi(object)]
+pub struct EngineOptions {
+    pub buy_threshold: Option<f64>,
+    pub sell_threshold: Option<f64>,
+    pub backend: Option<String>, // "auto" | "scalar" | "rayon" | "cuda"
+    pub deterministic: Option<bool>, // Fixed-point OBI/entropy/relative spread for ledger sealing
+    pub threads: Option<u32>,    // Rayon pool size (0 / omitted = one per core)
+    pub instrument_thresholds: Option<HashMap<String, InstrumentThresholds>>, // Keyed "SYMBOL" or "SYMBOL@VENUE"
+}
+
+/// Per-instrument signal thresholds (omitted side falls back to the engine threshold)
+#[napi(object)]
+pub struct InstrumentThresholds {
+    pub buy: Option<f64>,
+    pub sell: Option<f64>,
+}
+
+impl EngineOptions {
+    fn into_config(self) -> EngineConfig {
+        let defaults = SignalThresholds::default();
+        let thresholds = SignalThresholds {
+            buy: self.buy_threshold.unwrap_or(defaults.buy),
+            sell: self.sell_threshold.unwrap_or(defaults.sell),
+        };
+
+        EngineConfig {
+            thresholds,
+            backend: self.backend.unwrap_or_else(|| "auto".to_string()),
+            arithmetic: if self.deterministic.unwrap_or(false) {
+                ArithmeticMode::FixedPoint
+            } else {
+                ArithmeticMode::Float
+            },
+            threads: self.threads.unwrap_or(0) as usize,
+            instrument_thresholds: self
+                .instrument_thresholds
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(key, t)| {
+                    let overrides = SignalThresholds {
+                        buy: t.buy.unwrap_or(thresholds.buy),
+                        sell: t.sell.unwrap_or(thresholds.sell),
+                    };
+                    (key, overrides)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Default engine behind the free functions (`init_physics_engine`, `calculate_obi_batch`, ...).
+/// New code should create `PhysicsEngine` class instances instead.
+static DEFAULT_ENGINE: RwLock<Option<Arc<PhysicsEngine>>> = RwLock::new(None);
+
+fn default_engine() -> Result<Arc<PhysicsEngine>> {
+    let engine = DEFAULT_ENGINE.read().unwrap();
+    engine.clone().ok_or_else(|| EngineError::NotInitialized.into())
+}
+
+/// Initialize the default Physics Engine (call once on startup)
+/// `deterministic: true` selects fixed-point OBI/entropy/relative spread for ledger sealing
 #[napi]
-pub fn init_physics_engine() -> Result<String> {
-    match obi_engine::PhysicsEngine::init() {
-        Ok(_) => Ok("🔥 Physics Engine: CUDA/CPU ONLINE".to_string()),
+pub fn init_physics_engine(deterministic: Option<bool>) -> Result<String> {
+    let config = EngineConfig {
+        arithmetic: if deterministic.unwrap_or(false) {
+            ArithmeticMode::FixedPoint
+        } else {
+            ArithmeticMode::Float
+        },
+        ..Default::default()
+    };
+
+    match PhysicsEngine::new(config) {
+        Ok(engine) => {
+            *DEFAULT_ENGINE.write().unwrap() = Some(Arc::new(engine));
+            Ok("🔥 Physics Engine: CUDA/CPU ONLINE".to_string())
+        }
         Err(e) => Err(Error::from_reason(format!("Failed to init physics engine: {}", e))),
     }
 }
@@ -70,12 +174,7 @@ pub fn init_physics_engine() -> Result<String> {
 pub fn calculate_manifold_curvature(market_data: Vec<OrderBookData>) -> f64 {
     let snapshots: Vec<OrderBookSnapshot> = market_data
         .iter()
-        .map(|data| OrderBookSnapshot {
-            bid_price: data.bid_price as f32,
-            bid_volume: data.bid_volume as f32,
-            ask_price: data.ask_price as f32,
-            ask_volume: data.ask_volume as f32,
-        })
+        .map(OrderBookSnapshot::from)
         .collect();
 
     let curvatures = TopologicalAnalyzer::calculate_curvature(&snapshots);
@@ -92,18 +191,117 @@ pub struct DetectedWhale {
     pub hash: String,
     pub value_eth: f64,
     pub to_exchange: bool,
+    pub from: String,
+    pub to: String,
+    pub gas_price_gwei: f64,
+    pub timestamp: i64,
 }
 
-/// Scan Mempool for Whales
-#[napi]
-pub fn scan_mempool() -> Vec<DetectedWhale> {
-    let txs = MempoolListener::scan();
-    
-    txs.into_iter().map(|tx| DetectedWhale {
-        hash: tx.hash,
-        value_eth: tx.value_eth,
-        to_exchange: tx.to.contains("Binance") || tx.to.contains("3f5C"), // Simple check
-    }).collect()
+impl From<MempoolTransaction> for DetectedWhale {
+    fn from(tx: MempoolTransaction) -> Self {
+        DetectedWhale {
+            to_exchange: tx.to_exchange(),
+            hash: tx.hash,
+            value_eth: tx.value_eth,
+            from: tx.from,
+            to: tx.to,
+            gas_price_gwei: tx.gas_price_gwei,
+            timestamp: tx.timestamp as i64,
+        }
+    }
+}
+
+/// Process-wide pending-transaction subscriber behind connect_mempool / scan_mempool
+static MEMPOOL: Mutex<Option<MempoolListener>> = Mutex::new(None);
+
+fn mempool() -> std::sync::MutexGuard<'static, Option<MempoolListener>> {
+    MEMPOOL.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Ethereum node subscription options (url required, the rest optional)
+#[napi(object)]
+pub struct MempoolOptions {
+    pub url: String,                      // ws:// or wss:// JSON-RPC endpoint
+    pub whale_threshold_eth: Option<f64>, // Minimum buffered value (default 100)
+    pub buffer_capacity: Option<u32>,     // Buffered transactions, oldest dropped (default 1024)
+    pub initial_backoff_ms: Option<u32>,  // Reconnect backoff, doubled per failure (default 250)
+    pub max_backoff_ms: Option<u32>,      // Backoff cap (default 30000)
+    pub request_timeout_ms: Option<u32>,  // Lookup timeout (default 10000)
+    pub max_in_flight: Option<u32>,       // Concurrent lookups (default 256)
+}
+
+impl MempoolOptions {
+    fn into_config(self) -> MempoolConfig {
+        let defaults = MempoolConfig::default();
+        MempoolConfig {
+            url: self.url,
+            whale_threshold_eth: self.whale_threshold_eth.unwrap_or(defaults.whale_threshold_eth),
+            buffer_capacity: self.buffer_capacity.map_or(defaults.buffer_capacity, |n| n as usize),
+            initial_backoff_ms: self.initial_backoff_ms.map_or(defaults.initial_backoff_ms, u64::from),
+            max_backoff_ms: self.max_backoff_ms.map_or(defaults.max_backoff_ms, u64::from),
+            request_timeout_ms: self.request_timeout_ms.map_or(defaults.request_timeout_ms, u64::from),
+            max_in_flight: self.max_in_flight.map_or(defaults.max_in_flight, |n| n as usize),
+        }
+    }
+}
+
+#[napi(object)]
+pub struct MempoolStatsData {
+    pub connected: bool,
+    pub connects: i64,
+    pub reconnects: i64,
+    pub hashes_seen: i64,
+    pub transactions: i64,
+    pub filtered: i64,
+    pub missing: i64,
+    pub dropped: i64,
+    pub timeouts: i64,
+    pub last_error: Option<String>,
+}
+
+impl From<MempoolStats> for MempoolStatsData {
+    fn from(s: MempoolStats) -> Self {
+        MempoolStatsData {
+            connected: s.connected,
+            connects: s.connects as i64,
+            reconnects: s.reconnects as i64,
+            hashes_seen: s.hashes_seen as i64,
+            transactions: s.transactions as i64,
+            filtered: s.filtered as i64,
+            missing: s.missing as i64,
+            dropped: s.dropped as i64,
+            timeouts: s.timeouts as i64,
+            last_error: s.last_error,
+        }
+    }
+}
+
+/// Subscribe to newPendingTransactions on an Ethereum node (replaces any existing subscription)
+#[napi(namespace = "mempool", js_name = "connect_mempool")]
+pub fn connect_mempool(options: MempoolOptions) -> Result<()> {
+    let listener = MempoolListener::connect(options.into_config()).map_err(EngineError::InvalidConfig)?;
+    *mempool() = Some(listener);
+    Ok(())
+}
+
+#[napi(namespace = "mempool", js_name = "disconnect_mempool")]
+pub fn disconnect_mempool() {
+    mempool().take();
+}
+
+/// Subscriber counters (null when not connected)
+#[napi(namespace = "mempool", js_name = "mempool_stats")]
+pub fn mempool_stats() -> Option<MempoolStatsData> {
+    mempool().as_ref().map(|listener| listener.stats().into())
+}
+
+/// Drain buffered pending transactions at or above `whale_threshold_eth`; smaller ones are
+/// discarded (empty when not connected)
+#[napi(namespace = "mempool", js_name = "scan_mempool")]
+pub fn scan_mempool(whale_threshold_eth: Option<f64>) -> Vec<DetectedWhale> {
+    let threshold = whale_threshold_eth.unwrap_or(0.0);
+    let txs = mempool().as_ref().map(|listener| listener.scan(threshold)).unwrap_or_default();
+    txs.into_iter().map(DetectedWhale::from).collect()
 }
 
 /// Analyze Competitor Behavior (Game Theory)
@@ -119,6 +317,25 @@ pub struct OrderBookData {
     pub bid_volume: f64,
     pub ask_price: f64,
     pub ask_volume: f64,
+    pub timestamp: Option<i64>, // Epoch millis (optional, 0 if omitted)
+    pub last_trade_price: Option<f64>,
+    pub symbol: Option<String>, // Instrument tag, e.g. "BTC-USD"
+    pub venue: Option<String>,  // Exchange tag, e.g. "binance"
+}
+
+impl From<&OrderBookData> for OrderBookSnapshot {
+    fn from(data: &OrderBookData) -> Self {
+        OrderBookSnapshot {
+            timestamp: data.timestamp.unwrap_or(0).max(0) as u64,
+            bid_price: data.bid_price,
+            bid_volume: data.bid_volume,
+            ask_price: data.ask_price,
+            ask_volume: data.ask_volume,
+            last_trade_price: data.last_trade_price,
+            symbol: data.symbol.clone(),
+            venue: data.venue.clone(),
+        }
+    }
 }
 
 /// OBI Result returned to TypeScript
@@ -127,55 +344,2319 @@ pub struct ObiResult {
     pub imbalance: f64,
     pub signal: String,
     pub gpu_latency_ms: f64,
+    pub microprice: f64,
+    pub weighted_mid: f64,
+    pub spread_bps: f64,
+    pub spread_ticks: f64,
+    pub effective_spread: Option<f64>,
+    pub entropy: f64,               // Shannon entropy (bits) of the book's volume distribution
+    pub renyi_entropy: f64,         // Rényi entropy (bits), order 2 by default
+    pub kl_divergence: Option<f64>, // Divergence from the previous book of the same instrument (bits)
+    pub relative_spread: f64,       // Spread / mid (formerly reported as "entropy")
+    pub obi_fixed: Option<i64>,     // Raw fixed-point OBI (deterministic mode only)
+    pub entropy_fixed: Option<i64>, // Raw fixed-point Shannon entropy (deterministic mode only)
+    pub relative_spread_fixed: Option<i64>, // Raw fixed-point relative spread (deterministic mode only)
+    pub validation_error: Option<String>, // Set for invalid snapshots in "flag" validation mode
+}
+
+impl ObiResult {
+    fn from_engine(r: &RustObiResult, gpu_latency_ms: f64) -> Self {
+        ObiResult {
+            imbalance: r.obi,
+            signal: r.signal.clone(),
+            gpu_latency_ms,
+            microprice: r.microprice,
+            weighted_mid: r.weighted_mid,
+            spread_bps: r.spread_bps,
+            spread_ticks: r.spread_ticks,
+            effective_spread: r.effective_spread,
+            entropy: r.entropy,
+            renyi_entropy: r.renyi_entropy,
+            kl_divergence: r.kl_divergence,
+            relative_spread: r.relative_spread,
+            obi_fixed: r.fixed.map(|f| f.obi),
+            entropy_fixed: r.fixed.map(|f| f.entropy),
+            relative_spread_fixed: r.fixed.map(|f| f.relative_spread),
+            validation_error: r.validation.map(|e| e.to_string()),
+        }
+    }
+}
+
+/// Tick size from TypeScript, falling back to the engine default
+fn obi_config(tick_size: Option<f64>) -> ObiConfig {
+    let defaults = ObiConfig::default();
+    ObiConfig {
+        tick_size: tick_size.unwrap_or(defaults.tick_size),
+        ..defaults
+    }
 }
 
 /// Calculate Order Book Imbalance (exposed to TypeScript)
+/// `tick_size` defaults to 0.01; mixed-instrument batches are not split (see `calculate_grouped_obi_batch`)
 #[napi]
-pub async fn calculate_obi_batch(market_data: Vec<OrderBookData>) -> Result<Vec<ObiResult>> {
+pub async fn calculate_obi_batch(market_data: Vec<OrderBookData>, tick_size: Option<f64>) -> Result<Vec<ObiResult>> {
+    let engine = default_engine()?;
     let start = std::time::Instant::now();
     
     // Convert TypeScript data to Rust structs
     let snapshots: Vec<OrderBookSnapshot> = market_data
         .iter()
-        .map(|data| OrderBookSnapshot {
-            timestamp: 0, // Will be set by engine
-            bid_price: data.bid_price,
-            bid_volume: data.bid_volume,
-            ask_price: data.ask_price,
-            ask_volume: data.ask_volume,
-        })
+        .map(OrderBookSnapshot::from)
         .collect();
 
     // Calculate OBI using new engine
-    let results = obi_engine::calculate_obi_batch(&snapshots);
+    let results = engine.calculate_obi_batch(&snapshots, &obi_config(tick_size))?;
     
     let gpu_latency_ms = start.elapsed().as_secs_f64() * 1000.0;
 
     // Convert results to TypeScript format
     let ts_results = results
         .iter()
-        .map(|r| ObiResult {
-            imbalance: r.obi,
-            signal: r.signal.clone(),
-            gpu_latency_ms,
-        })
+        .map(|r| ObiResult::from_engine(r, gpu_latency_ms))
         .collect();
 
     Ok(ts_results)
 }
 
-/// Evaluate single market entropy (synchronous helper)
+/// OFI point returned to TypeScript
+#[napi(object)]
+pub struct OfiPoint {
+    pub timestamp: i64,
+    pub ofi: f64,
+    pub bucket_start: i64,
+    pub bucket_ofi: f64,
+    pub validation_error: Option<String>, // Set for invalid snapshots in "flag" validation mode
+}
+
+impl From<&OfiResult> for OfiPoint {
+    fn from(r: &OfiResult) -> Self {
+        OfiPoint {
+            timestamp: r.timestamp as i64,
+            ofi: r.ofi,
+            bucket_start: r.bucket_start as i64,
+            bucket_ofi: r.bucket_ofi,
+            validation_error: r.validation.map(|e| e.to_string()),
+        }
+    }
+}
+
+/// Calculate Order Flow Imbalance from consecutive snapshots (exposed to TypeScript)
+/// `bucket_ms` of 0 (default) treats every tick as its own bucket
 #[napi]
-pub fn evaluate_market_entropy(imbalance: f64) -> String {
-    if imbalance > 0.3 {
-        "BUY_PRESSURE".to_string()
-    } else if imbalance < -0.3 {
-        "SELL_PRESSURE".to_string()
-    } else {
-        "NEUTRAL".to_string()
+pub async fn calculate_ofi_batch(market_data: Vec<OrderBookData>, bucket_ms: Option<i64>) -> Result<Vec<OfiPoint>> {
+    let engine = default_engine()?;
+    ofi_batch(&engine, &market_data, bucket_ms)
+}
+
+fn ofi_batch(engine: &PhysicsEngine, market_data: &[OrderBookData], bucket_ms: Option<i64>) -> Result<Vec<OfiPoint>> {
+    let snapshots: Vec<OrderBookSnapshot> = market_data
+        .iter()
+        .map(OrderBookSnapshot::from)
+        .collect();
+
+    Ok(engine
+        .calculate_ofi_batch(&snapshots, &ofi_config(bucket_ms))
+        .iter()
+        .map(OfiPoint::from)
+        .collect())
+}
+
+fn ofi_config(bucket_ms: Option<i64>) -> OfiConfig {
+    OfiConfig {
+        bucket_ms: bucket_ms.unwrap_or(0).max(0) as u64,
+    }
+}
+
+/// Trade print from TypeScript
+#[napi(object)]
+pub struct TradeData {
+    pub price: f64,
+    pub volume: f64,
+    pub timestamp: Option<i64>, // Epoch millis (optional, 0 if omitted)
+    pub symbol: Option<String>,
+    pub venue: Option<String>,
+}
+
+impl From<&TradeData> for Trade {
+    fn from(data: &TradeData) -> Self {
+        Trade {
+            timestamp: data.timestamp.unwrap_or(0).max(0) as u64,
+            price: data.price,
+            volume: data.volume,
+            symbol: data.symbol.clone(),
+            venue: data.venue.clone(),
+        }
+    }
+}
+
+/// Trade classification options (all optional)
+#[napi(object)]
+pub struct TradeClassifierOptions {
+    pub method: Option<String>,     // "tick" | "lee-ready" (default) | "bvc"
+    pub quote_lag_ms: Option<i64>,  // Lee-Ready quote delay (default 0)
+    pub bar_ms: Option<i64>,        // BVC bar width (default 60000)
+    pub bucket_ms: Option<i64>,     // Signed-volume bucket width (default 0 = per trade)
+}
+
+/// Trade with its aggressor ("BUY" | "SELL" | "UNKNOWN") and signed volume
+#[napi(object)]
+pub struct ClassifiedTradeData {
+    pub timestamp: i64,
+    pub price: f64,
+    pub volume: f64,
+    pub aggressor: String,
+    pub buy_volume: f64,
+    pub sell_volume: f64,
+    pub signed_volume: f64,
+}
+
+/// Bucketed signed volume
+#[napi(object)]
+pub struct TradeFlowBucketData {
+    pub bucket_start: i64,
+    pub trades: u32,
+    pub buy_volume: f64,
+    pub sell_volume: f64,
+    pub signed_volume: f64,
+    pub trade_imbalance: f64,
+}
+
+#[napi(object)]
+pub struct TradeFlowResult {
+    pub trades: Vec<ClassifiedTradeData>,
+    pub buckets: Vec<TradeFlowBucketData>,
+}
+
+/// Classify trades by aggressor against optional quotes (exposed to TypeScript)
+#[napi]
+pub fn classify_trades(
+    trades: Vec<TradeData>,
+    quotes: Option<Vec<OrderBookData>>,
+    options: Option<TradeClassifierOptions>,
+) -> Result<TradeFlowResult> {
+    let engine = default_engine()?;
+    trade_flow(&engine, &trades, quotes.as_deref(), options)
+}
+
+/// Classifier config and signed-volume bucket width from optional TypeScript options
+fn classifier_config(options: Option<&TradeClassifierOptions>) -> Result<(TradeClassifierConfig, u64)> {
+    let defaults = TradeClassifierConfig::default();
+    Ok(match options {
+        Some(o) => (
+            TradeClassifierConfig {
+                method: match o.method.as_deref() {
+                    Some(name) => ClassificationMethod::parse(name)?,
+                    None => defaults.method,
+                },
+                quote_lag_ms: o.quote_lag_ms.map_or(defaults.quote_lag_ms, |v| v.max(0) as u64),
+                bar_ms: o.bar_ms.map_or(defaults.bar_ms, |v| v.max(0) as u64),
+            },
+            o.bucket_ms.unwrap_or(0).max(0) as u64,
+        ),
+        None => (defaults, 0),
+    })
+}
+
+fn tape(trades: &[TradeData], quotes: Option<&[OrderBookData]>) -> (Vec<Trade>, Vec<OrderBookSnapshot>) {
+    (
+        trades.iter().map(Trade::from).collect(),
+        quotes.unwrap_or(&[]).iter().map(OrderBookSnapshot::from).collect(),
+    )
+}
+
+fn trade_flow(
+    engine: &PhysicsEngine,
+    trades: &[TradeData],
+    quotes: Option<&[OrderBookData]>,
+    options: Option<TradeClassifierOptions>,
+) -> Result<TradeFlowResult> {
+    let (config, bucket_ms) = classifier_config(options.as_ref())?;
+    let (trades, quotes) = tape(trades, quotes);
+    let flow = engine.calculate_trade_flow(&trades, &quotes, &config, bucket_ms)?;
+
+    Ok(TradeFlowResult {
+        trades: flow
+            .trades
+            .iter()
+            .map(|t| ClassifiedTradeData {
+                timestamp: t.timestamp as i64,
+                price: t.price,
+                volume: t.volume,
+                aggressor: t.aggressor.as_str().to_string(),
+                buy_volume: t.buy_volume,
+                sell_volume: t.sell_volume,
+                signed_volume: t.signed_volume,
+            })
+            .collect(),
+        buckets: flow
+            .buckets
+            .iter()
+            .map(|b| TradeFlowBucketData {
+                bucket_start: b.bucket_start as i64,
+                trades: b.trades as u32,
+                buy_volume: b.buy_volume,
+                sell_volume: b.sell_volume,
+                signed_volume: b.signed_volume,
+                trade_imbalance: b.trade_imbalance,
+            })
+            .collect(),
+    })
+}
+
+/// VPIN options (all optional)
+#[napi(object)]
+pub struct VpinOptions {
+    pub bucket_volume: Option<f64>, // Volume per bucket (default: tape volume / (window + cdfWindow))
+    pub window: Option<u32>,        // Buckets per VPIN value (default 50)
+    pub cdf_window: Option<u32>,    // VPIN history for the CDF fit (default 250)
+    pub medium: Option<f64>,        // CDF alert levels (default 0.8 / 0.9 / 0.99)
+    pub high: Option<f64>,
+    pub critical: Option<f64>,
+}
+
+impl VpinOptions {
+    fn into_config(self) -> VpinConfig {
+        let defaults = VpinConfig::default();
+        VpinConfig {
+            bucket_volume: self.bucket_volume.unwrap_or(defaults.bucket_volume),
+            window: self.window.map_or(defaults.window, |w| w as usize),
+            cdf_window: self.cdf_window.map_or(defaults.cdf_window, |w| w as usize),
+            medium: self.medium.unwrap_or(defaults.medium),
+            high: self.high.unwrap_or(defaults.high),
+            critical: self.critical.unwrap_or(defaults.critical),
+        }
+    }
+}
+
+/// Completed VPIN bucket; `alert` is 'LOW' | 'MEDIUM' | 'HIGH' | 'CRITICAL'
+#[napi(object)]
+pub struct VpinPointData {
+    pub bucket: u32,
+    pub start_timestamp: i64,
+    pub timestamp: i64,
+    pub buy_volume: f64,
+    pub sell_volume: f64,
+    pub vpin: Option<f64>,
+    pub cdf: Option<f64>,
+    pub alert: String,
+}
+
+/// VPIN flow toxicity over a trade tape (exposed to TypeScript).
+/// Needs no `init_physics_engine`: VPIN does not touch the compute backend.
+#[napi]
+pub fn calculate_vpin(
+    trades: Vec<TradeData>,
+    quotes: Option<Vec<OrderBookData>>,
+    classifier: Option<TradeClassifierOptions>,
+    options: Option<VpinOptions>,
+) -> Result<Vec<VpinPointData>> {
+    let (classifier, _) = classifier_config(classifier.as_ref())?;
+    let config = options.map_or_else(VpinConfig::default, VpinOptions::into_config);
+    let (trades, quotes) = tape(&trades, quotes.as_deref());
+    Ok(vpin_points(&vpin::tape_vpin(&trades, &quotes, &classifier, &config)?))
+}
+
+/// `calculate_vpin` under the name Omega calls
+#[napi(namespace = "tda", js_name = "calculate_vpin")]
+pub fn tda_calculate_vpin(
+    trades: Vec<TradeData>,
+    quotes: Option<Vec<OrderBookData>>,
+    classifier: Option<TradeClassifierOptions>,
+    options: Option<VpinOptions>,
+) -> Result<Vec<VpinPointData>> {
+    calculate_vpin(trades, quotes, classifier, options)
+}
+
+fn vpin_points(points: &[VpinPoint]) -> Vec<VpinPointData> {
+    points
+        .iter()
+        .map(|p| VpinPointData {
+            bucket: p.bucket as u32,
+            start_timestamp: p.start_timestamp as i64,
+            timestamp: p.timestamp as i64,
+            buy_volume: p.buy_volume,
+            sell_volume: p.sell_volume,
+            vpin: p.vpin,
+            cdf: p.cdf,
+            alert: p.alert.as_str().to_string(),
+        })
+        .collect()
+}
+
+/// Columnar OBI result (one entry per input row)
+#[napi(object)]
+pub struct ObiColumnsResult {
+    pub obi: Float64Array,
+    pub entropy: Float64Array,
+    pub relative_spread: Float64Array,
+    /// -1 = SELL_PRESSURE, 0 = NEUTRAL, 1 = BUY_PRESSURE
+    pub signal: Int8Array,
+}
+
+/// Columnar OBI off the JS thread; the typed arrays are copied once on the JS thread
+pub struct ObiColumnsTask {
+    engine: Arc<PhysicsEngine>,
+    columns: [Vec<f64>; 4], // bid_price, bid_volume, ask_price, ask_volume
+}
+
+impl ObiColumnsTask {
+    fn new(
+        engine: Arc<PhysicsEngine>,
+        bid_price: &[f64],
+        bid_volume: &[f64],
+        ask_price: &[f64],
+        ask_volume: &[f64],
+    ) -> AsyncTask<ObiColumnsTask> {
+        AsyncTask::new(ObiColumnsTask {
+            engine,
+            columns: [bid_price.to_vec(), bid_volume.to_vec(), ask_price.to_vec(), ask_volume.to_vec()],
+        })
+    }
+}
+
+impl Task for ObiColumnsTask {
+    type Output = ObiColumns;
+    type JsValue = ObiColumnsResult;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let [bid_price, bid_volume, ask_price, ask_volume] = &self.columns;
+        let input = OrderBookColumns::new(bid_price, bid_volume, ask_price, ask_volume)?;
+        Ok(self.engine.calculate_obi_columns(input)?)
+    }
+
+    fn resolve(&mut self, _env: Env, columns: Self::Output) -> Result<Self::JsValue> {
+        Ok(ObiColumnsResult::from(columns))
+    }
+}
+
+impl From<ObiColumns> for ObiColumnsResult {
+    fn from(columns: ObiColumns) -> Self {
+        ObiColumnsResult {
+            obi: Float64Array::new(columns.obi),
+            entropy: Float64Array::new(columns.entropy),
+            relative_spread: Float64Array::new(columns.relative_spread),
+            signal: Int8Array::new(columns.signal.into_iter().map(|s| s as i8).collect()),
+        }
+    }
+}
+
+/// Struct-of-arrays OBI on the default engine (float mode only, off the JS thread)
+#[napi]
+pub fn calculate_obi_columns(
+    bid_price: Float64Array,
+    bid_volume: Float64Array,
+    ask_price: Float64Array,
+    ask_volume: Float64Array,
+) -> Result<AsyncTask<ObiColumnsTask>> {
+    Ok(ObiColumnsTask::new(default_engine()?, &bid_price, &bid_volume, &ask_price, &ask_volume))
+}
+
+/// Single price level from TypeScript
+#[napi(object)]
+pub struct PriceLevelData {
+    pub price: f64,
+    pub volume: f64,
+}
+
+/// Multi-level Order Book Data from TypeScript (best level first)
+#[napi(object)]
+pub struct DepthBookData {
+    pub bids: Vec<PriceLevelData>,
+    pub asks: Vec<PriceLevelData>,
+    pub timestamp: Option<i64>,
+    pub last_trade_price: Option<f64>,
+    pub symbol: Option<String>,
+    pub venue: Option<String>,
+}
+
+impl From<&DepthBookData> for DepthSnapshot {
+    fn from(data: &DepthBookData) -> Self {
+        let to_levels = |levels: &[PriceLevelData]| -> Vec<PriceLevel> {
+            levels
+                .iter()
+                .map(|level| PriceLevel {
+                    price: level.price,
+                    volume: level.volume,
+                })
+                .collect()
+        };
+
+        DepthSnapshot {
+            timestamp: data.timestamp.unwrap_or(0).max(0) as u64,
+            bids: to_levels(&data.bids),
+            asks: to_levels(&data.asks),
+            last_trade_price: data.last_trade_price,
+            symbol: data.symbol.clone(),
+            venue: data.venue.clone(),
+        }
+    }
+}
+
+/// Depth weighting from TypeScript, falling back to the engine defaults
+fn depth_config(levels: Option<u32>, decay: Option<f64>) -> DepthObiConfig {
+    let defaults = DepthObiConfig::default();
+    DepthObiConfig {
+        levels: levels.map(|l| l as usize).unwrap_or(defaults.levels),
+        decay: decay.unwrap_or(defaults.decay),
+    }
+}
+
+/// Calculate depth-weighted Order Book Imbalance (exposed to TypeScript)
+/// `levels` defaults to 10, `decay` (per bps from mid) to 0.1, `tick_size` to 0.01
+#[napi]
+pub async fn calculate_depth_obi_batch(
+    market_data: Vec<DepthBookData>,
+    levels: Option<u32>,
+    decay: Option<f64>,
+    tick_size: Option<f64>,
+) -> Result<Vec<ObiResult>> {
+    let engine = default_engine()?;
+    let start = std::time::Instant::now();
+
+    let snapshots: Vec<DepthSnapshot> = market_data
+        .iter()
+        .map(DepthSnapshot::from)
+        .collect();
+
+    let results = engine.calculate_depth_obi_batch(
+        &snapshots,
+        &obi_config(tick_size),
+        &depth_config(levels, decay),
+    )?;
+
+    let gpu_latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    Ok(results
+        .iter()
+        .map(|r| ObiResult::from_engine(r, gpu_latency_ms))
+        .collect())
+}
+
+/// Input of an engine-instance OBI batch
+enum ObiBatchInput {
+    TopOfBook(Vec<OrderBookSnapshot>),
+    Depth(Vec<DepthSnapshot>, DepthObiConfig),
+}
+
+/// OBI batch computed on the libuv thread pool for a `PhysicsEngine` instance
+pub struct ObiBatchTask {
+    engine: Arc<PhysicsEngine>,
+    input: ObiBatchInput,
+    config: ObiConfig,
+}
+
+impl Task for ObiBatchTask {
+    type Output = (Vec<RustObiResult>, f64);
+    type JsValue = Vec<ObiResult>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let start = std::time::Instant::now();
+
+        let results = match &self.input {
+            ObiBatchInput::TopOfBook(snapshots) => self.engine.calculate_obi_batch(snapshots, &self.config)?,
+            ObiBatchInput::Depth(snapshots, depth) => {
+                self.engine.calculate_depth_obi_batch(snapshots, &self.config, depth)?
+            }
+        };
+
+        Ok((results, start.elapsed().as_secs_f64() * 1000.0))
+    }
+
+    fn resolve(&mut self, _env: Env, (results, latency_ms): Self::Output) -> Result<Self::JsValue> {
+        Ok(results
+            .iter()
+            .map(|r| ObiResult::from_engine(r, latency_ms))
+            .collect())
+    }
+}
+
+/// Per-instrument summary returned to TypeScript
+#[napi(object)]
+pub struct SymbolStatsData {
+    pub count: u32,
+    pub mean_obi: f64,
+    pub std_obi: f64,
+    pub min_obi: f64,
+    pub max_obi: f64,
+    pub mean_spread_bps: f64,
+    pub buy_signals: u32,
+    pub sell_signals: u32,
+    pub neutral_signals: u32,
+    pub first_timestamp: i64,
+    pub last_timestamp: i64,
+}
+
+/// OBI results for one symbol/venue
+#[napi(object)]
+pub struct ObiGroupResult {
+    pub symbol: Option<String>,
+    pub venue: Option<String>,
+    pub buy_threshold: f64,
+    pub sell_threshold: f64,
+    pub indices: Vec<u32>, // Input index of each result
+    pub results: Vec<ObiResult>,
+    pub stats: SymbolStatsData,
+}
+
+impl ObiGroupResult {
+    fn from_engine(group: ObiGroup, latency_ms: f64) -> Self {
+        let stats = &group.stats;
+
+        ObiGroupResult {
+            stats: SymbolStatsData {
+                count: stats.count as u32,
+                mean_obi: stats.mean_obi,
+                std_obi: stats.std_obi,
+                min_obi: stats.min_obi,
+                max_obi: stats.max_obi,
+                mean_spread_bps: stats.mean_spread_bps,
+                buy_signals: stats.buy_signals as u32,
+                sell_signals: stats.sell_signals as u32,
+                neutral_signals: stats.neutral_signals as u32,
+                first_timestamp: stats.first_timestamp as i64,
+                last_timestamp: stats.last_timestamp as i64,
+            },
+            buy_threshold: group.thresholds.buy,
+            sell_threshold: group.thresholds.sell,
+            indices: group.indices.iter().map(|&i| i as u32).collect(),
+            results: group
+                .results
+                .iter()
+                .map(|r| ObiResult::from_engine(r, latency_ms))
+                .collect(),
+            symbol: group.key.symbol,
+            venue: group.key.venue,
+        }
+    }
+}
+
+/// Grouped OBI batch computed on the libuv thread pool
+pub struct GroupedObiBatchTask {
+    engine: Arc<PhysicsEngine>,
+    snapshots: Vec<OrderBookSnapshot>,
+    config: ObiConfig,
+}
+
+impl Task for GroupedObiBatchTask {
+    type Output = (Vec<ObiGroup>, f64);
+    type JsValue = Vec<ObiGroupResult>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let start = std::time::Instant::now();
+        let groups = self.engine.calculate_grouped_obi_batch(&self.snapshots, &self.config)?;
+
+        Ok((groups, start.elapsed().as_secs_f64() * 1000.0))
+    }
+
+    fn resolve(&mut self, _env: Env, (groups, latency_ms): Self::Output) -> Result<Self::JsValue> {
+        Ok(groups
+            .into_iter()
+            .map(|group| ObiGroupResult::from_engine(group, latency_ms))
+            .collect())
+    }
+}
+
+/// OBI for a mixed batch on the default engine, grouped by symbol/venue with per-instrument thresholds
+#[napi]
+pub fn calculate_grouped_obi_batch(
+    market_data: Vec<OrderBookData>,
+    tick_size: Option<f64>,
+) -> Result<AsyncTask<GroupedObiBatchTask>> {
+    Ok(AsyncTask::new(GroupedObiBatchTask {
+        engine: default_engine()?,
+        snapshots: market_data.iter().map(OrderBookSnapshot::from).collect(),
+        config: obi_config(tick_size),
+    }))
+}
+
+/// Venue share of a consolidated level
+#[napi(object)]
+pub struct VenueVolumeData {
+    pub venue: String,
+    pub volume: f64,
+}
+
+/// Consolidated price level with venue attribution
+#[napi(object)]
+pub struct ConsolidatedLevelData {
+    pub price: f64,
+    pub volume: f64,
+    pub venues: Vec<VenueVolumeData>,
+}
+
+/// Cross-venue arbitrage candidate: buy on `buy_venue`, sell on `sell_venue`
+#[napi(object)]
+pub struct ArbitrageCandidateData {
+    pub buy_venue: String,
+    pub sell_venue: String,
+    pub ask: f64,
+    pub bid: f64,
+    pub edge: f64,
+    pub edge_bps: f64,
+    pub volume: f64,
+}
+
+/// Consolidated (NBBO) book with aggregate OBI
+#[napi(object)]
+pub struct ConsolidatedObiResult {
+    pub timestamp: i64,
+    pub symbol: Option<String>,
+    pub venue_count: u32,
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+    pub bids: Vec<ConsolidatedLevelData>,
+    pub asks: Vec<ConsolidatedLevelData>,
+    pub obi: ObiResult,
+    pub market_state: String, // "NORMAL" | "LOCKED" | "CROSSED"
+    pub arbitrage: Vec<ArbitrageCandidateData>,
+}
+
+impl ConsolidatedObiResult {
+    fn from_engine(r: ConsolidatedResult, latency_ms: f64) -> Self {
+        let levels = |levels: Vec<ConsolidatedLevel>| -> Vec<ConsolidatedLevelData> {
+            levels
+                .into_iter()
+                .map(|level| ConsolidatedLevelData {
+                    price: level.price,
+                    volume: level.volume,
+                    venues: level
+                        .venues
+                        .into_iter()
+                        .map(|v| VenueVolumeData {
+                            venue: v.venue,
+                            volume: v.volume,
+                        })
+                        .collect(),
+                })
+                .collect()
+        };
+
+        ConsolidatedObiResult {
+            timestamp: r.book.timestamp as i64,
+            venue_count: r.book.venue_count as u32,
+            best_bid: r.book.bids.first().map(|l| l.price),
+            best_ask: r.book.asks.first().map(|l| l.price),
+            obi: ObiResult::from_engine(&r.obi, latency_ms),
+            market_state: r.state.as_str().to_string(),
+            arbitrage: r
+                .arbitrage
+                .into_iter()
+                .map(|a| ArbitrageCandidateData {
+                    buy_venue: a.buy_venue,
+                    sell_venue: a.sell_venue,
+                    ask: a.ask,
+                    bid: a.bid,
+                    edge: a.edge,
+                    edge_bps: a.edge_bps,
+                    volume: a.volume,
+                })
+                .collect(),
+            symbol: r.book.symbol,
+            bids: levels(r.book.bids),
+            asks: levels(r.book.asks),
+        }
+    }
+}
+
+/// Snapshot validation options from TypeScript
+#[napi(object)]
+pub struct ValidationOptions {
+    pub mode: Option<String>,     // "strict" (default) | "skip" | "flag"
+    pub max_age_ms: Option<i64>,  // Snapshots older than this are Stale
+    pub now_ms: Option<i64>,      // Age reference (default: newest timestamp in the batch)
+}
+
+impl ValidationOptions {
+    fn into_config(self) -> Result<ValidationConfig> {
+        Ok(ValidationConfig {
+            mode: match self.mode {
+                Some(mode) => ValidationMode::parse(&mode)?,
+                None => ValidationMode::default(),
+            },
+            max_age_ms: self.max_age_ms.map(|ms| ms.max(0) as u64),
+            now_ms: self.now_ms.map(|ms| ms.max(0) as u64),
+        })
+    }
+}
+
+/// Validation counters for one batch
+#[napi(object)]
+pub struct ValidationCountsData {
+    pub total: u32,
+    pub valid: u32,
+    pub crossed: u32,
+    pub negative_volume: u32,
+    pub non_finite: u32,
+    pub non_positive_price: u32,
+    pub stale: u32,
+}
+
+impl From<&ValidationCounts> for ValidationCountsData {
+    fn from(c: &ValidationCounts) -> Self {
+        ValidationCountsData {
+            total: c.total as u32,
+            valid: c.valid as u32,
+            crossed: c.crossed as u32,
+            negative_volume: c.negative_volume as u32,
+            non_finite: c.non_finite as u32,
+            non_positive_price: c.non_positive_price as u32,
+            stale: c.stale as u32,
+        }
     }
 }
 
+/// Validated OBI batch returned to TypeScript
+#[napi(object)]
+pub struct ValidatedObiBatchResult {
+    pub results: Vec<ObiResult>,
+    pub indices: Vec<u32>, // Input index of each result
+    pub counts: ValidationCountsData,
+}
+
+/// Validated OBI batch computed on the libuv thread pool
+pub struct ValidatedObiBatchTask {
+    engine: Arc<PhysicsEngine>,
+    input: ObiBatchInput,
+    config: ObiConfig,
+    validation: ValidationConfig,
+}
+
+impl Task for ValidatedObiBatchTask {
+    type Output = (ValidatedObiBatch, f64);
+    type JsValue = ValidatedObiBatchResult;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let start = std::time::Instant::now();
+        let batch = match &self.input {
+            ObiBatchInput::TopOfBook(snapshots) => {
+                self.engine
+                    .calculate_obi_batch_validated(snapshots, &self.config, &self.validation)?
+            }
+            ObiBatchInput::Depth(snapshots, depth) => {
+                self.engine
+                    .calculate_depth_obi_batch_validated(snapshots, &self.config, depth, &self.validation)?
+            }
+        };
+
+        Ok((batch, start.elapsed().as_secs_f64() * 1000.0))
+    }
+
+    fn resolve(&mut self, _env: Env, (batch, latency_ms): Self::Output) -> Result<Self::JsValue> {
+        Ok(ValidatedObiBatchResult {
+            results: batch
+                .results
+                .iter()
+                .map(|r| ObiResult::from_engine(r, latency_ms))
+                .collect(),
+            indices: batch.indices.iter().map(|&i| i as u32).collect(),
+            counts: ValidationCountsData::from(&batch.counts),
+        })
+    }
+}
+
+/// Validated OFI series returned to TypeScript
+#[napi(object)]
+pub struct ValidatedOfiBatchResult {
+    pub results: Vec<OfiPoint>,
+    pub indices: Vec<u32>, // Input index of each result
+    pub counts: ValidationCountsData,
+}
+
+/// Validated columnar OBI returned to TypeScript
+#[napi(object)]
+pub struct ValidatedObiColumnsResult {
+    pub columns: ObiColumnsResult, // Kept rows only
+    pub indices: Uint32Array,      // Input row of each output row
+    pub counts: ValidationCountsData,
+    pub flagged_rows: Vec<u32>,      // Invalid input rows kept in "flag" mode
+    pub flagged_errors: Vec<String>, // Error of each flagged row
+}
+
+/// Validated columnar OBI off the JS thread
+pub struct ValidatedObiColumnsTask {
+    engine: Arc<PhysicsEngine>,
+    columns: [Vec<f64>; 4], // bid_price, bid_volume, ask_price, ask_volume
+    mode: ValidationMode,
+}
+
+impl Task for ValidatedObiColumnsTask {
+    type Output = ValidatedObiColumns;
+    type JsValue = ValidatedObiColumnsResult;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let [bid_price, bid_volume, ask_price, ask_volume] = &self.columns;
+        let input = OrderBookColumns::new(bid_price, bid_volume, ask_price, ask_volume)?;
+        Ok(self.engine.calculate_obi_columns_validated(input, self.mode)?)
+    }
+
+    fn resolve(&mut self, _env: Env, batch: Self::Output) -> Result<Self::JsValue> {
+        Ok(ValidatedObiColumnsResult {
+            columns: ObiColumnsResult::from(batch.columns),
+            indices: Uint32Array::new(batch.indices.iter().map(|&i| i as u32).collect()),
+            counts: ValidationCountsData::from(&batch.counts),
+            flagged_rows: batch.flagged.iter().map(|&(i, _)| i as u32).collect(),
+            flagged_errors: batch.flagged.iter().map(|(_, e)| e.to_string()).collect(),
+        })
+    }
+}
+
+fn validation_config(validation: Option<ValidationOptions>) -> Result<ValidationConfig> {
+    match validation {
+        Some(options) => options.into_config(),
+        None => Ok(ValidationConfig::default()),
+    }
+}
+
+/// Physics Engine instance with its own config, thread pool and backend
+#[napi(js_name = "PhysicsEngine")]
+pub struct PhysicsEngineInstance {
+    inner: EngineHandle,
+    regime: Option<HmmFilter>, // Loaded regime model with its live belief
+}
+
+#[napi]
+impl PhysicsEngineInstance {
+    #[napi(constructor)]
+    pub fn new(options: Option<EngineOptions>) -> Self {
+        let config = options.map(EngineOptions::into_config).unwrap_or_default();
+
+        PhysicsEngineInstance {
+            inner: EngineHandle::new(config),
+            regime: None,
+        }
+    }
+
+    /// Bring up the backend and thread pool; returns the active backend name
+    #[napi]
+    pub fn init(&mut self) -> Result<String> {
+        let engine = self.inner.init()?;
+        Ok(engine.backend_name().to_string())
+    }
+
+    /// Active backend name; throws ENGINE_NOT_INITIALIZED before init()
+    #[napi]
+    pub fn backend(&self) -> Result<String> {
+        Ok(self.inner.engine()?.backend_name().to_string())
+    }
+
+    /// Mean manifold curvature on this engine's backend
+    #[napi]
+    pub fn calculate_manifold_curvature(&self, market_data: Vec<OrderBookData>) -> Result<f64> {
+        let snapshots: Vec<OrderBookSnapshot> = market_data
+            .iter()
+            .map(OrderBookSnapshot::from)
+            .collect();
+
+        let curvatures = self.inner.engine()?.calculate_curvature(&snapshots)?;
+        if curvatures.is_empty() {
+            Ok(0.0)
+        } else {
+            Ok(curvatures.iter().sum::<f64>() / curvatures.len() as f64)
+        }
+    }
+
+    #[napi]
+    pub fn calculate_obi_batch(
+        &self,
+        market_data: Vec<OrderBookData>,
+        tick_size: Option<f64>,
+    ) -> Result<AsyncTask<ObiBatchTask>> {
+        Ok(AsyncTask::new(ObiBatchTask {
+            engine: self.inner.engine()?,
+            input: ObiBatchInput::TopOfBook(market_data.iter().map(OrderBookSnapshot::from).collect()),
+            config: obi_config(tick_size),
+        }))
+    }
+
+    /// OBI for a mixed batch, grouped by symbol/venue with per-instrument thresholds
+    #[napi]
+    pub fn calculate_grouped_obi_batch(
+        &self,
+        market_data: Vec<OrderBookData>,
+        tick_size: Option<f64>,
+    ) -> Result<AsyncTask<GroupedObiBatchTask>> {
+        Ok(AsyncTask::new(GroupedObiBatchTask {
+            engine: self.inner.engine()?,
+            snapshots: market_data.iter().map(OrderBookSnapshot::from).collect(),
+            config: obi_config(tick_size),
+        }))
+    }
+
+    /// Consolidate one book per venue (each tagged with `venue`) into the NBBO book
+    #[napi]
+    pub fn calculate_consolidated_obi(
+        &self,
+        venue_books: Vec<DepthBookData>,
+        levels: Option<u32>,
+        decay: Option<f64>,
+        tick_size: Option<f64>,
+    ) -> Result<ConsolidatedObiResult> {
+        let engine = self.inner.engine()?;
+        let start = std::time::Instant::now();
+
+        let books: Vec<DepthSnapshot> = venue_books.iter().map(DepthSnapshot::from).collect();
+        let result = engine.calculate_consolidated_obi(&books, &obi_config(tick_size), &depth_config(levels, decay))?;
+
+        Ok(ConsolidatedObiResult::from_engine(result, start.elapsed().as_secs_f64() * 1000.0))
+    }
+
+    /// OBI with snapshot validation; "strict" mode rejects with INVALID_SNAPSHOT
+    #[napi]
+    pub fn calculate_validated_obi_batch(
+        &self,
+        market_data: Vec<OrderBookData>,
+        validation: Option<ValidationOptions>,
+        tick_size: Option<f64>,
+    ) -> Result<AsyncTask<ValidatedObiBatchTask>> {
+        Ok(AsyncTask::new(ValidatedObiBatchTask {
+            engine: self.inner.engine()?,
+            input: ObiBatchInput::TopOfBook(market_data.iter().map(OrderBookSnapshot::from).collect()),
+            config: obi_config(tick_size),
+            validation: validation_config(validation)?,
+        }))
+    }
+
+    /// Depth OBI with snapshot validation of every level
+    #[napi]
+    pub fn calculate_validated_depth_obi_batch(
+        &self,
+        market_data: Vec<DepthBookData>,
+        validation: Option<ValidationOptions>,
+        levels: Option<u32>,
+        decay: Option<f64>,
+        tick_size: Option<f64>,
+    ) -> Result<AsyncTask<ValidatedObiBatchTask>> {
+        Ok(AsyncTask::new(ValidatedObiBatchTask {
+            engine: self.inner.engine()?,
+            input: ObiBatchInput::Depth(
+                market_data.iter().map(DepthSnapshot::from).collect(),
+                depth_config(levels, decay),
+            ),
+            config: obi_config(tick_size),
+            validation: validation_config(validation)?,
+        }))
+    }
+
+    /// OFI with snapshot validation; skipped snapshots drop out of the tick pairing
+    #[napi]
+    pub fn calculate_validated_ofi_batch(
+        &self,
+        market_data: Vec<OrderBookData>,
+        validation: Option<ValidationOptions>,
+        bucket_ms: Option<i64>,
+    ) -> Result<ValidatedOfiBatchResult> {
+        let snapshots: Vec<OrderBookSnapshot> = market_data.iter().map(OrderBookSnapshot::from).collect();
+        let batch = self.inner.engine()?.calculate_ofi_batch_validated(
+            &snapshots,
+            &ofi_config(bucket_ms),
+            &validation_config(validation)?,
+        )?;
+
+        Ok(ValidatedOfiBatchResult {
+            results: batch.results.iter().map(OfiPoint::from).collect(),
+            indices: batch.indices.iter().map(|&i| i as u32).collect(),
+            counts: ValidationCountsData::from(&batch.counts),
+        })
+    }
+
+    /// Columnar OBI with row validation ("strict" | "skip" | "flag"; rows carry no timestamps)
+    #[napi]
+    pub fn calculate_validated_obi_columns(
+        &self,
+        bid_price: Float64Array,
+        bid_volume: Float64Array,
+        ask_price: Float64Array,
+        ask_volume: Float64Array,
+        mode: Option<String>,
+    ) -> Result<AsyncTask<ValidatedObiColumnsTask>> {
+        Ok(AsyncTask::new(ValidatedObiColumnsTask {
+            engine: self.inner.engine()?,
+            columns: [bid_price.to_vec(), bid_volume.to_vec(), ask_price.to_vec(), ask_volume.to_vec()],
+            mode: match mode {
+                Some(mode) => ValidationMode::parse(&mode)?,
+                None => ValidationMode::default(),
+            },
+        }))
+    }
+
+    /// Struct-of-arrays OBI: four equal-length Float64Arrays in, typed arrays out (off the JS thread)
+    #[napi]
+    pub fn calculate_obi_columns(
+        &self,
+        bid_price: Float64Array,
+        bid_volume: Float64Array,
+        ask_price: Float64Array,
+        ask_volume: Float64Array,
+    ) -> Result<AsyncTask<ObiColumnsTask>> {
+        Ok(ObiColumnsTask::new(self.inner.engine()?, &bid_price, &bid_volume, &ask_price, &ask_volume))
+    }
+
+    #[napi]
+    pub fn calculate_depth_obi_batch(
+        &self,
+        market_data: Vec<DepthBookData>,
+        levels: Option<u32>,
+        decay: Option<f64>,
+        tick_size: Option<f64>,
+    ) -> Result<AsyncTask<ObiBatchTask>> {
+        Ok(AsyncTask::new(ObiBatchTask {
+            engine: self.inner.engine()?,
+            input: ObiBatchInput::Depth(
+                market_data.iter().map(DepthSnapshot::from).collect(),
+                depth_config(levels, decay),
+            ),
+            config: obi_config(tick_size),
+        }))
+    }
+
+    #[napi]
+    pub fn calculate_ofi_batch(&self, market_data: Vec<OrderBookData>, bucket_ms: Option<i64>) -> Result<Vec<OfiPoint>> {
+        let engine = self.inner.engine()?;
+        ofi_batch(&engine, &market_data, bucket_ms)
+    }
+
+    #[napi]
+    pub fn classify_trades(
+        &self,
+        trades: Vec<TradeData>,
+        quotes: Option<Vec<OrderBookData>>,
+        options: Option<TradeClassifierOptions>,
+    ) -> Result<TradeFlowResult> {
+        let engine = self.inner.engine()?;
+        trade_flow(&engine, &trades, quotes.as_deref(), options)
+    }
+
+    /// Load a regime model from `fitRegimeModel` JSON; returns its state labels
+    #[napi]
+    pub fn load_regime_model(&mut self, model: String) -> Result<Vec<String>> {
+        let model = GaussianHmm::from_json(&model)?;
+        let labels = model.labels.clone();
+        self.regime = Some(HmmFilter::new(model));
+        Ok(labels)
+    }
+
+    /// Online filtering step of the loaded regime model
+    #[napi]
+    pub fn filter_regime(&mut self, features: Vec<f64>) -> Result<RegimeState> {
+        let filter = self
+            .regime
+            .as_mut()
+            .ok_or_else(|| EngineError::InvalidConfig("no regime model loaded".to_string()))?;
+        let probabilities = filter.step(&features)?;
+        let state = (0..probabilities.len())
+            .max_by(|&a, &b| probabilities[a].total_cmp(&probabilities[b]))
+            .unwrap_or(0);
+
+        Ok(RegimeState {
+            state: state as u32,
+            label: filter.model().labels[state].clone(),
+            probabilities,
+        })
+    }
+
+    /// Forget the filtered belief (the model stays loaded)
+    #[napi]
+    pub fn reset_regime(&mut self) {
+        if let Some(filter) = self.regime.as_mut() {
+            filter.reset();
+        }
+    }
+
+    #[napi]
+    pub fn calculate_vpin(
+        &self,
+        trades: Vec<TradeData>,
+        quotes: Option<Vec<OrderBookData>>,
+        classifier: Option<TradeClassifierOptions>,
+        options: Option<VpinOptions>,
+    ) -> Result<Vec<VpinPointData>> {
+        calculate_vpin(trades, quotes, classifier, options)
+    }
+}
+
+/// Streaming OBI tick returned to TypeScript
+#[napi(object)]
+pub struct StreamingObiTick {
+    pub imbalance: f64,
+    pub ewma_imbalance: f64,
+    pub rolling_mean: f64,
+    pub rolling_std: f64,
+    pub z_score: f64,
+    pub persistence: u32,
+    pub signal: String,
+}
+
+/// Stateful per-symbol OBI stream (TypeScript pushes ticks, Rust keeps history)
+#[napi(js_name = "StreamingObiEngine")]
+pub struct StreamingObi {
+    inner: StreamingObiEngine,
+}
+
+#[napi]
+impl StreamingObi {
+    /// `ewma_alpha` defaults to 0.2, `window` to 100 ticks
+    #[napi(constructor)]
+    pub fn new(ewma_alpha: Option<f64>, window: Option<u32>) -> Self {
+        let defaults = StreamingObiConfig::default();
+        StreamingObi {
+            inner: StreamingObiEngine::new(StreamingObiConfig {
+                ewma_alpha: ewma_alpha.unwrap_or(defaults.ewma_alpha),
+                window: window.map(|w| w as usize).unwrap_or(defaults.window),
+                ..defaults
+            }),
+        }
+    }
+
+    /// Push one order book tick for `symbol`. `timestamp` (epoch millis) is kept for
+    /// existing callers and overrides `tick.timestamp` when given.
+    #[napi]
+    pub fn push(&mut self, symbol: String, tick: OrderBookData, timestamp: Option<i64>) -> Result<StreamingObiTick> {
+        let mut snapshot = OrderBookSnapshot::from(&tick);
+        if let Some(timestamp) = timestamp {
+            snapshot.timestamp = timestamp.max(0) as u64;
+        }
+        let state = self.inner.push(&symbol, &snapshot)?;
+
+        Ok(StreamingObiTick {
+            imbalance: state.obi,
+            ewma_imbalance: state.ewma_obi,
+            rolling_mean: state.rolling_mean,
+            rolling_std: state.rolling_std,
+            z_score: state.z_score,
+            persistence: state.persistence,
+            signal: state.signal,
+        })
+    }
+
+    /// Reset one symbol, or every symbol when omitted
+    #[napi]
+    pub fn reset(&mut self, symbol: Option<String>) {
+        self.inner.reset(symbol.as_deref());
+    }
+
+    /// Symbols with live rolling state
+    #[napi]
+    pub fn symbols(&self) -> Vec<String> {
+        self.inner.symbols()
+    }
+}
+
+/// Entropy of a volume distribution (e.g. one book's depth levels)
+#[napi(object)]
+pub struct VolumeEntropy {
+    pub shannon: f64,     // Bits
+    pub renyi: f64,       // Bits, order `alpha`
+    pub max_entropy: f64, // log2(non-empty levels): shannon / max_entropy is the evenness
+}
+
+/// Shannon and Rényi entropy of raw volumes; `alpha` defaults to 2
+#[napi]
+pub fn calculate_volume_entropy(volumes: Vec<f64>, alpha: Option<f64>) -> VolumeEntropy {
+    let distribution = entropy::normalize(&volumes);
+    let levels = distribution.iter().filter(|&&p| p > 0.0).count();
+
+    VolumeEntropy {
+        shannon: entropy::shannon_entropy(&distribution),
+        renyi: entropy::renyi_entropy(&distribution, alpha.unwrap_or(ObiConfig::default().renyi_alpha)),
+        max_entropy: if levels > 0 { (levels as f64).log2() } else { 0.0 },
+    }
+}
+
+/// OHLC bar from TypeScript (timestamp = bar start, epoch millis)
+#[napi(object)]
+pub struct OhlcBarData {
+    pub timestamp: Option<i64>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+impl From<&OhlcBarData> for OhlcBar {
+    fn from(data: &OhlcBarData) -> Self {
+        OhlcBar {
+            timestamp: data.timestamp.unwrap_or(0).max(0) as u64,
+            open: data.open,
+            high: data.high,
+            low: data.low,
+            close: data.close,
+        }
+    }
+}
+
+/// Volatility options (all optional)
+#[napi(object)]
+pub struct VolatilityOptions {
+    pub bar_ms: Option<i64>,  // Mid-price bar width for snapshot input (default 60000)
+    pub window: Option<u32>,  // Bars per rolling estimate (default 20)
+    pub jump_z: Option<f64>,  // BNS jump threshold (default 3.09)
+}
+
+impl VolatilityOptions {
+    fn into_config(self) -> VolatilityConfig {
+        let defaults = VolatilityConfig::default();
+        VolatilityConfig {
+            bar_ms: self.bar_ms.map_or(defaults.bar_ms, |v| v.max(0) as u64),
+            window: self.window.map_or(defaults.window, |w| w as usize),
+            jump_z: self.jump_z.unwrap_or(defaults.jump_z),
+        }
+    }
+}
+
+/// Volatility estimate returned to TypeScript (range estimators are per-bar volatilities)
+#[napi(object)]
+pub struct VolatilityData {
+    pub timestamp: i64,
+    pub bars: u32,
+    pub parkinson: f64,
+    pub garman_klass: f64,
+    pub rogers_satchell: f64,
+    pub yang_zhang: Option<f64>,
+    pub realized_variance: f64,
+    pub bipower_variation: f64,
+    pub jump_variation: f64,
+    pub jump_z: Option<f64>,
+    pub jump: bool,
+}
+
+impl From<&VolatilityEstimate> for VolatilityData {
+    fn from(e: &VolatilityEstimate) -> Self {
+        VolatilityData {
+            timestamp: e.timestamp as i64,
+            bars: e.bars as u32,
+            parkinson: e.parkinson,
+            garman_klass: e.garman_klass,
+            rogers_satchell: e.rogers_satchell,
+            yang_zhang: e.yang_zhang,
+            realized_variance: e.realized_variance,
+            bipower_variation: e.bipower_variation,
+            jump_variation: e.jump_variation,
+            jump_z: e.jump_z,
+            jump: e.jump,
+        }
+    }
+}
+
+fn volatility_config(options: Option<VolatilityOptions>) -> VolatilityConfig {
+    options.map_or_else(VolatilityConfig::default, VolatilityOptions::into_config)
+}
+
+fn mid_series(market_data: &[OrderBookData], config: &VolatilityConfig) -> Result<BarSeries> {
+    let snapshots: Vec<OrderBookSnapshot> = market_data.iter().map(OrderBookSnapshot::from).collect();
+    Ok(BarSeries::from_snapshots(&snapshots, config.bar_ms)?)
+}
+
+fn bar_series(bars: &[OhlcBarData]) -> Result<BarSeries> {
+    let bars: Vec<OhlcBar> = bars.iter().map(OhlcBar::from).collect();
+    Ok(BarSeries::from_bars(&bars)?)
+}
+
+/// Volatility of the whole mid-price series (null when there is no valid mid)
+#[napi]
+pub fn calculate_volatility(market_data: Vec<OrderBookData>, options: Option<VolatilityOptions>) -> Result<Option<VolatilityData>> {
+    let config = volatility_config(options);
+    let series = mid_series(&market_data, &config)?;
+    Ok(volatility::estimate_volatility(&series, &config).as_ref().map(VolatilityData::from))
+}
+
+/// Trailing-window volatility of the mid-price series, one estimate per bar
+#[napi]
+pub fn calculate_rolling_volatility(market_data: Vec<OrderBookData>, options: Option<VolatilityOptions>) -> Result<Vec<VolatilityData>> {
+    let config = volatility_config(options);
+    let series = mid_series(&market_data, &config)?;
+    Ok(volatility::rolling_volatility(&series, &config)?.iter().map(VolatilityData::from).collect())
+}
+
+/// Volatility of pre-built OHLC bars (realised measures from close-to-close returns)
+#[napi]
+pub fn calculate_bar_volatility(bars: Vec<OhlcBarData>, options: Option<VolatilityOptions>) -> Result<Option<VolatilityData>> {
+    let config = volatility_config(options);
+    let series = bar_series(&bars)?;
+    Ok(volatility::estimate_volatility(&series, &config).as_ref().map(VolatilityData::from))
+}
+
+/// Trailing-window volatility of pre-built OHLC bars
+#[napi]
+pub fn calculate_rolling_bar_volatility(bars: Vec<OhlcBarData>, options: Option<VolatilityOptions>) -> Result<Vec<VolatilityData>> {
+    let config = volatility_config(options);
+    let series = bar_series(&bars)?;
+    Ok(volatility::rolling_volatility(&series, &config)?.iter().map(VolatilityData::from).collect())
+}
+
+/// Baum-Welch options (all optional)
+#[napi(object)]
+pub struct HmmOptions {
+    pub states: Option<u32>,       // Default 3
+    pub max_iter: Option<u32>,     // Default 100
+    pub tolerance: Option<f64>,    // Log-likelihood gain to stop at (default 1e-6)
+    pub min_variance: Option<f64>, // Per-feature variance floor (default 1e-6)
+    pub seed: Option<i64>,         // Initialisation seed (default 42)
+}
+
+impl HmmOptions {
+    fn into_config(self) -> HmmConfig {
+        let defaults = HmmConfig::default();
+        HmmConfig {
+            states: self.states.map_or(defaults.states, |k| k as usize),
+            max_iter: self.max_iter.map_or(defaults.max_iter, |n| n as usize),
+            tolerance: self.tolerance.unwrap_or(defaults.tolerance),
+            min_variance: self.min_variance.unwrap_or(defaults.min_variance),
+            seed: self.seed.map_or(defaults.seed, |s| s as u64),
+        }
+    }
+}
+
+/// Fitted regime model: `model` is the JSON to store and later load into an engine
+#[napi(object)]
+pub struct RegimeFitResult {
+    pub model: String,
+    pub labels: Vec<String>, // Label per state
+    pub path: Vec<u32>,      // Viterbi state per observation
+    pub log_likelihood: f64,
+    pub iterations: u32,
+    pub converged: bool,
+}
+
+/// Viterbi decode of a sequence
+#[napi(object)]
+pub struct RegimeDecodeResult {
+    pub path: Vec<u32>,
+    pub labels: Vec<String>, // Label per observation
+    pub log_likelihood: f64,
+}
+
+/// Filtered regime after one live feature vector
+#[napi(object)]
+pub struct RegimeState {
+    pub state: u32,
+    pub label: String,
+    pub probabilities: Vec<f64>, // P(state | features so far)
+}
+
+/// Fit a Gaussian HMM to feature vectors (e.g. [obi, spread_bps, volatility, curvature]).
+/// Feature names containing spread/curvature and obi/ofi/return drive the regime labels.
+#[napi]
+pub fn fit_regime_model(
+    observations: Vec<Vec<f64>>,
+    feature_names: Option<Vec<String>>,
+    options: Option<HmmOptions>,
+) -> Result<RegimeFitResult> {
+    let config = options.map_or_else(HmmConfig::default, HmmOptions::into_config);
+    let (model, report) = GaussianHmm::fit(&observations, &feature_names.unwrap_or_default(), &config)?;
+    let path = model.viterbi(&observations)?;
+
+    Ok(RegimeFitResult {
+        model: model.to_json(),
+        labels: model.labels.clone(),
+        path: path.iter().map(|&s| s as u32).collect(),
+        log_likelihood: report.log_likelihood,
+        iterations: report.iterations as u32,
+        converged: report.converged,
+    })
+}
+
+/// Most likely regime path for a sequence under a stored model
+#[napi]
+pub fn decode_regimes(model: String, observations: Vec<Vec<f64>>) -> Result<RegimeDecodeResult> {
+    let model = GaussianHmm::from_json(&model)?;
+    let path = model.viterbi(&observations)?;
+
+    Ok(RegimeDecodeResult {
+        labels: path.iter().map(|&s| model.labels[s].clone()).collect(),
+        path: path.iter().map(|&s| s as u32).collect(),
+        log_likelihood: model.log_likelihood(&observations)?,
+    })
+}
+
+/// Fair-value filter options (all optional; noise terms are variances in price² per tick)
+#[napi(object)]
+pub struct KalmanOptions {
+    pub model: Option<String>,          // "level" | "trend" (default)
+    pub level_noise: Option<f64>,       // Default 1e-4
+    pub velocity_noise: Option<f64>,    // Default 1e-6
+    pub observation_noise: Option<f64>, // Default 1e-2
+    pub band_sigma: Option<f64>,        // Default 2
+    pub em_iterations: Option<u32>,     // EM noise estimation passes (default 0)
+    pub smooth: Option<bool>,           // RTS-smoothed (non-causal) output (default false)
+}
+
+impl KalmanOptions {
+    fn into_config(self) -> Result<KalmanConfig> {
+        let defaults = KalmanConfig::default();
+        Ok(KalmanConfig {
+            model: match self.model.as_deref() {
+                Some(name) => KalmanModel::parse(name)?,
+                None => defaults.model,
+            },
+            level_noise: self.level_noise.unwrap_or(defaults.level_noise),
+            velocity_noise: self.velocity_noise.unwrap_or(defaults.velocity_noise),
+            observation_noise: self.observation_noise.unwrap_or(defaults.observation_noise),
+            band_sigma: self.band_sigma.unwrap_or(defaults.band_sigma),
+            em_iterations: self.em_iterations.map_or(defaults.em_iterations, |n| n as usize),
+            smooth: self.smooth.unwrap_or(defaults.smooth),
+        })
+    }
+}
+
+fn kalman_config(options: Option<KalmanOptions>) -> Result<KalmanConfig> {
+    options.map_or_else(|| Ok(KalmanConfig::default()), KalmanOptions::into_config)
+}
+
+/// Fair value for one tick
+#[napi(object)]
+pub struct KalmanPointData {
+    pub timestamp: i64,
+    pub observation: f64,
+    pub price: f64,
+    pub velocity: f64,
+    pub std: f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub innovation: f64,
+    pub z_score: f64,
+}
+
+impl From<&KalmanPoint> for KalmanPointData {
+    fn from(p: &KalmanPoint) -> Self {
+        KalmanPointData {
+            timestamp: p.timestamp as i64,
+            observation: p.observation,
+            price: p.price,
+            velocity: p.velocity,
+            std: p.std,
+            lower: p.lower,
+            upper: p.upper,
+            innovation: p.innovation,
+            z_score: p.z_score,
+        }
+    }
+}
+
+/// Fair-value series with the (possibly EM-estimated) noise it ran with
+#[napi(object)]
+pub struct FairValueResult {
+    pub points: Vec<KalmanPointData>,
+    pub level_noise: f64,
+    pub velocity_noise: f64,
+    pub observation_noise: f64,
+    pub log_likelihood: f64,
+}
+
+/// Kalman fair value of the snapshot mid stream
+#[napi]
+pub fn calculate_fair_value(market_data: Vec<OrderBookData>, options: Option<KalmanOptions>) -> Result<FairValueResult> {
+    let config = kalman_config(options)?;
+    let snapshots: Vec<OrderBookSnapshot> = market_data.iter().map(OrderBookSnapshot::from).collect();
+    let (timestamps, mids) = kalman::mid_series(&snapshots);
+    let series = kalman::kalman_series(&timestamps, &mids, &config)?;
+
+    Ok(FairValueResult {
+        points: series.points.iter().map(KalmanPointData::from).collect(),
+        level_noise: series.level_noise,
+        velocity_noise: series.velocity_noise,
+        observation_noise: series.observation_noise,
+        log_likelihood: series.log_likelihood,
+    })
+}
+
+/// Streaming fair-value filter (filtered, causal)
+#[napi(js_name = "FairValueFilter")]
+pub struct StreamingFairValue {
+    inner: FairValueFilter,
+}
+
+#[napi]
+impl StreamingFairValue {
+    #[napi(constructor)]
+    pub fn new(options: Option<KalmanOptions>) -> Result<Self> {
+        Ok(StreamingFairValue {
+            inner: FairValueFilter::new(kalman_config(options)?)?,
+        })
+    }
+
+    /// Push one order book tick (throws on a non-finite mid)
+    #[napi]
+    pub fn push(&mut self, tick: OrderBookData) -> Result<KalmanPointData> {
+        let snapshot = OrderBookSnapshot::from(&tick);
+        let mid = (snapshot.bid_price + snapshot.ask_price) / 2.0;
+        Ok(KalmanPointData::from(&self.inner.push(snapshot.timestamp, mid)?))
+    }
+
+    #[napi]
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+/// Kalman step result from the generic filter
+#[napi(object)]
+pub struct KalmanStep {
+    pub state: Vec<f64>,
+    pub variances: Vec<f64>, // Diagonal of the state covariance
+    pub innovation: f64,
+    pub innovation_variance: f64,
+}
+
+/// Generic linear Kalman filter with a scalar observation (x' = F x + w, y = H x + v)
+#[napi(js_name = "KalmanFilter")]
+pub struct LinearKalmanFilter {
+    inner: LinearKalman,
+}
+
+#[napi]
+impl LinearKalmanFilter {
+    #[napi(constructor)]
+    pub fn new(
+        transition: Vec<Vec<f64>>,
+        observation: Vec<f64>,
+        process_noise: Vec<Vec<f64>>,
+        observation_noise: f64,
+        initial_state: Vec<f64>,
+        initial_covariance: Vec<Vec<f64>>,
+    ) -> Result<Self> {
+        Ok(LinearKalmanFilter {
+            inner: LinearKalman::new(
+                &transition,
+                &observation,
+                &process_noise,
+                observation_noise,
+                &initial_state,
+                &initial_covariance,
+            )?,
+        })
+    }
+
+    /// Predict, then update with observation `y`; a non-finite `y` throws and leaves the state unchanged
+    #[napi]
+    pub fn step(&mut self, y: f64) -> Result<KalmanStep> {
+        let innovation = self.inner.step(y)?;
+        Ok(KalmanStep {
+            state: self.inner.state().to_vec(),
+            variances: (0..self.inner.state().len()).map(|i| self.inner.variance(i)).collect(),
+            innovation: innovation.value,
+            innovation_variance: innovation.variance,
+        })
+    }
+}
+
+/// Persistent homology options (all optional)
+#[napi(object)]
+pub struct TdaOptions {
+    pub dimension: Option<u32>,        // Embedding dimension (default 3)
+    pub delay: Option<u32>,            // Embedding delay in ticks (default 1)
+    pub include_volume: Option<bool>,  // Embed volume alongside the mid (default true)
+    pub max_edge: Option<f64>,         // Rips threshold in standardised units (default: full)
+    pub max_points: Option<u32>,       // Embedded point cap (default 200)
+    pub hole_persistence: Option<f64>, // H1 persistence counted as a hole (default 0.5)
+    pub auto_embedding: Option<bool>,  // Choose dimension/delay via AMI + FNN / Cao (default false)
+    pub embedding: Option<TakensOptions>, // Source series and search ranges for autoEmbedding
+}
+
+impl TdaOptions {
+    fn into_config(self) -> Result<TdaConfig> {
+        let defaults = TdaConfig::default();
+        let (embedding_source, takens) = match self.embedding {
+            Some(options) => options.into_config()?,
+            None => (defaults.embedding_source, defaults.takens),
+        };
+        Ok(TdaConfig {
+            dimension: self.dimension.map_or(defaults.dimension, |d| d as usize),
+            delay: self.delay.map_or(defaults.delay, |d| d as usize),
+            include_volume: self.include_volume.unwrap_or(defaults.include_volume),
+            max_edge: self.max_edge.or(defaults.max_edge),
+            max_points: self.max_points.map_or(defaults.max_points, |n| n as usize),
+            hole_persistence: self.hole_persistence.unwrap_or(defaults.hole_persistence),
+            auto_embedding: self.auto_embedding.unwrap_or(defaults.auto_embedding),
+            embedding_source,
+            takens,
+        })
+    }
+}
+
+fn tda_config(options: Option<TdaOptions>) -> Result<TdaConfig> {
+    match options {
+        Some(options) => options.into_config(),
+        None => Ok(TdaConfig::default()),
+    }
+}
+
+/// Birth/death pair; `death` is null for an essential (never-dying) feature
+#[napi(object)]
+pub struct PersistencePairData {
+    pub dimension: u32,
+    pub birth: f64,
+    pub death: Option<f64>,
+    pub persistence: Option<f64>,
+}
+
+impl From<&PersistencePair> for PersistencePairData {
+    fn from(p: &PersistencePair) -> Self {
+        let finite = (!p.is_essential()).then_some(());
+        PersistencePairData {
+            dimension: p.dimension as u32,
+            birth: p.birth,
+            death: finite.map(|_| p.death),
+            persistence: finite.map(|_| p.persistence()),
+        }
+    }
+}
+
+/// H0/H1 persistence diagrams of the embedded series
+#[napi(object)]
+pub struct PersistenceResult {
+    pub points: u32,
+    pub dimension: u32, // Embedding used (chosen automatically with autoEmbedding)
+    pub delay: u32,
+    pub h0: Vec<PersistencePairData>,
+    pub h1: Vec<PersistencePairData>,
+    pub total_persistence_h0: f64,
+    pub total_persistence_h1: f64,
+    pub max_persistence_h1: f64,
+    pub holes: u32,
+}
+
+impl From<&TopologySummary> for PersistenceResult {
+    fn from(s: &TopologySummary) -> Self {
+        PersistenceResult {
+            points: s.points as u32,
+            dimension: s.dimension as u32,
+            delay: s.delay as u32,
+            h0: s.diagram.h0.iter().map(PersistencePairData::from).collect(),
+            h1: s.diagram.h1.iter().map(PersistencePairData::from).collect(),
+            total_persistence_h0: s.total_persistence_h0,
+            total_persistence_h1: s.total_persistence_h1,
+            max_persistence_h1: s.max_persistence_h1,
+            holes: s.holes as u32,
+        }
+    }
+}
+
+pub struct PersistenceTask {
+    snapshots: Vec<OrderBookSnapshot>,
+    config: TdaConfig,
+}
+
+impl Task for PersistenceTask {
+    type Output = TopologySummary;
+    type JsValue = PersistenceResult;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        Ok(TopologicalAnalyzer::persistence(&self.snapshots, &self.config)?)
+    }
+
+    fn resolve(&mut self, _env: Env, summary: Self::Output) -> Result<Self::JsValue> {
+        Ok(PersistenceResult::from(&summary))
+    }
+}
+
+/// Vietoris-Rips persistent homology of the delay-embedded mid/volume series (off the JS thread)
+#[napi]
+pub fn compute_persistence(
+    market_data: Vec<OrderBookData>,
+    options: Option<TdaOptions>,
+) -> Result<AsyncTask<PersistenceTask>> {
+    Ok(AsyncTask::new(PersistenceTask {
+        snapshots: market_data.iter().map(OrderBookSnapshot::from).collect(),
+        config: tda_config(options)?,
+    }))
+}
+
+pub struct HolesTask {
+    snapshots: Vec<OrderBookSnapshot>,
+    config: TdaConfig,
+}
+
+impl Task for HolesTask {
+    type Output = bool;
+    type JsValue = bool;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        Ok(TopologicalAnalyzer::detect_holes(&self.snapshots, &self.config)?)
+    }
+
+    fn resolve(&mut self, _env: Env, holes: Self::Output) -> Result<Self::JsValue> {
+        Ok(holes)
+    }
+}
+
+/// True if any window of the embedded series has a persistent H1 loop (off the JS thread).
+/// Series longer than `maxPoints` are scanned in overlapping windows.
+#[napi]
+pub fn detect_liquidity_holes(
+    market_data: Vec<OrderBookData>,
+    options: Option<TdaOptions>,
+) -> Result<AsyncTask<HolesTask>> {
+    Ok(AsyncTask::new(HolesTask {
+        snapshots: market_data.iter().map(OrderBookSnapshot::from).collect(),
+        config: tda_config(options)?,
+    }))
+}
+
+/// Takens parameter search options (all optional)
+#[napi(object)]
+pub struct TakensOptions {
+    pub source: Option<String>,      // "mid" (default) | "obi"
+    pub max_delay: Option<u32>,      // Delays scanned for the AMI minimum (default 50)
+    pub bins: Option<u32>,           // Mutual information histogram bins (default 16)
+    pub max_dimension: Option<u32>,  // Dimensions scanned (default 10)
+    pub method: Option<String>,      // "fnn" (default) | "cao"
+    pub fnn_rtol: Option<f64>,       // Kennel tolerances (default 10 / 2)
+    pub fnn_atol: Option<f64>,
+    pub fnn_threshold: Option<f64>,  // Accepted FNN fraction (default 0.02)
+    pub cao_threshold: Option<f64>,  // Saturated E1 level (default 0.8)
+}
+
+impl TakensOptions {
+    fn into_config(self) -> Result<(EmbeddingSource, TakensConfig)> {
+        let defaults = TakensConfig::default();
+        let source = self.source.as_deref().map(EmbeddingSource::parse).transpose()?.unwrap_or_default();
+        let config = TakensConfig {
+            max_delay: self.max_delay.map_or(defaults.max_delay, |n| n as usize),
+            bins: self.bins.map_or(defaults.bins, |n| n as usize),
+            max_dimension: self.max_dimension.map_or(defaults.max_dimension, |n| n as usize),
+            method: self.method.as_deref().map(DimensionMethod::parse).transpose()?.unwrap_or(defaults.method),
+            fnn_rtol: self.fnn_rtol.unwrap_or(defaults.fnn_rtol),
+            fnn_atol: self.fnn_atol.unwrap_or(defaults.fnn_atol),
+            fnn_threshold: self.fnn_threshold.unwrap_or(defaults.fnn_threshold),
+            cao_threshold: self.cao_threshold.unwrap_or(defaults.cao_threshold),
+        };
+        Ok((source, config))
+    }
+}
+
+/// Chosen embedding; pass `dimension`/`delay` back as TdaOptions to reuse it
+#[napi(object)]
+pub struct TakensResult {
+    pub delay: u32,
+    pub dimension: u32,
+    pub mutual_information: Vec<f64>, // AMI by delay 0..=maxDelay
+    pub false_neighbours: Vec<f64>,   // FNN fraction by dimension 1..=maxDimension
+    pub cao_e1: Vec<f64>,
+    pub cao_e2: Vec<f64>,
+}
+
+impl From<TakensEmbedding> for TakensResult {
+    fn from(e: TakensEmbedding) -> Self {
+        TakensResult {
+            delay: e.delay as u32,
+            dimension: e.dimension as u32,
+            mutual_information: e.mutual_information,
+            false_neighbours: e.false_neighbours,
+            cao_e1: e.cao_e1,
+            cao_e2: e.cao_e2,
+        }
+    }
+}
+
+/// Takens delay (first AMI minimum) and dimension (FNN / Cao) for the mid or OBI series
+#[napi]
+pub fn select_embedding(market_data: Vec<OrderBookData>, options: Option<TakensOptions>) -> Result<TakensResult> {
+    let (source, config) = match options {
+        Some(options) => options.into_config()?,
+        None => (EmbeddingSource::default(), TakensConfig::default()),
+    };
+    let snapshots: Vec<OrderBookSnapshot> = market_data.iter().map(OrderBookSnapshot::from).collect();
+    Ok(TopologicalAnalyzer::select_embedding(&snapshots, source, &config)?.into())
+}
+
+/// Streaming TDA options (all optional)
+#[napi(object)]
+pub struct StreamingTdaOptions {
+    pub window: Option<u32>,            // Embedded points kept (default 64, at most 200)
+    pub dimension: Option<u32>,         // Embedding dimension (default 3)
+    pub delay: Option<u32>,             // Embedding delay in ticks (default 1)
+    pub include_volume: Option<bool>,   // Embed volume alongside the mid (default true)
+    pub ewma_alpha: Option<f64>,        // Online z-score smoothing (default 0.05)
+    pub max_edge: Option<f64>,          // H1 Rips threshold (default: full)
+    pub h1_every: Option<u32>,          // Ticks between H1 runs (default 10)
+    pub latency_budget_ms: Option<f64>, // Per-push budget H1 is skipped against, 0 = unlimited (default 10)
+    pub hole_persistence: Option<f64>,  // H1 persistence counted as a hole (default 0.5)
+}
+
+impl StreamingTdaOptions {
+    fn into_config(self) -> StreamingTdaConfig {
+        let defaults = StreamingTdaConfig::default();
+        StreamingTdaConfig {
+            window: self.window.map_or(defaults.window, |n| n as usize),
+            dimension: self.dimension.map_or(defaults.dimension, |n| n as usize),
+            delay: self.delay.map_or(defaults.delay, |n| n as usize),
+            include_volume: self.include_volume.unwrap_or(defaults.include_volume),
+            ewma_alpha: self.ewma_alpha.unwrap_or(defaults.ewma_alpha),
+            max_edge: self.max_edge.or(defaults.max_edge),
+            h1_every: self.h1_every.map_or(defaults.h1_every, |n| n as usize),
+            latency_budget_ms: self.latency_budget_ms.unwrap_or(defaults.latency_budget_ms),
+            hole_persistence: self.hole_persistence.unwrap_or(defaults.hole_persistence),
+        }
+    }
+}
+
+#[napi(object)]
+pub struct StreamingTdaStateData {
+    pub timestamp: i64,
+    pub points: u32,
+    pub total_persistence_h0: f64,
+    pub max_persistence_h0: f64,
+    pub h1: Vec<PersistencePairData>,
+    pub h1_timestamp: Option<i64>,
+    pub h1_fresh: bool,
+    pub total_persistence_h1: f64,
+    pub max_persistence_h1: f64,
+    pub holes: u32,
+    pub push_ms: f64,
+    pub budget_exceeded: bool,
+}
+
+impl From<&StreamingTdaState> for StreamingTdaStateData {
+    fn from(s: &StreamingTdaState) -> Self {
+        StreamingTdaStateData {
+            timestamp: s.timestamp as i64,
+            points: s.points as u32,
+            total_persistence_h0: s.total_persistence_h0,
+            max_persistence_h0: s.max_persistence_h0,
+            h1: s.h1.iter().map(PersistencePairData::from).collect(),
+            h1_timestamp: s.h1_timestamp.map(|t| t as i64),
+            h1_fresh: s.h1_fresh,
+            total_persistence_h1: s.total_persistence_h1,
+            max_persistence_h1: s.max_persistence_h1,
+            holes: s.holes as u32,
+            push_ms: s.push_ms,
+            budget_exceeded: s.budget_exceeded,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct StreamingTdaMetricsData {
+    pub pushes: i64,
+    pub h1_runs: i64,
+    pub h1_skipped: i64,
+    pub budget_overruns: i64,
+    pub last_push_ms: f64,
+    pub mean_push_ms: f64,
+    pub max_push_ms: f64,
+    pub last_h0_ms: f64,
+    pub last_h1_ms: f64,
+}
+
+impl From<StreamingTdaMetrics> for StreamingTdaMetricsData {
+    fn from(m: StreamingTdaMetrics) -> Self {
+        StreamingTdaMetricsData {
+            pushes: m.pushes as i64,
+            h1_runs: m.h1_runs as i64,
+            h1_skipped: m.h1_skipped as i64,
+            budget_overruns: m.budget_overruns as i64,
+            last_push_ms: m.last_push_ms,
+            mean_push_ms: m.mean_push_ms,
+            max_push_ms: m.max_push_ms,
+            last_h0_ms: m.last_h0_ms,
+            last_h1_ms: m.last_h1_ms,
+        }
+    }
+}
+
+/// Sliding-window persistence for live ticks: H0 every push, H1 on a cadence within budget
+#[napi(js_name = "StreamingTopology")]
+pub struct StreamingTopologyEngine {
+    inner: StreamingTopology,
+}
+
+#[napi]
+impl StreamingTopologyEngine {
+    #[napi(constructor)]
+    pub fn new(options: Option<StreamingTdaOptions>) -> Result<Self> {
+        let config = options.map_or_else(StreamingTdaConfig::default, StreamingTdaOptions::into_config);
+        Ok(StreamingTopologyEngine {
+            inner: StreamingTopology::new(config)?,
+        })
+    }
+
+    /// Push one order book tick
+    #[napi]
+    pub fn push(&mut self, tick: OrderBookData) -> Result<StreamingTdaStateData> {
+        let state = self.inner.push(&OrderBookSnapshot::from(&tick))?;
+        Ok(StreamingTdaStateData::from(&state))
+    }
+
+    #[napi]
+    pub fn metrics(&self) -> StreamingTdaMetricsData {
+        self.inner.metrics().into()
+    }
+
+    /// Embedded point cloud in the window, oldest first
+    #[napi]
+    pub fn points(&self) -> Vec<Vec<f64>> {
+        self.inner.points()
+    }
+
+    #[napi]
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+/// Mapper options (all optional)
+#[napi(object)]
+pub struct MapperOptions {
+    pub lens: Option<String>,        // "pca" (default) | "mid" | "obi" | "spread" | "time"
+    pub intervals: Option<u32>,      // Cover elements (default 10)
+    pub overlap: Option<f64>,        // Interval overlap fraction (default 0.3)
+    pub cluster_eps: Option<f64>,    // Single-linkage cut (default: first empty histogram bin)
+    pub histogram_bins: Option<u32>, // Bins for the automatic cut (default 10)
+}
+
+impl MapperOptions {
+    fn into_config(self) -> Result<(MapperLens, MapperConfig)> {
+        let defaults = MapperConfig::default();
+        let lens = self.lens.as_deref().map(MapperLens::parse).transpose()?.unwrap_or_default();
+        let config = MapperConfig {
+            intervals: self.intervals.map_or(defaults.intervals, |n| n as usize),
+            overlap: self.overlap.unwrap_or(defaults.overlap),
+            cluster_eps: self.cluster_eps.or(defaults.cluster_eps),
+            histogram_bins: self.histogram_bins.map_or(defaults.histogram_bins, |n| n as usize),
+        };
+        Ok((lens, config))
+    }
+}
+
+/// Mapper graph of the market state as JSON `{nodes: [{id, interval, members, lens_mean,
+/// centroid}], edges: [{source, target, shared, jaccard}], components}`; members index `market_data`
+#[napi]
+pub fn build_mapper_graph(market_data: Vec<OrderBookData>, options: Option<MapperOptions>) -> Result<String> {
+    let (lens, config) = match options {
+        Some(options) => options.into_config()?,
+        None => (MapperLens::default(), MapperConfig::default()),
+    };
+    let snapshots: Vec<OrderBookSnapshot> = market_data.iter().map(OrderBookSnapshot::from).collect();
+    Ok(TopologicalAnalyzer::mapper_graph(&snapshots, lens, &config)?.to_json())
+}
+
+/// Sliding-window topology series options (all optional)
+#[napi(object)]
+pub struct TopologySeriesOptions {
+    pub window: Option<u32>,           // Snapshots per diagram (default 50)
+    pub step: Option<u32>,             // Snapshots between windows (default 1)
+    pub wasserstein_p: Option<f64>,    // Wasserstein order (default 2)
+    pub landscape_levels: Option<u32>, // Landscape functions per dimension (default 3)
+    pub resolution: Option<u32>,       // Grid samples per feature curve (default 32)
+    pub baseline: Option<u32>,         // Prior distances scored for a spike (default 50)
+    pub spike_z: Option<f64>,          // Spike z-score threshold (default 3)
+}
+
+impl TopologySeriesOptions {
+    fn into_config(self) -> TopologySeriesConfig {
+        let defaults = TopologySeriesConfig::default();
+        TopologySeriesConfig {
+            window: self.window.map_or(defaults.window, |n| n as usize),
+            step: self.step.map_or(defaults.step, |n| n as usize),
+            wasserstein_p: self.wasserstein_p.unwrap_or(defaults.wasserstein_p),
+            landscape_levels: self.landscape_levels.map_or(defaults.landscape_levels, |n| n as usize),
+            resolution: self.resolution.map_or(defaults.resolution, |n| n as usize),
+            baseline: self.baseline.map_or(defaults.baseline, |n| n as usize),
+            spike_z: self.spike_z.unwrap_or(defaults.spike_z),
+        }
+    }
+}
+
+#[napi(object)]
+pub struct TopologyPointData {
+    pub timestamp: i64,
+    pub total_persistence_h1: f64,
+    pub max_persistence_h1: f64,
+    pub holes: u32,
+    pub bottleneck_h0: Option<f64>,
+    pub bottleneck_h1: Option<f64>,
+    pub wasserstein_h0: Option<f64>,
+    pub wasserstein_h1: Option<f64>,
+    pub distance_z: Option<f64>,
+    pub spike: bool,
+    pub landscape_h0: Vec<f64>,
+    pub landscape_h1: Vec<f64>,
+    pub betti_h0: Vec<f64>,
+    pub betti_h1: Vec<f64>,
+}
+
+impl From<&TopologyPoint> for TopologyPointData {
+    fn from(p: &TopologyPoint) -> Self {
+        TopologyPointData {
+            timestamp: p.timestamp as i64,
+            total_persistence_h1: p.total_persistence_h1,
+            max_persistence_h1: p.max_persistence_h1,
+            holes: p.holes as u32,
+            bottleneck_h0: p.bottleneck_h0,
+            bottleneck_h1: p.bottleneck_h1,
+            wasserstein_h0: p.wasserstein_h0,
+            wasserstein_h1: p.wasserstein_h1,
+            distance_z: p.distance_z,
+            spike: p.spike,
+            landscape_h0: p.landscape_h0.clone(),
+            landscape_h1: p.landscape_h1.clone(),
+            betti_h0: p.betti_h0.clone(),
+            betti_h1: p.betti_h1.clone(),
+        }
+    }
+}
+
+/// Topology time series; `grid` is the filtration axis of every landscape / Betti curve
+#[napi(object)]
+pub struct TopologySeriesResult {
+    pub grid: Vec<f64>,
+    pub points: Vec<TopologyPointData>,
+}
+
+pub struct TopologySeriesTask {
+    snapshots: Vec<OrderBookSnapshot>,
+    config: TdaConfig,
+    series: TopologySeriesConfig,
+}
+
+impl Task for TopologySeriesTask {
+    type Output = TopologySeries;
+    type JsValue = TopologySeriesResult;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        Ok(TopologicalAnalyzer::topology_series(&self.snapshots, &self.config, &self.series)?)
+    }
+
+    fn resolve(&mut self, _env: Env, series: Self::Output) -> Result<Self::JsValue> {
+        Ok(TopologySeriesResult {
+            points: series.points.iter().map(TopologyPointData::from).collect(),
+            grid: series.grid,
+        })
+    }
+}
+
+/// Windowed persistence with bottleneck/Wasserstein distances between consecutive
+/// diagrams, landscapes and Betti curves (off the JS thread)
+#[napi(namespace = "tda", js_name = "calculate_topology_series")]
+pub fn calculate_topology_series(
+    market_data: Vec<OrderBookData>,
+    tda_options: Option<TdaOptions>,
+    series_options: Option<TopologySeriesOptions>,
+) -> Result<AsyncTask<TopologySeriesTask>> {
+    Ok(AsyncTask::new(TopologySeriesTask {
+        snapshots: market_data.iter().map(OrderBookSnapshot::from).collect(),
+        config: tda_config(tda_options)?,
+        series: series_options.map_or_else(TopologySeriesConfig::default, TopologySeriesOptions::into_config),
+    }))
+}
+
+/// Liquidity surface thresholds (all optional)
+#[napi(object)]
+pub struct LiquidityCurvatureOptions {
+    pub void_fraction: Option<f64>,  // Void = depth below this fraction of the median non-empty depth (default 0.25)
+    pub min_void_cells: Option<u32>, // Smallest counted void (default 1)
+    pub medium: Option<f64>,         // Void share per risk level (default 0.02 / 0.1 / 0.25)
+    pub high: Option<f64>,
+    pub critical: Option<f64>,
+}
+
+impl LiquidityCurvatureOptions {
+    fn into_config(self) -> LiquiditySurfaceConfig {
+        let defaults = LiquiditySurfaceConfig::default();
+        LiquiditySurfaceConfig {
+            void_fraction: self.void_fraction.unwrap_or(defaults.void_fraction),
+            min_void_cells: self.min_void_cells.map_or(defaults.min_void_cells, |n| n as usize),
+            medium: self.medium.unwrap_or(defaults.medium),
+            high: self.high.unwrap_or(defaults.high),
+            critical: self.critical.unwrap_or(defaults.critical),
+        }
+    }
+}
+
+#[napi(object)]
+pub struct LiquidityVoidData {
+    pub cells: u32,
+    pub level_start: u32,
+    pub level_end: u32,
+    pub time_start: u32,
+    pub time_end: u32,
+    pub centroid_level: f64,
+    pub centroid_time: f64,
+    pub depth: f64,
+}
+
+impl From<&LiquidityVoid> for LiquidityVoidData {
+    fn from(v: &LiquidityVoid) -> Self {
+        LiquidityVoidData {
+            cells: v.cells as u32,
+            level_start: v.level_start as u32,
+            level_end: v.level_end as u32,
+            time_start: v.time_start as u32,
+            time_end: v.time_end as u32,
+            centroid_level: v.centroid_level,
+            centroid_time: v.centroid_time,
+            depth: v.depth,
+        }
+    }
+}
+
+/// Omega's LiquidityCurvature contract plus the per-cell curvature grids and voids
+#[napi(object)]
+pub struct LiquidityCurvatureResult {
+    pub curvature: f64,
+    pub holes: u32,
+    pub manifold_dimension: f64,
+    pub risk_level: String, // LOW | MEDIUM | HIGH | CRITICAL
+    pub void_share: f64,
+    pub voids: Vec<LiquidityVoidData>,
+    pub gaussian_curvature: Vec<Vec<f64>>,
+    pub mean_curvature: Vec<Vec<f64>>,
+}
+
+impl From<LiquiditySurfaceCurvature> for LiquidityCurvatureResult {
+    fn from(c: LiquiditySurfaceCurvature) -> Self {
+        LiquidityCurvatureResult {
+            curvature: c.curvature,
+            holes: c.holes as u32,
+            manifold_dimension: c.manifold_dimension,
+            risk_level: c.risk_level.as_str().to_string(),
+            void_share: c.void_share,
+            voids: c.voids.iter().map(LiquidityVoidData::from).collect(),
+            gaussian_curvature: c.gaussian,
+            mean_curvature: c.mean,
+        }
+    }
+}
+
+/// Curvature and voids of a price-level × time liquidity surface (`liquidityData[level][t]`).
+/// Exported as `tda.calculate_liquidity_curvature`, the name Omega calls.
+#[napi(namespace = "tda", js_name = "calculate_liquidity_curvature")]
+pub fn calculate_liquidity_curvature(
+    liquidity_data: Vec<Vec<f64>>,
+    options: Option<LiquidityCurvatureOptions>,
+) -> Result<LiquidityCurvatureResult> {
+    let config = options.map_or_else(LiquiditySurfaceConfig::default, LiquidityCurvatureOptions::into_config);
+    Ok(TopologicalAnalyzer::liquidity_curvature(&liquidity_data, &config)?.into())
+}
+
+/// Intrinsic dimension options (all optional)
+#[napi(object)]
+pub struct IntrinsicDimensionOptions {
+    pub window: Option<u32>,           // Snapshots per window (default 100)
+    pub step: Option<u32>,             // Snapshots between windows (default 10)
+    pub k: Option<u32>,                // Levina-Bickel neighbours (default 10)
+    pub gp_low_quantile: Option<f64>,  // Correlation-integral scaling range (default 0.05 - 0.5)
+    pub gp_high_quantile: Option<f64>,
+    pub gp_radii: Option<u32>,         // Radii fitted in the scaling range (default 12)
+}
+
+impl IntrinsicDimensionOptions {
+    fn into_config(self) -> DimensionSeriesConfig {
+        let defaults = DimensionSeriesConfig::default();
+        let estimator = defaults.estimator;
+        DimensionSeriesConfig {
+            window: self.window.map_or(defaults.window, |n| n as usize),
+            step: self.step.map_or(defaults.step, |n| n as usize),
+            estimator: DimensionConfig {
+                k: self.k.map_or(estimator.k, |n| n as usize),
+                gp_low_quantile: self.gp_low_quantile.unwrap_or(estimator.gp_low_quantile),
+                gp_high_quantile: self.gp_high_quantile.unwrap_or(estimator.gp_high_quantile),
+                gp_radii: self.gp_radii.map_or(estimator.gp_radii, |n| n as usize),
+            },
+        }
+    }
+}
+
+fn dimension_config(options: Option<IntrinsicDimensionOptions>) -> DimensionSeriesConfig {
+    options.map_or_else(DimensionSeriesConfig::default, IntrinsicDimensionOptions::into_config)
+}
+
+/// Intrinsic dimension by estimator; null where the cloud is too small or degenerate
+#[napi(object)]
+pub struct IntrinsicDimensionData {
+    pub timestamp: Option<i64>, // Last snapshot of the window (null for a raw point cloud)
+    pub points: u32,
+    pub mle: Option<f64>,
+    pub correlation: Option<f64>,
+    pub two_nn: Option<f64>,
+}
+
+impl From<&DimensionEstimate> for IntrinsicDimensionData {
+    fn from(e: &DimensionEstimate) -> Self {
+        IntrinsicDimensionData {
+            timestamp: None,
+            points: e.points as u32,
+            mle: e.mle,
+            correlation: e.correlation,
+            two_nn: e.two_nn,
+        }
+    }
+}
+
+pub struct IntrinsicDimensionTask {
+    snapshots: Vec<OrderBookSnapshot>,
+    config: DimensionSeriesConfig,
+}
+
+impl Task for IntrinsicDimensionTask {
+    type Output = Vec<DimensionPoint>;
+    type JsValue = Vec<IntrinsicDimensionData>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        Ok(TopologicalAnalyzer::intrinsic_dimension_series(&self.snapshots, &self.config)?)
+    }
+
+    fn resolve(&mut self, _env: Env, points: Self::Output) -> Result<Self::JsValue> {
+        Ok(points
+            .iter()
+            .map(|p| IntrinsicDimensionData {
+                timestamp: Some(p.timestamp as i64),
+                ..IntrinsicDimensionData::from(&p.estimate)
+            })
+            .collect())
+    }
+}
+
+/// Intrinsic dimension of the market state (mid, spread, bid/ask volume) over sliding windows
+#[napi]
+pub fn calculate_intrinsic_dimension(
+    market_data: Vec<OrderBookData>,
+    options: Option<IntrinsicDimensionOptions>,
+) -> AsyncTask<IntrinsicDimensionTask> {
+    AsyncTask::new(IntrinsicDimensionTask {
+        snapshots: market_data.iter().map(OrderBookSnapshot::from).collect(),
+        config: dimension_config(options),
+    })
+}
+
+/// Intrinsic dimension of an arbitrary point cloud (rows are points); window/step are ignored
+#[napi]
+pub fn estimate_intrinsic_dimension(
+    points: Vec<Vec<f64>>,
+    options: Option<IntrinsicDimensionOptions>,
+) -> Result<IntrinsicDimensionData> {
+    let config = dimension_config(options).estimator;
+    config.validate()?;
+    let width = points.first().map_or(0, Vec::len);
+    if points.iter().any(|p| p.len() != width || p.iter().any(|v| !v.is_finite())) {
+        return Err(EngineError::InvalidConfig("points must be finite rows of equal length".to_string()).into());
+    }
+    Ok(IntrinsicDimensionData::from(&dimension::estimate_dimension(&points, &config)))
+}
+
+/// Legacy imbalance classifier kept for existing callers; despite the name it
+/// returns a signal, not an entropy (see `calculate_volume_entropy`)
+#[napi]
+pub fn evaluate_market_entropy(imbalance: f64) -> String {
+    if imbalance > 0.3 {
+        "BUY_PRESSURE".to_string()
+    } else if imbalance < -0.3 {
+        "SELL_PRESSURE".to_string()
+    } else {
+        "NEUTRAL".to_string()
+    }
+}
+
+/// Compute backends registered in this build ("scalar", "rayon", "cuda" if available)
+#[napi]
+pub fn available_backends() -> Vec<String> {
+    BackendRegistry::with_defaults()
+        .names()
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
 /// Health check for GPU availability
 #[napi]
 pub fn check_gpu_status() -> String {
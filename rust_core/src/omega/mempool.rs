@@ -1,38 +1,417 @@
+// MEMPOOL.rs - Ethereum Pending-Transaction Subscriber (JSON-RPC over WebSocket)
+// COMPLEXITY: O(1) per notification, O(capacity) buffer, O(max_in_flight) pending lookups
+// DETERMINISTIC: No - network driven; buffer order follows lookup completion order
+
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
-use std::time::{SystemTime, UNIX_EPOCH};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::watch;
+
+use async_tungstenite::tokio::connect_async;
+use async_tungstenite::tungstenite::Message;
+
+/// Request id of the `eth_subscribe` call; transaction lookups count up from here
+const SUBSCRIBE_ID: u64 = 1;
 
-#[derive(Serialize, Deserialize, Debug)]
+/// Known exchange hot wallets (lowercase)
+const EXCHANGE_WALLETS: [&str; 4] = [
+    "0x3f5ce5fbfe3e9af3971dd833d26ba9b5c936f0be", // Binance
+    "0x28c6c06298d514db089934071355e5743bf21d60", // Binance 14
+    "0x71660c4005ba85c37ccec55d0c4493e66fe775d3", // Coinbase
+    "0x2910543af39aba0cd09dbb2d50200b3e800a63d2", // Kraken
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct MempoolTransaction {
     pub hash: String,
     pub from: String,
-    pub to: String,
+    pub to: String, // Empty for contract creation
     pub value_eth: f64,
-    pub timestamp: u64,
+    pub gas_price_gwei: f64, // gasPrice, or maxFeePerGas for EIP-1559 transactions
+    pub timestamp: u64,      // Epoch millis when the lookup returned
+}
+
+impl MempoolTransaction {
+    /// Parse an `eth_getTransactionByHash` result (hex quantities in wei)
+    pub fn from_rpc(tx: &Value, timestamp: u64) -> Option<Self> {
+        let field = |name: &str| tx.get(name).and_then(Value::as_str);
+        let wei = |name: &str| field(name).and_then(|hex| u128::from_str_radix(hex.trim_start_matches("0x"), 16).ok());
+        Some(MempoolTransaction {
+            hash: field("hash")?.to_string(),
+            from: field("from")?.to_string(),
+            to: field("to").unwrap_or_default().to_string(),
+            value_eth: wei("value")? as f64 / 1e18,
+            gas_price_gwei: wei("gasPrice").or_else(|| wei("maxFeePerGas")).unwrap_or(0) as f64 / 1e9,
+            timestamp,
+        })
+    }
+
+    pub fn to_exchange(&self) -> bool {
+        EXCHANGE_WALLETS.contains(&self.to.to_ascii_lowercase().as_str())
+    }
+}
+
+/// Connection, filtering and buffering settings
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MempoolConfig {
+    pub url: String,              // ws:// or wss:// endpoint of an Ethereum node
+    pub whale_threshold_eth: f64, // Transactions below this value are not buffered (0 = keep all)
+    pub buffer_capacity: usize,   // Buffered transactions; the oldest is dropped when full
+    pub initial_backoff_ms: u64,  // First reconnect delay, doubled per failure
+    pub max_backoff_ms: u64,
+    pub request_timeout_ms: u64,  // Unanswered transaction lookups are abandoned after this
+    pub max_in_flight: usize,     // Concurrent lookups; further hashes are dropped
 }
 
-pub struct MempoolListener;
+impl Default for MempoolConfig {
+    fn default() -> Self {
+        MempoolConfig {
+            url: "ws://127.0.0.1:8546".to_string(),
+            whale_threshold_eth: 100.0,
+            buffer_capacity: 1024,
+            initial_backoff_ms: 250,
+            max_backoff_ms: 30_000,
+            request_timeout_ms: 10_000,
+            max_in_flight: 256,
+        }
+    }
+}
+
+impl MempoolConfig {
+    fn validate(&self) -> Result<(), String> {
+        if !(self.url.starts_with("ws://") || self.url.starts_with("wss://")) {
+            return Err(format!("mempool url must be a ws:// or wss:// endpoint, got '{}'", self.url));
+        }
+        if self.buffer_capacity == 0 || self.max_in_flight == 0 {
+            return Err("buffer_capacity and max_in_flight must be >= 1".to_string());
+        }
+        if self.initial_backoff_ms == 0 || self.initial_backoff_ms > self.max_backoff_ms {
+            return Err(format!(
+                "backoff must satisfy 0 < initial <= max, got {} / {}",
+                self.initial_backoff_ms, self.max_backoff_ms
+            ));
+        }
+        if self.whale_threshold_eth.is_nan() || self.whale_threshold_eth < 0.0 {
+            return Err(format!("whale_threshold_eth must be >= 0, got {}", self.whale_threshold_eth));
+        }
+        Ok(())
+    }
+}
+
+/// Subscriber counters
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MempoolStats {
+    pub connected: bool,    // Subscribed on a live connection
+    pub connects: u64,      // Successful subscriptions
+    pub reconnects: u64,    // Reconnect attempts after a failure
+    pub hashes_seen: u64,   // Pending transaction notifications
+    pub transactions: u64,  // Buffered (at or above the threshold)
+    pub filtered: u64,      // Below the whale threshold (on arrival or at scan)
+    pub missing: u64,       // Lookups answered with null (mined or evicted)
+    pub dropped: u64,       // Buffer overflow or in-flight limit
+    pub timeouts: u64,      // Lookups abandoned after request_timeout_ms
+    pub last_error: Option<String>,
+}
+
+struct Shared {
+    buffer: Mutex<VecDeque<MempoolTransaction>>,
+    stats: Mutex<MempoolStats>,
+}
+
+impl Shared {
+    fn stats(&self) -> std::sync::MutexGuard<'_, MempoolStats> {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn deliver(&self, tx: MempoolTransaction, config: &MempoolConfig) {
+        if tx.value_eth < config.whale_threshold_eth {
+            self.stats().filtered += 1;
+            return;
+        }
+        let mut buffer = self.buffer.lock().unwrap_or_else(|e| e.into_inner());
+        let overflow = buffer.len() >= config.buffer_capacity;
+        if overflow {
+            buffer.pop_front();
+        }
+        buffer.push_back(tx);
+        drop(buffer);
+
+        let mut stats = self.stats();
+        stats.transactions += 1;
+        stats.dropped += overflow as u64;
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64)
+}
+
+/// Live pending-transaction subscriber. Runs on its own runtime; dropping it disconnects.
+pub struct MempoolListener {
+    shared: Arc<Shared>,
+    shutdown: watch::Sender<bool>,
+    runtime: Option<tokio::runtime::Runtime>,
+}
 
 impl MempoolListener {
-    pub fn scan() -> Vec<MempoolTransaction> {
-        // SIMULATION: In a real scenario, this would connect to an Ethereum Node WebSocket
-        // and filter pending transactions.
-        
-        let mut suspicious_txs = Vec::new();
-        
-        // Simulate a random chance of a Whale movement based on time
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
-        
-        // 20% chance of detecting a whale per scan
-        if now % 5 == 0 { 
-            suspicious_txs.push(MempoolTransaction {
-                hash: format!("0x{:x}", now),
-                from: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".to_string(), // Known Whale
-                to: "0x3f5CE5FBFe3E9af3971dD833D26bA9b5C936f0bE".to_string(), // Binance Hot Wallet
-                value_eth: 10000.0 + (now % 50000) as f64 / 1000.0, // 10k - 60k ETH
-                timestamp: now as u64,
-            });
-        }
-        
-        suspicious_txs
+    /// Start subscribing in the background (connection errors are retried, not returned)
+    pub fn connect(config: MempoolConfig) -> Result<Self, String> {
+        config.validate()?;
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("mempool")
+            .enable_all()
+            .build()
+            .map_err(|e| format!("mempool runtime: {e}"))?;
+        let shared = Arc::new(Shared {
+            buffer: Mutex::new(VecDeque::with_capacity(config.buffer_capacity)),
+            stats: Mutex::new(MempoolStats::default()),
+        });
+        let (shutdown, stop) = watch::channel(false);
+        runtime.spawn(run(config, Arc::clone(&shared), stop));
+        Ok(MempoolListener {
+            shared,
+            shutdown,
+            runtime: Some(runtime),
+        })
+    }
+
+    /// Drain the buffer, returning transactions worth at least `min_value_eth`, oldest first.
+    /// Smaller ones are discarded and counted as filtered, so they cannot fill the buffer
+    /// and push out whales that arrive before the next scan.
+    pub fn scan(&self, min_value_eth: f64) -> Vec<MempoolTransaction> {
+        let drained: Vec<MempoolTransaction> = {
+            let mut buffer = self.shared.buffer.lock().unwrap_or_else(|e| e.into_inner());
+            buffer.drain(..).collect()
+        };
+        let (taken, below): (Vec<_>, Vec<_>) = drained.into_iter().partition(|tx| tx.value_eth >= min_value_eth);
+        self.shared.stats().filtered += below.len() as u64;
+        taken
+    }
+
+    pub fn stats(&self) -> MempoolStats {
+        self.shared.stats().clone()
+    }
+}
+
+impl Drop for MempoolListener {
+    fn drop(&mut self) {
+        let _ = self.shutdown.send(true);
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// Reconnect loop with exponential backoff (reset after each successful subscription)
+async fn run(config: MempoolConfig, shared: Arc<Shared>, mut stop: watch::Receiver<bool>) {
+    let initial = Duration::from_millis(config.initial_backoff_ms);
+    let mut backoff = initial;
+    loop {
+        let outcome = tokio::select! {
+            outcome = session(&config, &shared, &mut backoff) => outcome,
+            _ = stop.changed() => return,
+        };
+        {
+            let mut stats = shared.stats();
+            stats.connected = false;
+            stats.last_error = Some(outcome.err().unwrap_or_else(|| "connection closed".to_string()));
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(backoff) => {}
+            _ = stop.changed() => return,
+        }
+        backoff = (backoff * 2).min(Duration::from_millis(config.max_backoff_ms));
+        shared.stats().reconnects += 1;
+    }
+}
+
+/// One connection: subscribe, then look up every announced hash. Returns on disconnect.
+async fn session(config: &MempoolConfig, shared: &Shared, backoff: &mut Duration) -> Result<(), String> {
+    let (mut ws, _) = connect_async(config.url.as_str()).await.map_err(|e| format!("connect: {e}"))?;
+    let subscribe = json!({"jsonrpc": "2.0", "id": SUBSCRIBE_ID, "method": "eth_subscribe", "params": ["newPendingTransactions"]});
+    ws.send(Message::text(subscribe.to_string())).await.map_err(|e| format!("subscribe: {e}"))?;
+
+    let timeout = Duration::from_millis(config.request_timeout_ms.max(1));
+    let mut sweep = tokio::time::interval(timeout.min(Duration::from_secs(1)));
+    let mut subscription: Option<String> = None;
+    let mut pending: HashMap<u64, Instant> = HashMap::new();
+    let mut next_id = SUBSCRIBE_ID + 1;
+
+    loop {
+        let frame = tokio::select! {
+            frame = ws.next() => frame,
+            _ = sweep.tick() => {
+                let before = pending.len();
+                pending.retain(|_, sent| sent.elapsed() < timeout);
+                shared.stats().timeouts += (before - pending.len()) as u64;
+                continue;
+            }
+        };
+        let text = match frame {
+            None => return Ok(()),
+            Some(Err(e)) => return Err(format!("read: {e}")),
+            Some(Ok(Message::Text(text))) => text,
+            Some(Ok(Message::Close(_))) => return Ok(()),
+            Some(Ok(_)) => continue, // Pings are answered by the protocol layer
+        };
+        let Ok(message) = serde_json::from_str::<Value>(text.as_str()) else {
+            shared.stats().last_error = Some("non-JSON frame".to_string());
+            continue;
+        };
+
+        if message.get("method").and_then(Value::as_str) == Some("eth_subscription") {
+            let params = &message["params"];
+            if params["subscription"].as_str() != subscription.as_deref() {
+                continue;
+            }
+            shared.stats().hashes_seen += 1;
+            match &params["result"] {
+                // Nodes that push full transactions skip the lookup
+                tx @ Value::Object(_) => {
+                    if let Some(tx) = MempoolTransaction::from_rpc(tx, now_ms()) {
+                        shared.deliver(tx, config);
+                    }
+                }
+                Value::String(hash) if pending.len() < config.max_in_flight => {
+                    let lookup = json!({"jsonrpc": "2.0", "id": next_id, "method": "eth_getTransactionByHash", "params": [hash]});
+                    ws.send(Message::text(lookup.to_string())).await.map_err(|e| format!("lookup: {e}"))?;
+                    pending.insert(next_id, Instant::now());
+                    next_id += 1;
+                }
+                _ => shared.stats().dropped += 1,
+            }
+            continue;
+        }
+
+        let Some(id) = message.get("id").and_then(Value::as_u64) else { continue };
+        if let Some(error) = message.get("error") {
+            if id == SUBSCRIBE_ID {
+                return Err(format!("eth_subscribe rejected: {error}"));
+            }
+            pending.remove(&id);
+            shared.stats().last_error = Some(format!("eth_getTransactionByHash: {error}"));
+        } else if id == SUBSCRIBE_ID {
+            subscription = message["result"].as_str().map(str::to_string);
+            *backoff = Duration::from_millis(config.initial_backoff_ms);
+            let mut stats = shared.stats();
+            stats.connected = true;
+            stats.connects += 1;
+        } else if pending.remove(&id).is_some() {
+            match MempoolTransaction::from_rpc(&message["result"], now_ms()) {
+                Some(tx) => shared.deliver(tx, config),
+                None => shared.stats().missing += 1,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_tungstenite::tokio::accept_async;
+    use tokio::net::TcpListener;
+
+    fn mock_tx(hash: &str) -> Value {
+        let (to, wei) = match hash {
+            "0xwhale1" => ("0x3F5CE5FBFE3E9AF3971DD833D26BA9B5C936F0BE", "0x821ab0d4414980000"), // 150 ETH
+            "0xsmall" => ("0x00000000000000000000000000000000000000aa", "0xde0b6b3a7640000"),    // 1 ETH
+            "0xwhale2" => ("0x00000000000000000000000000000000000000bb", "0x6c6b935b8bbd400000"), // 2000 ETH
+            _ => return Value::Null,                                                            // Already mined
+        };
+        json!({"hash": hash, "from": "0x00000000000000000000000000000000000000cc", "to": to, "value": wei, "gasPrice": "0x4a817c800"})
+    }
+
+    /// Minimal node: one connection per entry, announcing its hashes then (except the last) hanging up
+    async fn mock_node(listener: TcpListener, sessions: Vec<Vec<&'static str>>) {
+        let last = sessions.len() - 1;
+        for (n, hashes) in sessions.into_iter().enumerate() {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            let mut answered = 0;
+            while let Some(Ok(frame)) = ws.next().await {
+                let Message::Text(text) = frame else { continue };
+                let request: Value = serde_json::from_str(text.as_str()).unwrap();
+                let id = request["id"].clone();
+                if request["method"] == "eth_subscribe" {
+                    assert_eq!(request["params"][0], "newPendingTransactions");
+                    ws.send(Message::text(json!({"jsonrpc": "2.0", "id": id, "result": "0xsub"}).to_string())).await.unwrap();
+                    // A notification for someone else's subscription is ignored
+                    let foreign = json!({"jsonrpc": "2.0", "method": "eth_subscription", "params": {"subscription": "0xother", "result": "0xwhale1"}});
+                    ws.send(Message::text(foreign.to_string())).await.unwrap();
+                    for hash in &hashes {
+                        let note = json!({"jsonrpc": "2.0", "method": "eth_subscription", "params": {"subscription": "0xsub", "result": hash}});
+                        ws.send(Message::text(note.to_string())).await.unwrap();
+                    }
+                } else {
+                    assert_eq!(request["method"], "eth_getTransactionByHash");
+                    let hash = request["params"][0].as_str().unwrap();
+                    ws.send(Message::text(json!({"jsonrpc": "2.0", "id": id, "result": mock_tx(hash)}).to_string())).await.unwrap();
+                    answered += 1;
+                    if answered == hashes.len() && n != last {
+                        ws.close(None).await.unwrap();
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_subscriber_against_mock_node_with_reconnect() {
+        let node = tokio::runtime::Runtime::new().unwrap();
+        let listener = node.block_on(TcpListener::bind("127.0.0.1:0")).unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        node.spawn(mock_node(listener, vec![vec!["0xwhale1", "0xsmall", "0xgone"], vec!["0xwhale2"]]));
+
+        let config = MempoolConfig { url, initial_backoff_ms: 20, max_backoff_ms: 100, ..Default::default() };
+        let listener = MempoolListener::connect(config.clone()).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut whales = Vec::new();
+        while whales.len() < 2 && Instant::now() < deadline {
+            whales.extend(listener.scan(0.0));
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let hashes: Vec<&str> = whales.iter().map(|tx| tx.hash.as_str()).collect();
+        assert_eq!(hashes, ["0xwhale1", "0xwhale2"]);
+        assert!((whales[0].value_eth - 150.0).abs() < 1e-9);
+        assert!((whales[0].gas_price_gwei - 20.0).abs() < 1e-9);
+        assert!(whales[0].to_exchange() && !whales[1].to_exchange());
+
+        let stats = listener.stats();
+        assert_eq!(stats.connects, 2);
+        assert!(stats.reconnects >= 1);
+        assert_eq!(stats.hashes_seen, 4);
+        assert_eq!((stats.transactions, stats.filtered, stats.missing), (2, 1, 1));
+        assert!(stats.connected);
+        drop(listener);
+
+        // Bounded buffer drops the oldest transaction
+        let shared = Shared { buffer: Mutex::new(VecDeque::new()), stats: Mutex::new(MempoolStats::default()) };
+        let small = MempoolConfig { buffer_capacity: 1, whale_threshold_eth: 0.0, ..config };
+        for hash in ["0xwhale1", "0xwhale2"] {
+            shared.deliver(MempoolTransaction::from_rpc(&mock_tx(hash), 0).unwrap(), &small);
+        }
+        assert_eq!(shared.buffer.lock().unwrap()[0].hash, "0xwhale2");
+        assert_eq!(shared.stats().dropped, 1);
+
+        // Scanning above a transaction's value discards it rather than keeping it buffered
+        let (shutdown, _) = watch::channel(false);
+        let listener = MempoolListener { shared: Arc::new(shared), shutdown, runtime: None };
+        assert!(listener.scan(5000.0).is_empty());
+        assert_eq!(listener.stats().filtered, 1);
+        assert!(listener.shared.buffer.lock().unwrap().is_empty());
+        listener.shared.deliver(MempoolTransaction::from_rpc(&mock_tx("0xwhale1"), 0).unwrap(), &small);
+        assert_eq!(listener.scan(100.0)[0].hash, "0xwhale1");
+        assert!(listener.scan(0.0).is_empty());
+        drop(listener);
+
+        assert!(MempoolListener::connect(MempoolConfig { url: "https://node".to_string(), ..Default::default() }).is_err());
+        assert!(MempoolListener::connect(MempoolConfig { url: "wss://127.0.0.1:9".to_string(), ..Default::default() }).is_ok());
     }
 }
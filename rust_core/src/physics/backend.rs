@@ -0,0 +1,528 @@
+// BACKEND.rs - Pluggable Compute Backends for the Physics Engine
+// COMPLEXITY: O(n) per batch; Rayon splits work across the engine's thread pool
+// DETERMINISTIC: ScalarBackend is the reference every other backend is checked against
+
+use crate::physics::obi_columns::{self, ObiColumns, OrderBookColumns};
+use crate::physics::obi_engine::{
+    DepthObiConfig, DepthSnapshot, EngineError, ObiConfig, ObiResult, OrderBookSnapshot,
+    PhysicsEngine, SignalThresholds,
+};
+use crate::physics::tda::TopologicalAnalyzer;
+use std::sync::Arc;
+
+#[cfg(feature = "cuda")]
+use cudarc::driver::CudaDevice;
+#[cfg(feature = "cuda")]
+use std::sync::OnceLock;
+
+/// A compute backend for the engine's batch kernels.
+/// Returning `BackendUnavailable` makes the engine fall back to the Rayon backend.
+pub trait ComputeBackend: Send + Sync {
+    /// Registry name ("scalar", "rayon", "cuda", ...)
+    fn name(&self) -> &'static str;
+
+    /// Whether `obi_batch_fixed` is implemented
+    fn supports_fixed_point(&self) -> bool {
+        false
+    }
+
+    /// Whether any kernel runs on this backend. Backends where every call would
+    /// fall back are never registered by default or auto-selected.
+    fn has_kernels(&self) -> bool {
+        true
+    }
+
+    fn obi_batch(
+        &self,
+        snapshots: &[OrderBookSnapshot],
+        config: &ObiConfig,
+        thresholds: &SignalThresholds,
+    ) -> Result<Vec<ObiResult>, EngineError>;
+
+    fn obi_batch_fixed(
+        &self,
+        _snapshots: &[OrderBookSnapshot],
+        _config: &ObiConfig,
+        _thresholds: &SignalThresholds,
+    ) -> Result<Vec<ObiResult>, EngineError> {
+        Err(EngineError::BackendUnavailable(format!(
+            "{} does not support fixed-point arithmetic",
+            self.name()
+        )))
+    }
+
+    /// Struct-of-arrays OBI; the default is the sequential columnar kernel
+    fn obi_columns(
+        &self,
+        input: OrderBookColumns<'_>,
+        thresholds: &SignalThresholds,
+    ) -> Result<ObiColumns, EngineError> {
+        Ok(obi_columns::calculate_obi_columns(input, thresholds))
+    }
+
+    fn depth_obi_batch(
+        &self,
+        snapshots: &[DepthSnapshot],
+        obi_config: &ObiConfig,
+        config: &DepthObiConfig,
+        thresholds: &SignalThresholds,
+    ) -> Result<Vec<ObiResult>, EngineError>;
+
+    fn curvature_batch(&self, snapshots: &[OrderBookSnapshot]) -> Result<Vec<f64>, EngineError>;
+}
+
+/// Single-threaded reference backend
+pub struct ScalarBackend;
+
+impl ComputeBackend for ScalarBackend {
+    fn name(&self) -> &'static str {
+        "scalar"
+    }
+
+    fn supports_fixed_point(&self) -> bool {
+        true
+    }
+
+    fn obi_batch(
+        &self,
+        snapshots: &[OrderBookSnapshot],
+        config: &ObiConfig,
+        thresholds: &SignalThresholds,
+    ) -> Result<Vec<ObiResult>, EngineError> {
+        Ok(snapshots
+            .iter()
+            .map(|snapshot| PhysicsEngine::calculate_single_obi(snapshot, config, thresholds))
+            .collect())
+    }
+
+    fn obi_batch_fixed(
+        &self,
+        snapshots: &[OrderBookSnapshot],
+        config: &ObiConfig,
+        thresholds: &SignalThresholds,
+    ) -> Result<Vec<ObiResult>, EngineError> {
+        snapshots
+            .iter()
+            .enumerate()
+            .map(|(index, snapshot)| PhysicsEngine::fixed_or_overflow(index, snapshot, config, thresholds))
+            .collect()
+    }
+
+    fn depth_obi_batch(
+        &self,
+        snapshots: &[DepthSnapshot],
+        obi_config: &ObiConfig,
+        config: &DepthObiConfig,
+        thresholds: &SignalThresholds,
+    ) -> Result<Vec<ObiResult>, EngineError> {
+        Ok(snapshots
+            .iter()
+            .map(|snapshot| PhysicsEngine::calculate_depth_obi(snapshot, obi_config, config, thresholds))
+            .collect())
+    }
+
+    fn curvature_batch(&self, snapshots: &[OrderBookSnapshot]) -> Result<Vec<f64>, EngineError> {
+        Ok(snapshots
+            .iter()
+            .map(TopologicalAnalyzer::snapshot_curvature)
+            .collect())
+    }
+}
+
+/// Data-parallel CPU backend (runs inside the engine's Rayon pool)
+pub struct RayonBackend;
+
+impl ComputeBackend for RayonBackend {
+    fn name(&self) -> &'static str {
+        "rayon"
+    }
+
+    fn supports_fixed_point(&self) -> bool {
+        true
+    }
+
+    fn obi_batch(
+        &self,
+        snapshots: &[OrderBookSnapshot],
+        config: &ObiConfig,
+        thresholds: &SignalThresholds,
+    ) -> Result<Vec<ObiResult>, EngineError> {
+        Ok(PhysicsEngine::calculate_obi_batch_cpu(snapshots, config, thresholds))
+    }
+
+    fn obi_batch_fixed(
+        &self,
+        snapshots: &[OrderBookSnapshot],
+        config: &ObiConfig,
+        thresholds: &SignalThresholds,
+    ) -> Result<Vec<ObiResult>, EngineError> {
+        PhysicsEngine::calculate_obi_batch_fixed(snapshots, config, thresholds)
+    }
+
+    fn obi_columns(
+        &self,
+        input: OrderBookColumns<'_>,
+        thresholds: &SignalThresholds,
+    ) -> Result<ObiColumns, EngineError> {
+        Ok(obi_columns::calculate_obi_columns_par(input, thresholds))
+    }
+
+    fn depth_obi_batch(
+        &self,
+        snapshots: &[DepthSnapshot],
+        obi_config: &ObiConfig,
+        config: &DepthObiConfig,
+        thresholds: &SignalThresholds,
+    ) -> Result<Vec<ObiResult>, EngineError> {
+        Ok(PhysicsEngine::calculate_depth_obi_batch_cpu(
+            snapshots, obi_config, config, thresholds,
+        ))
+    }
+
+    fn curvature_batch(&self, snapshots: &[OrderBookSnapshot]) -> Result<Vec<f64>, EngineError> {
+        Ok(TopologicalAnalyzer::calculate_curvature(snapshots))
+    }
+}
+
+/// CUDA backend (device 0). Kernels not yet ported report an error before any
+/// device transfer, so an explicitly selected CUDA engine falls back to Rayon.
+#[cfg(feature = "cuda")]
+pub struct CudaBackend {
+    #[allow(dead_code)] // Held for the kernels once they are ported
+    device: Arc<CudaDevice>,
+}
+
+#[cfg(feature = "cuda")]
+impl CudaBackend {
+    /// Flip once `obi_batch` launches a real kernel (requires .cu file compilation)
+    pub const KERNELS_PORTED: bool = false;
+
+    pub fn new(ordinal: usize) -> Result<Self, EngineError> {
+        let device = CudaDevice::new(ordinal)
+            .map_err(|e| EngineError::BackendUnavailable(format!("CUDA init failed: {:?}", e)))?;
+        println!(
+            "[PHYSICS_ENGINE] ✓ CUDA Device Initialized: {}",
+            device.name().unwrap_or_else(|_| "unknown".to_string())
+        );
+        Ok(CudaBackend { device })
+    }
+
+    /// Process-wide device-0 backend, initialised on first use and cached
+    pub fn shared() -> Result<Arc<CudaBackend>, EngineError> {
+        static DEVICE: OnceLock<Result<Arc<CudaBackend>, EngineError>> = OnceLock::new();
+        DEVICE
+            .get_or_init(|| {
+                CudaBackend::new(0)
+                    .map(Arc::new)
+                    .inspect_err(|e| eprintln!("[PHYSICS_ENGINE] ⚠ {}", e))
+            })
+            .clone()
+    }
+
+    fn unsupported(kernel: &str) -> EngineError {
+        EngineError::BackendUnavailable(format!("cuda: {} kernel not implemented", kernel))
+    }
+}
+
+#[cfg(feature = "cuda")]
+impl ComputeBackend for CudaBackend {
+    fn name(&self) -> &'static str {
+        "cuda"
+    }
+
+    fn has_kernels(&self) -> bool {
+        Self::KERNELS_PORTED
+    }
+
+    /// Batch OBI calculation (GPU with CUDA)
+    fn obi_batch(
+        &self,
+        _snapshots: &[OrderBookSnapshot],
+        _config: &ObiConfig,
+        _thresholds: &SignalThresholds,
+    ) -> Result<Vec<ObiResult>, EngineError> {
+        // TODO: Copy the SoA columns to the device and launch the OBI kernel here.
+        // Until then reject before any H2D transfer.
+        Err(Self::unsupported("obi"))
+    }
+
+    fn obi_columns(
+        &self,
+        _input: OrderBookColumns<'_>,
+        _thresholds: &SignalThresholds,
+    ) -> Result<ObiColumns, EngineError> {
+        Err(Self::unsupported("obi_columns"))
+    }
+
+    fn depth_obi_batch(
+        &self,
+        _snapshots: &[DepthSnapshot],
+        _obi_config: &ObiConfig,
+        _config: &DepthObiConfig,
+        _thresholds: &SignalThresholds,
+    ) -> Result<Vec<ObiResult>, EngineError> {
+        Err(Self::unsupported("depth_obi"))
+    }
+
+    fn curvature_batch(&self, _snapshots: &[OrderBookSnapshot]) -> Result<Vec<f64>, EngineError> {
+        Err(Self::unsupported("curvature"))
+    }
+}
+
+/// Named set of backends an engine can select from
+pub struct BackendRegistry {
+    backends: Vec<Arc<dyn ComputeBackend>>,
+}
+
+impl BackendRegistry {
+    /// Empty registry (register backends explicitly)
+    pub fn new() -> Self {
+        BackendRegistry { backends: vec![] }
+    }
+
+    /// scalar + rayon, plus cuda when built with the feature, its kernels are ported
+    /// and a device is present (the device is initialised once per process)
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        registry.register(Arc::new(ScalarBackend));
+        registry.register(Arc::new(RayonBackend));
+
+        #[cfg(feature = "cuda")]
+        if CudaBackend::KERNELS_PORTED {
+            if let Ok(cuda) = CudaBackend::shared() {
+                registry.register(cuda);
+            }
+        }
+
+        registry
+    }
+
+    /// Add a backend; replaces any backend registered under the same name
+    pub fn register(&mut self, backend: Arc<dyn ComputeBackend>) {
+        self.backends.retain(|b| b.name() != backend.name());
+        self.backends.push(backend);
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn ComputeBackend>> {
+        self.backends
+            .iter()
+            .find(|b| b.name().eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    /// Registered backend names, in registration order
+    pub fn names(&self) -> Vec<&'static str> {
+        self.backends.iter().map(|b| b.name()).collect()
+    }
+}
+
+impl Default for BackendRegistry {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::obi_engine::{EngineConfig, PriceLevel};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_snapshots(n: usize) -> Vec<OrderBookSnapshot> {
+        let mut rng = StdRng::seed_from_u64(42);
+        (0..n)
+            .map(|i| {
+                let mid = rng.gen_range(10.0..50_000.0);
+                let half_spread = rng.gen_range(0.0..mid * 0.01);
+                OrderBookSnapshot {
+                    timestamp: i as u64,
+                    bid_volume: rng.gen_range(0.0..1_000.0),
+                    ask_volume: rng.gen_range(0.0..1_000.0),
+                    bid_price: mid - half_spread,
+                    ask_price: mid + half_spread,
+                    last_trade_price: Some(mid + rng.gen_range(-half_spread..=half_spread)),
+                    symbol: None,
+                    venue: None,
+                }
+            })
+            .collect()
+    }
+
+    fn random_depth(n: usize) -> Vec<DepthSnapshot> {
+        let mut rng = StdRng::seed_from_u64(7);
+        (0..n)
+            .map(|i| {
+                let mid = rng.gen_range(10.0..50_000.0);
+                let tick = mid * 1e-4;
+                let side = |rng: &mut StdRng, sign: f64| -> Vec<PriceLevel> {
+                    (1..=5)
+                        .map(|k| PriceLevel {
+                            price: mid + sign * k as f64 * tick,
+                            volume: rng.gen_range(0.0..500.0),
+                        })
+                        .collect()
+                };
+                DepthSnapshot {
+                    timestamp: i as u64,
+                    bids: side(&mut rng, -1.0),
+                    asks: side(&mut rng, 1.0),
+                    last_trade_price: None,
+                    symbol: None,
+                    venue: None,
+                }
+            })
+            .collect()
+    }
+
+    fn assert_same(reference: &[ObiResult], candidate: &[ObiResult]) {
+        assert_eq!(reference.len(), candidate.len());
+        for (a, b) in reference.iter().zip(candidate) {
+            assert_eq!(a.timestamp, b.timestamp);
+            assert_eq!(a.obi.to_bits(), b.obi.to_bits());
+            assert_eq!(a.entropy.to_bits(), b.entropy.to_bits());
+            assert_eq!(a.relative_spread.to_bits(), b.relative_spread.to_bits());
+            assert_eq!(a.signal, b.signal);
+            assert_eq!(a.weighted_mid.to_bits(), b.weighted_mid.to_bits());
+            assert_eq!(a.fixed, b.fixed);
+        }
+    }
+
+    #[test]
+    fn test_backends_match_scalar_reference() {
+        let snapshots = random_snapshots(2_000);
+        let depth = random_depth(500);
+        let config = ObiConfig::default();
+        let depth_config = DepthObiConfig::default();
+        let thresholds = SignalThresholds::default();
+
+        let reference = ScalarBackend;
+        let registry = BackendRegistry::with_defaults();
+
+        for name in registry.names() {
+            let backend = registry.get(name).unwrap();
+
+            if let Ok(results) = backend.obi_batch(&snapshots, &config, &thresholds) {
+                assert_same(&reference.obi_batch(&snapshots, &config, &thresholds).unwrap(), &results);
+            }
+            if backend.supports_fixed_point() {
+                assert_same(
+                    &reference.obi_batch_fixed(&snapshots, &config, &thresholds).unwrap(),
+                    &backend.obi_batch_fixed(&snapshots, &config, &thresholds).unwrap(),
+                );
+            }
+            if let Ok(results) = backend.depth_obi_batch(&depth, &config, &depth_config, &thresholds) {
+                assert_same(
+                    &reference.depth_obi_batch(&depth, &config, &depth_config, &thresholds).unwrap(),
+                    &results,
+                );
+            }
+            if let Ok(curvature) = backend.curvature_batch(&snapshots) {
+                assert_eq!(reference.curvature_batch(&snapshots).unwrap(), curvature);
+            }
+        }
+    }
+
+    /// Backend that counts as "new": registered without touching engine call sites
+    struct EchoBackend;
+
+    impl ComputeBackend for EchoBackend {
+        fn name(&self) -> &'static str {
+            "echo"
+        }
+
+        fn obi_batch(
+            &self,
+            snapshots: &[OrderBookSnapshot],
+            config: &ObiConfig,
+            thresholds: &SignalThresholds,
+        ) -> Result<Vec<ObiResult>, EngineError> {
+            ScalarBackend.obi_batch(snapshots, config, thresholds)
+        }
+
+        fn depth_obi_batch(
+            &self,
+            _snapshots: &[DepthSnapshot],
+            _obi_config: &ObiConfig,
+            _config: &DepthObiConfig,
+            _thresholds: &SignalThresholds,
+        ) -> Result<Vec<ObiResult>, EngineError> {
+            Err(EngineError::BackendUnavailable("echo: depth".to_string()))
+        }
+
+        fn curvature_batch(&self, _snapshots: &[OrderBookSnapshot]) -> Result<Vec<f64>, EngineError> {
+            Err(EngineError::BackendUnavailable("echo: curvature".to_string()))
+        }
+    }
+
+    /// Registered under "cuda" but with no ported kernels
+    struct HollowBackend;
+
+    impl ComputeBackend for HollowBackend {
+        fn name(&self) -> &'static str {
+            "cuda"
+        }
+
+        fn has_kernels(&self) -> bool {
+            false
+        }
+
+        fn obi_batch(
+            &self,
+            _snapshots: &[OrderBookSnapshot],
+            _config: &ObiConfig,
+            _thresholds: &SignalThresholds,
+        ) -> Result<Vec<ObiResult>, EngineError> {
+            Err(EngineError::BackendUnavailable("hollow: obi".to_string()))
+        }
+
+        fn depth_obi_batch(
+            &self,
+            _snapshots: &[DepthSnapshot],
+            _obi_config: &ObiConfig,
+            _config: &DepthObiConfig,
+            _thresholds: &SignalThresholds,
+        ) -> Result<Vec<ObiResult>, EngineError> {
+            Err(EngineError::BackendUnavailable("hollow: depth".to_string()))
+        }
+
+        fn curvature_batch(&self, _snapshots: &[OrderBookSnapshot]) -> Result<Vec<f64>, EngineError> {
+            Err(EngineError::BackendUnavailable("hollow: curvature".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_registry_selects_and_reports_backend() {
+        let mut registry = BackendRegistry::with_defaults();
+        registry.register(Arc::new(EchoBackend));
+        assert!(registry.names().starts_with(&["scalar", "rayon"]));
+
+        let config = |backend: &str| EngineConfig {
+            backend: backend.to_string(),
+            ..Default::default()
+        };
+
+        let engine = PhysicsEngine::with_registry(config("echo"), &registry).unwrap();
+        assert_eq!(engine.backend_name(), "echo");
+
+        // Unsupported kernels fall back to Rayon instead of failing
+        let depth = random_depth(10);
+        let results = engine
+            .calculate_depth_obi_batch(&depth, &ObiConfig::default(), &DepthObiConfig::default())
+            .unwrap();
+        assert_eq!(results.len(), 10);
+
+        let engine = PhysicsEngine::with_registry(config("auto"), &registry).unwrap();
+        assert!(["rayon", "cuda"].contains(&engine.backend_name()));
+
+        // A backend without kernels is never auto-selected
+        registry.register(Arc::new(HollowBackend));
+        let engine = PhysicsEngine::with_registry(config("auto"), &registry).unwrap();
+        assert_eq!(engine.backend_name(), "rayon");
+
+        assert!(matches!(
+            PhysicsEngine::with_registry(config("tpu"), &registry),
+            Err(EngineError::BackendUnavailable(_))
+        ));
+    }
+}
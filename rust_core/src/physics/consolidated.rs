@@ -0,0 +1,298 @@
+// CONSOLIDATED.rs - Cross-Venue Consolidated Book (NBBO) and Aggregate Imbalance
+// COMPLEXITY: O(L log L) per consolidation (L = total levels across venues), O(V^2) arbitrage scan
+// DETERMINISTIC: Venues merged in name order, levels keyed by integer tick index
+
+use crate::physics::obi_engine::{DepthSnapshot, EngineError, ObiResult, PriceLevel};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// One venue's contribution to a consolidated level
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VenueVolume {
+    pub venue: String,
+    pub volume: f64,
+}
+
+/// Consolidated price level with venue attribution
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConsolidatedLevel {
+    pub price: f64,
+    pub volume: f64,              // Sum across venues
+    pub venues: Vec<VenueVolume>, // Sorted by venue name
+}
+
+/// Cross-venue market state at the NBBO
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketState {
+    Normal,  // best bid < best ask
+    Locked,  // best bid == best ask on different venues
+    Crossed, // best bid > best ask on different venues
+}
+
+impl MarketState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MarketState::Normal => "NORMAL",
+            MarketState::Locked => "LOCKED",
+            MarketState::Crossed => "CROSSED",
+        }
+    }
+}
+
+/// Buy on `buy_venue` at its ask, sell on `sell_venue` at its bid
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArbitrageCandidate {
+    pub buy_venue: String,
+    pub sell_venue: String,
+    pub ask: f64,
+    pub bid: f64,
+    pub edge: f64,     // bid - ask (0 when locked)
+    pub edge_bps: f64, // edge / mid * 10_000
+    pub volume: f64,   // min(top bid volume, top ask volume)
+}
+
+/// Consolidated book across venues (bids best-first descending, asks ascending)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConsolidatedBook {
+    pub timestamp: u64, // Newest venue timestamp
+    pub symbol: Option<String>,
+    pub bids: Vec<ConsolidatedLevel>,
+    pub asks: Vec<ConsolidatedLevel>,
+    pub venue_count: usize,
+}
+
+impl ConsolidatedBook {
+    /// Collapse to a plain depth snapshot (venue attribution dropped)
+    pub fn to_depth_snapshot(&self) -> DepthSnapshot {
+        let strip = |levels: &[ConsolidatedLevel]| -> Vec<PriceLevel> {
+            levels
+                .iter()
+                .map(|level| PriceLevel {
+                    price: level.price,
+                    volume: level.volume,
+                })
+                .collect()
+        };
+
+        DepthSnapshot {
+            timestamp: self.timestamp,
+            bids: strip(&self.bids),
+            asks: strip(&self.asks),
+            last_trade_price: None,
+            symbol: self.symbol.clone(),
+            venue: None,
+        }
+    }
+}
+
+/// NBBO, consolidated OBI/microprice and cross-venue arbitrage flags
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConsolidatedResult {
+    pub book: ConsolidatedBook,
+    pub obi: ObiResult, // Depth-weighted OBI and microprice on the consolidated book
+    pub state: MarketState,
+    pub arbitrage: Vec<ArbitrageCandidate>, // Best edge first
+}
+
+/// Merge venue-tagged depth snapshots into one book.
+/// Prices are bucketed to `tick_size` so equal prices from different feeds share a level.
+/// Books tagged with different symbols are rejected; untagged books join any symbol.
+pub fn consolidate(books: &[DepthSnapshot], tick_size: f64) -> Result<ConsolidatedBook, EngineError> {
+    if tick_size <= 0.0 || !tick_size.is_finite() {
+        return Err(EngineError::InvalidConfig(format!("tick_size must be positive, got {}", tick_size)));
+    }
+
+    let symbol = books.iter().find_map(|b| b.symbol.clone());
+    for (index, book) in books.iter().enumerate() {
+        if let (Some(expected), Some(found)) = (&symbol, &book.symbol) {
+            if expected != found {
+                return Err(EngineError::SymbolMismatch {
+                    index,
+                    expected: expected.clone(),
+                    found: found.clone(),
+                });
+            }
+        }
+    }
+
+    let mut by_venue: BTreeMap<&str, &DepthSnapshot> = BTreeMap::new();
+    for book in books {
+        let venue = book
+            .venue
+            .as_deref()
+            .ok_or_else(|| EngineError::InvalidConfig("consolidation requires venue-tagged books".to_string()))?;
+        if by_venue.insert(venue, book).is_some() {
+            return Err(EngineError::InvalidConfig(format!("duplicate book for venue '{}'", venue)));
+        }
+    }
+
+    let merge = |side: fn(&DepthSnapshot) -> &[PriceLevel]| -> BTreeMap<i64, ConsolidatedLevel> {
+        let mut levels: BTreeMap<i64, ConsolidatedLevel> = BTreeMap::new();
+        for (venue, book) in &by_venue {
+            for level in side(book).iter().filter(|l| l.volume > 0.0) {
+                let tick = (level.price / tick_size).round() as i64;
+                let entry = levels.entry(tick).or_insert_with(|| ConsolidatedLevel {
+                    price: level.price,
+                    volume: 0.0,
+                    venues: Vec::new(),
+                });
+                entry.volume += level.volume;
+                match entry.venues.iter_mut().find(|v| v.venue == *venue) {
+                    Some(existing) => existing.volume += level.volume,
+                    None => entry.venues.push(VenueVolume {
+                        venue: venue.to_string(),
+                        volume: level.volume,
+                    }),
+                }
+            }
+        }
+        levels
+    };
+
+    Ok(ConsolidatedBook {
+        timestamp: books.iter().map(|b| b.timestamp).max().unwrap_or(0),
+        symbol,
+        bids: merge(|b| &b.bids).into_values().rev().collect(),
+        asks: merge(|b| &b.asks).into_values().collect(),
+        venue_count: by_venue.len(),
+    })
+}
+
+/// Every (sell venue bid >= buy venue ask, compared in ticks) pair across different venues, best edge first
+pub fn arbitrage_candidates(books: &[DepthSnapshot], tick_size: f64) -> Vec<ArbitrageCandidate> {
+    let ticks = |price: f64| (price / tick_size).round() as i64;
+
+    let mut candidates = Vec::new();
+
+    for seller in books {
+        for buyer in books {
+            let (Some(sell_venue), Some(buy_venue)) = (&seller.venue, &buyer.venue) else {
+                continue;
+            };
+            if sell_venue == buy_venue {
+                continue;
+            }
+            let (Some(bid), Some(ask)) = (seller.bids.first(), buyer.asks.first()) else {
+                continue;
+            };
+
+            let tick_edge = ticks(bid.price) - ticks(ask.price);
+            if tick_edge >= 0 {
+                let mid = (bid.price + ask.price) / 2.0;
+                let edge = if tick_edge > 0 { bid.price - ask.price } else { 0.0 };
+                candidates.push(ArbitrageCandidate {
+                    buy_venue: buy_venue.clone(),
+                    sell_venue: sell_venue.clone(),
+                    ask: ask.price,
+                    bid: bid.price,
+                    edge,
+                    edge_bps: if mid > 0.0 { edge / mid * 10_000.0 } else { 0.0 },
+                    volume: bid.volume.min(ask.volume),
+                });
+            }
+        }
+    }
+
+    candidates.sort_by(|a, b| {
+        b.edge
+            .total_cmp(&a.edge)
+            .then_with(|| a.buy_venue.cmp(&b.buy_venue))
+            .then_with(|| a.sell_venue.cmp(&b.sell_venue))
+    });
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::obi_engine::{ArithmeticMode, DepthObiConfig, EngineConfig, ObiConfig, PhysicsEngine};
+
+    fn venue_book(venue: &str, bids: &[(f64, f64)], asks: &[(f64, f64)]) -> DepthSnapshot {
+        let levels = |side: &[(f64, f64)]| side.iter().map(|&(price, volume)| PriceLevel { price, volume }).collect();
+        DepthSnapshot {
+            timestamp: 1,
+            bids: levels(bids),
+            asks: levels(asks),
+            last_trade_price: None,
+            symbol: Some("BTC-USD".to_string()),
+            venue: Some(venue.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_consolidated_nbbo_and_arbitrage_flags() {
+        let engine = PhysicsEngine::new(EngineConfig::default()).unwrap();
+        let obi_config = ObiConfig::default();
+        let depth = DepthObiConfig { levels: 10, decay: 0.0 };
+
+        // Normal: NBBO is 100.01 (kraken) / 100.02 (binance + coinbase)
+        let books = vec![
+            venue_book("binance", &[(100.00, 5.0)], &[(100.02, 2.0), (100.03, 1.0)]),
+            venue_book("coinbase", &[(100.00, 3.0)], &[(100.02, 1.0)]),
+            venue_book("kraken", &[(100.01, 1.0)], &[(100.04, 4.0)]),
+        ];
+        let result = engine.calculate_consolidated_obi(&books, &obi_config, &depth).unwrap();
+
+        assert_eq!(result.state, MarketState::Normal);
+        assert!(result.arbitrage.is_empty());
+        assert_eq!(result.book.venue_count, 3);
+        assert_eq!(result.book.bids[0].price, 100.01);
+        assert_eq!(result.book.asks[0].price, 100.02);
+
+        // Equal prices across venues merge into one attributed level
+        let best_ask = &result.book.asks[0];
+        assert_eq!(best_ask.volume, 3.0);
+        let venues: Vec<&str> = best_ask.venues.iter().map(|v| v.venue.as_str()).collect();
+        assert_eq!(venues, vec!["binance", "coinbase"]);
+        assert_eq!(result.book.bids[1].volume, 8.0);
+
+        // Flat-weighted consolidated OBI: bids 9, asks 8
+        assert!((result.obi.obi - 1.0 / 17.0).abs() < 1e-12);
+        // Microprice on the NBBO: (100.01 * 3 + 100.02 * 1) / 4
+        assert!((result.obi.microprice - 100.0125).abs() < 1e-9);
+
+        // Crossed: kraken bids above binance's ask
+        let crossed = vec![
+            venue_book("binance", &[(100.00, 5.0)], &[(100.02, 2.0)]),
+            venue_book("kraken", &[(100.05, 1.5)], &[(100.07, 4.0)]),
+        ];
+        let result = engine.calculate_consolidated_obi(&crossed, &obi_config, &depth).unwrap();
+        assert_eq!(result.state, MarketState::Crossed);
+        assert_eq!(result.arbitrage.len(), 1);
+        let arb = &result.arbitrage[0];
+        assert_eq!((arb.buy_venue.as_str(), arb.sell_venue.as_str()), ("binance", "kraken"));
+        assert!((arb.edge - 0.03).abs() < 1e-9);
+        assert_eq!(arb.volume, 1.5);
+
+        // Locked: bid on one venue equals ask on another
+        let locked = vec![
+            venue_book("binance", &[(100.00, 5.0)], &[(100.02, 2.0)]),
+            venue_book("kraken", &[(100.02, 1.0)], &[(100.04, 4.0)]),
+        ];
+        let result = engine.calculate_consolidated_obi(&locked, &obi_config, &depth).unwrap();
+        assert_eq!(result.state, MarketState::Locked);
+
+        // Untagged or duplicate venues are rejected
+        let mut untagged = books.clone();
+        untagged[0].venue = None;
+        assert!(consolidate(&untagged, 0.01).is_err());
+        assert!(consolidate(&[books[0].clone(), books[0].clone()], 0.01).is_err());
+
+        // Books of different symbols never merge into one NBBO
+        let mut mixed = books.clone();
+        mixed[2].symbol = Some("ETH-USD".to_string());
+        assert!(matches!(
+            consolidate(&mixed, 0.01),
+            Err(EngineError::SymbolMismatch { index: 2, .. })
+        ));
+        mixed[2].symbol = None;
+        assert_eq!(consolidate(&mixed, 0.01).unwrap().symbol.as_deref(), Some("BTC-USD"));
+
+        // Depth OBI is float-only, consolidated included
+        let fixed = PhysicsEngine::new(EngineConfig { arithmetic: ArithmeticMode::FixedPoint, ..Default::default() }).unwrap();
+        assert!(matches!(
+            fixed.calculate_consolidated_obi(&books, &obi_config, &depth),
+            Err(EngineError::InvalidConfig(_))
+        ));
+    }
+}
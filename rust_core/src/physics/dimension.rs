@@ -0,0 +1,201 @@
+// DIMENSION.rs - Intrinsic Dimension Estimators (Levina-Bickel MLE, Grassberger-Procaccia, Two-NN)
+// COMPLEXITY: O(n^2 log n) per point cloud (all-pairs distances, sorted per point)
+// DETERMINISTIC: Pure functions of the distance matrix
+
+use crate::physics::obi_engine::EngineError;
+use crate::physics::persistence;
+use serde::{Deserialize, Serialize};
+
+/// Estimator parameters
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct DimensionConfig {
+    pub k: usize,                // Neighbours used by the Levina-Bickel MLE
+    pub gp_low_quantile: f64,    // Scaling range of the correlation integral, as quantiles
+    pub gp_high_quantile: f64,   // of the pairwise distances
+    pub gp_radii: usize,         // Log-spaced radii fitted across the scaling range
+}
+
+impl Default for DimensionConfig {
+    fn default() -> Self {
+        DimensionConfig {
+            k: 10,
+            gp_low_quantile: 0.05,
+            gp_high_quantile: 0.5,
+            gp_radii: 12,
+        }
+    }
+}
+
+impl DimensionConfig {
+    pub fn validate(&self) -> Result<(), EngineError> {
+        if self.k < 2 || self.gp_radii < 2 {
+            return Err(EngineError::InvalidConfig(format!(
+                "dimension estimators need k >= 2 and gp_radii >= 2, got {} / {}",
+                self.k, self.gp_radii
+            )));
+        }
+        let (low, high) = (self.gp_low_quantile, self.gp_high_quantile);
+        if !(0.0 <= low && low < high && high <= 1.0) {
+            return Err(EngineError::InvalidConfig(format!(
+                "correlation quantiles must satisfy 0 <= low < high <= 1, got {} / {}",
+                low, high
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Intrinsic dimension of one point cloud by each estimator (None when undefined)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct DimensionEstimate {
+    pub points: usize,
+    pub mle: Option<f64>,         // Levina-Bickel with the MacKay-Ghahramani pooling
+    pub correlation: Option<f64>, // Grassberger-Procaccia slope of log C(r) vs log r
+    pub two_nn: Option<f64>,      // Facco et al. MLE from second/first neighbour ratios
+}
+
+/// Distances from each point to every other point, ascending (zero-distance duplicates dropped)
+fn sorted_neighbours(distances: &[f64], n: usize) -> Vec<Vec<f64>> {
+    (0..n)
+        .map(|i| {
+            let mut row: Vec<f64> = (0..n)
+                .filter(|&j| j != i)
+                .map(|j| distances[i * n + j])
+                .filter(|&d| d > 0.0)
+                .collect();
+            row.sort_by(f64::total_cmp);
+            row
+        })
+        .collect()
+}
+
+/// Levina-Bickel: m_k(x) = [(1/(k-1)) Σ_j ln(T_k / T_j)]^-1, pooled as the inverse of
+/// the mean inverse over points (MacKay & Ghahramani)
+fn levina_bickel(neighbours: &[Vec<f64>], k: usize) -> Option<f64> {
+    if k < 2 {
+        return None;
+    }
+    let inverses: Vec<f64> = neighbours
+        .iter()
+        .filter(|row| row.len() >= k)
+        .map(|row| row[..k - 1].iter().map(|t| (row[k - 1] / t).ln()).sum::<f64>() / (k - 1) as f64)
+        .collect();
+    let mean = inverses.iter().sum::<f64>() / inverses.len().max(1) as f64;
+    (!inverses.is_empty() && mean > 0.0).then(|| 1.0 / mean)
+}
+
+/// Two-NN: with μ = r2 / r1 Pareto(d)-distributed, d = N / Σ ln μ
+fn two_nn(neighbours: &[Vec<f64>]) -> Option<f64> {
+    let logs: Vec<f64> = neighbours
+        .iter()
+        .filter(|row| row.len() >= 2)
+        .map(|row| (row[1] / row[0]).ln())
+        .collect();
+    let total: f64 = logs.iter().sum();
+    (total > 0.0).then(|| logs.len() as f64 / total)
+}
+
+/// Grassberger-Procaccia: least-squares slope of log C(r) on log r, where C(r) is the
+/// fraction of pairs closer than r, over log-spaced radii between two distance quantiles
+fn correlation_dimension(distances: &[f64], n: usize, config: &DimensionConfig) -> Option<f64> {
+    let mut pairs: Vec<f64> = (0..n)
+        .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+        .map(|(i, j)| distances[i * n + j])
+        .collect();
+    pairs.sort_by(f64::total_cmp);
+    if pairs.len() < 2 || config.gp_radii < 2 {
+        return None;
+    }
+
+    let quantile = |q: f64| pairs[((pairs.len() - 1) as f64 * q).round() as usize];
+    let (low, high) = (quantile(config.gp_low_quantile), quantile(config.gp_high_quantile));
+    if !(low > 0.0 && high > low) {
+        return None;
+    }
+
+    let samples: Vec<(f64, f64)> = (0..config.gp_radii)
+        .filter_map(|i| {
+            let r = low * (high / low).powf(i as f64 / (config.gp_radii - 1) as f64);
+            let closer = pairs.partition_point(|&d| d <= r);
+            (closer > 0).then(|| (r.ln(), (closer as f64 / pairs.len() as f64).ln()))
+        })
+        .collect();
+    let m = samples.len() as f64;
+    if samples.len() < 2 {
+        return None;
+    }
+    let mean_x = samples.iter().map(|s| s.0).sum::<f64>() / m;
+    let mean_y = samples.iter().map(|s| s.1).sum::<f64>() / m;
+    let sxx: f64 = samples.iter().map(|s| (s.0 - mean_x).powi(2)).sum();
+    let sxy: f64 = samples.iter().map(|s| (s.0 - mean_x) * (s.1 - mean_y)).sum();
+    (sxx > 0.0).then(|| sxy / sxx)
+}
+
+/// Every estimator on one point cloud
+pub fn estimate_dimension(points: &[Vec<f64>], config: &DimensionConfig) -> DimensionEstimate {
+    let n = points.len();
+    let distances = persistence::distance_matrix(points);
+    let neighbours = sorted_neighbours(&distances, n);
+    DimensionEstimate {
+        points: n,
+        mle: levina_bickel(&neighbours, config.k),
+        correlation: correlation_dimension(&distances, n, config),
+        two_nn: two_nn(&neighbours),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic uniform sample of the unit cube (xorshift)
+    fn uniform(n: usize, dim: usize, seed: u64) -> Vec<Vec<f64>> {
+        let mut state = seed;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        };
+        (0..n).map(|_| (0..dim).map(|_| next()).collect()).collect()
+    }
+
+    #[test]
+    fn test_estimators_recover_embedded_dimension() {
+        let config = DimensionConfig::default();
+
+        // A 2-D sheet embedded linearly in 4-D
+        let sheet: Vec<Vec<f64>> = uniform(400, 2, 7)
+            .into_iter()
+            .map(|p| vec![p[0], p[1], p[0] + p[1], p[0] - 2.0 * p[1]])
+            .collect();
+        let est = estimate_dimension(&sheet, &config);
+        assert_eq!(est.points, 400);
+        for d in [est.mle, est.correlation, est.two_nn] {
+            let d = d.unwrap();
+            assert!((d - 2.0).abs() < 0.4, "sheet estimate {}", d);
+        }
+
+        // A helix is a curve: dimension ≈ 1
+        let helix: Vec<Vec<f64>> = uniform(300, 1, 3)
+            .into_iter()
+            .map(|u| {
+                let a = u[0] * 15.0;
+                vec![a.cos(), a.sin(), a]
+            })
+            .collect();
+        let est = estimate_dimension(&helix, &config);
+        for d in [est.mle, est.correlation, est.two_nn] {
+            let d = d.unwrap();
+            assert!((d - 1.0).abs() < 0.25, "helix estimate {}", d);
+        }
+
+        // A filled 3-cube
+        let cube = estimate_dimension(&uniform(500, 3, 11), &config);
+        assert!(cube.mle.unwrap() > 2.4 && cube.two_nn.unwrap() > 2.4);
+
+        // Degenerate clouds have no estimate
+        let est = estimate_dimension(&[vec![0.0], vec![0.0]], &config);
+        assert!(est.mle.is_none() && est.correlation.is_none() && est.two_nn.is_none());
+    }
+}
@@ -0,0 +1,160 @@
+// ENTROPY.rs - Information-Theoretic Measures of Order Book Depth
+// COMPLEXITY: O(L) per book (L = levels in the distribution)
+// DETERMINISTIC: Sequential sums in level order (f64, last-ulp platform dependent via ln);
+//                binary_entropy_fixed is integer-only
+
+use crate::physics::fixed::{Fixed, FIXED_SCALE};
+use crate::physics::obi_engine::{DepthSnapshot, OrderBookSnapshot, PriceLevel};
+
+/// Additive smoothing for KL divergence so empty levels do not produce infinities
+pub const KL_EPSILON: f64 = 1e-9;
+
+/// Normalise non-negative volumes into a probability distribution.
+/// Non-finite and negative volumes count as zero; an empty book gives all zeros.
+pub fn normalize(volumes: &[f64]) -> Vec<f64> {
+    let clean: Vec<f64> = volumes
+        .iter()
+        .map(|&v| if v.is_finite() && v > 0.0 { v } else { 0.0 })
+        .collect();
+    let total: f64 = clean.iter().sum();
+
+    if total > 0.0 {
+        clean.iter().map(|v| v / total).collect()
+    } else {
+        clean
+    }
+}
+
+/// Shannon entropy H(P) = -Σ p log2 p, in bits (0 for an empty distribution)
+pub fn shannon_entropy(p: &[f64]) -> f64 {
+    -p.iter().filter(|&&x| x > 0.0).map(|&x| x * x.log2()).sum::<f64>()
+}
+
+/// Rényi entropy of order alpha, H_α(P) = log2(Σ p^α) / (1 - α), in bits.
+/// α = 1 is the Shannon limit; α = 2 is collision entropy.
+pub fn renyi_entropy(p: &[f64], alpha: f64) -> f64 {
+    if (alpha - 1.0).abs() < 1e-12 {
+        return shannon_entropy(p);
+    }
+
+    let sum: f64 = p.iter().filter(|&&x| x > 0.0).map(|&x| x.powf(alpha)).sum();
+    if sum > 0.0 {
+        sum.log2() / (1.0 - alpha)
+    } else {
+        0.0
+    }
+}
+
+/// KL divergence D(P || Q) = Σ p log2(p / q) in bits, with `KL_EPSILON` smoothing.
+/// Distributions of different length are zero-padded to the longer one.
+pub fn kl_divergence(p: &[f64], q: &[f64]) -> f64 {
+    let n = p.len().max(q.len());
+    let smooth = |d: &[f64]| -> Vec<f64> {
+        let total = 1.0 + KL_EPSILON * n as f64;
+        (0..n)
+            .map(|i| (d.get(i).copied().unwrap_or(0.0) + KL_EPSILON) / total)
+            .collect()
+    };
+    let (p, q) = (smooth(p), smooth(q));
+
+    p.iter()
+        .zip(&q)
+        .map(|(&pi, &qi)| pi * (pi / qi).log2())
+        .sum::<f64>()
+        .max(0.0)
+}
+
+/// Shannon entropy of the two-level [bid, ask] distribution without allocating.
+/// Identical to `shannon_entropy(&normalize(&[bid, ask]))`.
+#[inline]
+pub fn binary_entropy(bid_volume: f64, ask_volume: f64) -> f64 {
+    let clean = |v: f64| if v.is_finite() && v > 0.0 { v } else { 0.0 };
+    let (bid, ask) = (clean(bid_volume), clean(ask_volume));
+    let total = bid + ask;
+    if total <= 0.0 {
+        return 0.0;
+    }
+
+    let term = |x: f64| if x > 0.0 { x * x.log2() } else { 0.0 };
+    -(term(bid / total) + term(ask / total))
+}
+
+/// Integer-only `binary_entropy` over raw fixed-point volumes (see `fixed::quantize_wide`),
+/// bit-identical everywhere. Each term p * log2(p) is rounded to 1e-9 before summing.
+pub fn binary_entropy_fixed(bid_volume: i128, ask_volume: i128) -> Fixed {
+    let (bid, ask) = (bid_volume.max(0), ask_volume.max(0));
+    let total = bid + ask;
+    if total <= 0 {
+        return Fixed::ZERO;
+    }
+
+    let scale = FIXED_SCALE as i128;
+    let term = |x: i128| -> i128 {
+        if x == 0 {
+            return 0;
+        }
+        let p = Fixed::from_ratio(x, total).raw() as i128;
+        let log = Fixed::log2_ratio(x as u128, total as u128).raw() as i128;
+        Fixed::from_ratio(p * log, scale * scale).raw() as i128
+    };
+    Fixed(-(term(bid) + term(ask)) as i64)
+}
+
+/// Top-of-book volume distribution: [bid, ask]
+pub fn top_of_book_distribution(snapshot: &OrderBookSnapshot) -> Vec<f64> {
+    normalize(&[snapshot.bid_volume, snapshot.ask_volume])
+}
+
+/// Depth volume distribution over `levels` per side, laid out
+/// [bid_0 .. bid_{L-1}, ask_0 .. ask_{L-1}] and zero-padded so consecutive books align
+pub fn depth_distribution(snapshot: &DepthSnapshot, levels: usize) -> Vec<f64> {
+    let padded = |book: &[PriceLevel]| -> Vec<f64> { (0..levels).map(|i| book.get(i).map_or(0.0, |l| l.volume)).collect() };
+    let volumes: Vec<f64> = [padded(&snapshot.bids), padded(&snapshot.asks)].concat();
+    normalize(&volumes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entropy_measures() {
+        // Uniform over 4 levels: 2 bits for every Rényi order
+        let uniform = normalize(&[5.0, 5.0, 5.0, 5.0]);
+        assert!((shannon_entropy(&uniform) - 2.0).abs() < 1e-12);
+        assert!((renyi_entropy(&uniform, 2.0) - 2.0).abs() < 1e-12);
+        assert!((renyi_entropy(&uniform, 0.5) - 2.0).abs() < 1e-12);
+
+        // All volume on one level: zero entropy; bad volumes are ignored
+        let point = normalize(&[0.0, 7.0, -3.0, f64::NAN]);
+        assert_eq!(shannon_entropy(&point), 0.0);
+        assert_eq!(shannon_entropy(&normalize(&[0.0, 0.0])), 0.0);
+
+        // Rényi is non-increasing in alpha, and alpha = 1 is Shannon
+        let skewed = normalize(&[8.0, 4.0, 2.0, 1.0]);
+        let h1 = shannon_entropy(&skewed);
+        assert!(renyi_entropy(&skewed, 0.5) > h1);
+        assert!(renyi_entropy(&skewed, 2.0) < h1);
+        assert_eq!(renyi_entropy(&skewed, 1.0), h1);
+
+        // KL: zero for identical books, positive and finite when a level empties
+        assert!(kl_divergence(&skewed, &skewed).abs() < 1e-12);
+        let shifted = normalize(&[0.0, 4.0, 2.0, 9.0]);
+        let kl = kl_divergence(&shifted, &skewed);
+        assert!(kl > 0.0 && kl.is_finite());
+        assert!(kl_divergence(&skewed, &shifted).is_finite());
+
+        for (bid, ask) in [(3.0, 1.0), (0.0, 2.0), (1.0, 1.0), (-1.0, 4.0)] {
+            assert_eq!(binary_entropy(bid, ask), shannon_entropy(&normalize(&[bid, ask])));
+        }
+
+        // Integer-only binary entropy tracks the f64 value to the 1e-9 scale
+        let raw = |v: f64| (v * FIXED_SCALE as f64) as i128;
+        let fixed = binary_entropy_fixed(raw(3.0), raw(1.0));
+        assert!((fixed.to_f64() - binary_entropy(3.0, 1.0)).abs() < 2e-9);
+        assert_eq!(binary_entropy_fixed(raw(1.0), raw(1.0)).raw(), 1_000_000_000);
+        assert_eq!(binary_entropy_fixed(0, raw(2.0)), Fixed::ZERO);
+        // Volumes past the i64 range of Fixed keep their exact ratio
+        assert_eq!(binary_entropy_fixed(raw(3e12), raw(1e12)), fixed);
+    }
+}
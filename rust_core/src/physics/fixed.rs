@@ -0,0 +1,131 @@
+// FIXED.rs - Deterministic Fixed-Point Arithmetic for Ledger-Sealed Results
+// COMPLEXITY: O(1) per operation
+// DETERMINISTIC: Integer-only math, bit-identical on every platform
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Decimal places carried by `Fixed` (1e-9 resolution)
+pub const FIXED_DECIMALS: u32 = 9;
+
+/// Scale factor: 1.0 == FIXED_SCALE
+pub const FIXED_SCALE: i64 = 1_000_000_000;
+
+/// Largest magnitude `quantize_wide` accepts; keeps raw * FIXED_SCALE products inside i128
+pub const FIXED_WIDE_MAX: f64 = 1e18;
+
+/// Input that cannot be represented in fixed-point (non-finite or out of range)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedOverflow(pub f64);
+
+impl fmt::Display for FixedOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not representable in {}-decimal fixed point", self.0, FIXED_DECIMALS)
+    }
+}
+
+impl std::error::Error for FixedOverflow {}
+
+/// Quantize an f64 to a raw i128 at 1e-9 (half away from zero), for volumes and prices
+/// beyond the i64 range of `Fixed` (about ±9.22e9). Errors above `FIXED_WIDE_MAX`.
+pub fn quantize_wide(value: f64) -> Result<i128, FixedOverflow> {
+    if !value.is_finite() || value.abs() > FIXED_WIDE_MAX {
+        return Err(FixedOverflow(value));
+    }
+    Ok((value * FIXED_SCALE as f64).round() as i128)
+}
+
+/// Signed fixed-point number with 9 decimals, stored as a raw i64
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Fixed(pub i64);
+
+impl Fixed {
+    pub const ZERO: Fixed = Fixed(0);
+
+    /// Quantize an f64 to the nearest 1e-9 (half away from zero).
+    /// IEEE-754 multiply and round are correctly rounded, so this is platform independent.
+    /// Non-finite input and values outside the i64 range (about ±9.22e9) are errors.
+    pub fn from_f64(value: f64) -> Result<Fixed, FixedOverflow> {
+        let scaled = (value * FIXED_SCALE as f64).round();
+        // i64::MAX as f64 rounds up to 2^63, which is itself out of range
+        if !scaled.is_finite() || scaled >= i64::MAX as f64 || scaled < i64::MIN as f64 {
+            return Err(FixedOverflow(value));
+        }
+        Ok(Fixed(scaled as i64))
+    }
+
+    /// Exact ratio of two integers, rounded half away from zero
+    pub fn from_ratio(numerator: i128, denominator: i128) -> Fixed {
+        if denominator == 0 {
+            return Fixed::ZERO;
+        }
+        let value = div_round(numerator * FIXED_SCALE as i128, denominator);
+        Fixed(value.clamp(i64::MIN as i128, i64::MAX as i128) as i64)
+    }
+
+    /// Binary logarithm of numerator / denominator (both > 0), integer-only.
+    /// Mantissa bits come from repeated squaring in 62-bit fixed point, truncated at 40 bits.
+    pub fn log2_ratio(mut numerator: u128, mut denominator: u128) -> Fixed {
+        const FRAC: u32 = 62;
+        const BITS: u32 = 40;
+        if numerator == 0 || denominator == 0 {
+            return Fixed::ZERO;
+        }
+
+        // Keep both operands below 2^64 so the shifts below cannot overflow
+        let excess = numerator.max(denominator).ilog2().saturating_sub(63);
+        numerator = (numerator >> excess).max(1);
+        denominator = (denominator >> excess).max(1);
+
+        // Integer part: shift the ratio into [1, 2)
+        let mut exponent = numerator.ilog2() as i128 - denominator.ilog2() as i128;
+        let mut mantissa = if exponent >= 0 {
+            (numerator << FRAC) / (denominator << exponent)
+        } else {
+            (numerator << (FRAC as i128 - exponent)) / denominator
+        };
+        if mantissa < 1 << FRAC {
+            mantissa <<= 1;
+            exponent -= 1;
+        }
+
+        // Fraction: squaring doubles the log, a carry past 2 is the next bit
+        let mut fraction: i128 = 0;
+        for _ in 0..BITS {
+            mantissa = (mantissa * mantissa) >> FRAC;
+            fraction <<= 1;
+            if mantissa >= 2 << FRAC {
+                mantissa >>= 1;
+                fraction |= 1;
+            }
+        }
+
+        Fixed::from_ratio((exponent << BITS) + fraction, 1 << BITS)
+    }
+
+    /// Raw scaled integer (value * FIXED_SCALE)
+    pub fn raw(self) -> i64 {
+        self.0
+    }
+
+    /// Convert back to f64 (correctly rounded, so also deterministic)
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / FIXED_SCALE as f64
+    }
+}
+
+/// Integer division rounded half away from zero
+fn div_round(numerator: i128, denominator: i128) -> i128 {
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+
+    if remainder.abs() * 2 >= denominator.abs() {
+        if (numerator < 0) == (denominator < 0) {
+            quotient + 1
+        } else {
+            quotient - 1
+        }
+    } else {
+        quotient
+    }
+}
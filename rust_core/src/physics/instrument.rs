@@ -0,0 +1,173 @@
+// INSTRUMENT.rs - Symbol/Venue Grouping for Mixed OBI Batches
+// COMPLEXITY: O(n log g) grouping (g = instruments), O(n) statistics
+// DETERMINISTIC: Groups ordered by key, input order preserved within a group
+
+use crate::physics::obi_engine::{ObiResult, OrderBookSnapshot, SignalThresholds};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Instrument identity of a snapshot (None = untagged)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct InstrumentKey {
+    pub symbol: Option<String>,
+    pub venue: Option<String>,
+}
+
+impl InstrumentKey {
+    pub fn of(snapshot: &OrderBookSnapshot) -> Self {
+        InstrumentKey {
+            symbol: snapshot.symbol.clone(),
+            venue: snapshot.venue.clone(),
+        }
+    }
+
+    /// Thresholds for this instrument: "SYMBOL@VENUE", then "SYMBOL", then `default`
+    pub fn thresholds(
+        &self,
+        overrides: &HashMap<String, SignalThresholds>,
+        default: SignalThresholds,
+    ) -> SignalThresholds {
+        let symbol = match &self.symbol {
+            Some(symbol) => symbol,
+            None => return default,
+        };
+
+        self.venue
+            .as_ref()
+            .and_then(|venue| overrides.get(&format!("{}@{}", symbol, venue)))
+            .or_else(|| overrides.get(symbol))
+            .copied()
+            .unwrap_or(default)
+    }
+}
+
+impl fmt::Display for InstrumentKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = self.symbol.as_deref().unwrap_or("*");
+        match &self.venue {
+            Some(venue) => write!(f, "{}@{}", symbol, venue),
+            None => write!(f, "{}", symbol),
+        }
+    }
+}
+
+/// Split a mixed batch into per-instrument input indices (ordered by key)
+pub fn group_indices(snapshots: &[OrderBookSnapshot]) -> BTreeMap<InstrumentKey, Vec<usize>> {
+    let mut groups: BTreeMap<InstrumentKey, Vec<usize>> = BTreeMap::new();
+    for (i, snapshot) in snapshots.iter().enumerate() {
+        groups.entry(InstrumentKey::of(snapshot)).or_default().push(i);
+    }
+    groups
+}
+
+/// Per-instrument summary of one batch
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SymbolStats {
+    pub count: usize,
+    pub mean_obi: f64,
+    pub std_obi: f64, // Population stddev
+    pub min_obi: f64,
+    pub max_obi: f64,
+    pub mean_spread_bps: f64,
+    pub buy_signals: usize,
+    pub sell_signals: usize,
+    pub neutral_signals: usize,
+    pub first_timestamp: u64,
+    pub last_timestamp: u64,
+}
+
+impl SymbolStats {
+    pub fn from_results(results: &[ObiResult]) -> Self {
+        if results.is_empty() {
+            return SymbolStats::default();
+        }
+
+        let n = results.len() as f64;
+        let mean_obi = results.iter().map(|r| r.obi).sum::<f64>() / n;
+        let variance = results.iter().map(|r| (r.obi - mean_obi).powi(2)).sum::<f64>() / n;
+
+        let count_signal = |signal: &str| results.iter().filter(|r| r.signal == signal).count();
+
+        SymbolStats {
+            count: results.len(),
+            mean_obi,
+            std_obi: variance.sqrt(),
+            min_obi: results.iter().map(|r| r.obi).fold(f64::INFINITY, f64::min),
+            max_obi: results.iter().map(|r| r.obi).fold(f64::NEG_INFINITY, f64::max),
+            mean_spread_bps: results.iter().map(|r| r.spread_bps).sum::<f64>() / n,
+            buy_signals: count_signal("BUY_PRESSURE"),
+            sell_signals: count_signal("SELL_PRESSURE"),
+            neutral_signals: count_signal("NEUTRAL"),
+            first_timestamp: results.iter().map(|r| r.timestamp).min().unwrap_or(0),
+            last_timestamp: results.iter().map(|r| r.timestamp).max().unwrap_or(0),
+        }
+    }
+}
+
+/// OBI results for one instrument
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ObiGroup {
+    pub key: InstrumentKey,
+    pub thresholds: SignalThresholds, // Thresholds the group was classified with
+    pub indices: Vec<usize>,          // Input index of each result
+    pub results: Vec<ObiResult>,
+    pub stats: SymbolStats,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::obi_engine::{EngineConfig, ObiConfig, PhysicsEngine};
+
+    fn tagged(symbol: &str, venue: &str, timestamp: u64, bid_volume: f64, ask_volume: f64) -> OrderBookSnapshot {
+        OrderBookSnapshot {
+            timestamp,
+            bid_volume,
+            ask_volume,
+            bid_price: 100.0,
+            ask_price: 100.1,
+            last_trade_price: None,
+            symbol: Some(symbol.to_string()),
+            venue: Some(venue.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_grouped_batch_applies_instrument_thresholds() {
+        let snapshots = vec![
+            tagged("BTC-USD", "binance", 1, 60.0, 40.0), // OBI 0.2
+            tagged("ETH-USD", "binance", 2, 60.0, 40.0), // OBI 0.2
+            tagged("BTC-USD", "coinbase", 3, 60.0, 40.0),
+            tagged("BTC-USD", "binance", 4, 20.0, 80.0), // OBI -0.6
+        ];
+
+        let mut config = EngineConfig::default();
+        config
+            .instrument_thresholds
+            .insert("BTC-USD".to_string(), SignalThresholds { buy: 0.1, sell: -0.1 });
+        config
+            .instrument_thresholds
+            .insert("BTC-USD@coinbase".to_string(), SignalThresholds { buy: 0.5, sell: -0.5 });
+        let engine = PhysicsEngine::new(config).unwrap();
+
+        let groups = engine.calculate_grouped_obi_batch(&snapshots, &ObiConfig::default()).unwrap();
+        let keys: Vec<String> = groups.iter().map(|g| g.key.to_string()).collect();
+        assert_eq!(keys, vec!["BTC-USD@binance", "BTC-USD@coinbase", "ETH-USD@binance"]);
+
+        // Symbol override: 0.2 > 0.1
+        let btc = &groups[0];
+        assert_eq!(btc.indices, vec![0, 3]);
+        assert_eq!(btc.results[0].signal, "BUY_PRESSURE");
+        assert_eq!(btc.results[1].signal, "SELL_PRESSURE");
+        assert_eq!(btc.stats.count, 2);
+        assert!((btc.stats.mean_obi - (-0.2)).abs() < 1e-12);
+        assert!((btc.stats.std_obi - 0.4).abs() < 1e-12);
+        assert_eq!((btc.stats.buy_signals, btc.stats.sell_signals), (1, 1));
+        assert_eq!((btc.stats.first_timestamp, btc.stats.last_timestamp), (1, 4));
+
+        // Venue override beats symbol override; untouched symbol keeps engine default
+        assert_eq!(groups[1].results[0].signal, "NEUTRAL");
+        assert_eq!(groups[2].results[0].signal, "NEUTRAL");
+    }
+}
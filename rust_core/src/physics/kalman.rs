@@ -0,0 +1,628 @@
+// KALMAN.rs - Linear Kalman Filter for Mid-Price Fair Value
+// COMPLEXITY: O(T * n^3) per pass (n = state dimension, 1 or 2 for the built-in models)
+// DETERMINISTIC: Sequential recursions, EM iterations fixed by config
+
+use crate::physics::obi_engine::{EngineError, OrderBookSnapshot};
+use crate::physics::validation::ValidationError;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// Dense row-major square matrix (state dimensions are tiny)
+#[derive(Debug, Clone, PartialEq)]
+struct Matrix {
+    n: usize,
+    data: Vec<f64>,
+}
+
+impl Matrix {
+    fn zeros(n: usize) -> Self {
+        Matrix { n, data: vec![0.0; n * n] }
+    }
+
+    fn diag(values: &[f64]) -> Self {
+        let mut m = Matrix::zeros(values.len());
+        for (i, v) in values.iter().enumerate() {
+            m.data[i * m.n + i] = *v;
+        }
+        m
+    }
+
+    fn from_rows(rows: &[Vec<f64>]) -> Self {
+        Matrix {
+            n: rows.len(),
+            data: rows.iter().flatten().copied().collect(),
+        }
+    }
+
+    fn get(&self, i: usize, j: usize) -> f64 {
+        self.data[i * self.n + j]
+    }
+
+    fn mul(&self, o: &Matrix) -> Matrix {
+        let n = self.n;
+        let mut out = Matrix::zeros(n);
+        for i in 0..n {
+            for j in 0..n {
+                out.data[i * n + j] = (0..n).map(|k| self.get(i, k) * o.get(k, j)).sum();
+            }
+        }
+        out
+    }
+
+    fn transpose(&self) -> Matrix {
+        let n = self.n;
+        let mut out = Matrix::zeros(n);
+        for i in 0..n {
+            for j in 0..n {
+                out.data[j * n + i] = self.get(i, j);
+            }
+        }
+        out
+    }
+
+    fn add(&self, o: &Matrix) -> Matrix {
+        Matrix {
+            n: self.n,
+            data: self.data.iter().zip(&o.data).map(|(a, b)| a + b).collect(),
+        }
+    }
+
+    fn sub(&self, o: &Matrix) -> Matrix {
+        Matrix {
+            n: self.n,
+            data: self.data.iter().zip(&o.data).map(|(a, b)| a - b).collect(),
+        }
+    }
+
+    fn scale(&self, s: f64) -> Matrix {
+        Matrix {
+            n: self.n,
+            data: self.data.iter().map(|a| a * s).collect(),
+        }
+    }
+
+    fn mul_vec(&self, v: &[f64]) -> Vec<f64> {
+        (0..self.n).map(|i| (0..self.n).map(|k| self.get(i, k) * v[k]).sum()).collect()
+    }
+
+    fn outer(a: &[f64], b: &[f64]) -> Matrix {
+        Matrix {
+            n: a.len(),
+            data: a.iter().flat_map(|x| b.iter().map(move |y| x * y)).collect(),
+        }
+    }
+
+    /// Gauss-Jordan inverse with partial pivoting (None if singular)
+    fn inverse(&self) -> Option<Matrix> {
+        let n = self.n;
+        let mut a = self.data.clone();
+        let mut inv = Matrix::diag(&vec![1.0; n]).data;
+        for col in 0..n {
+            let pivot = (col..n).max_by(|&x, &y| a[x * n + col].abs().total_cmp(&a[y * n + col].abs()))?;
+            if a[pivot * n + col].abs() < 1e-300 {
+                return None;
+            }
+            for k in 0..n {
+                a.swap(col * n + k, pivot * n + k);
+                inv.swap(col * n + k, pivot * n + k);
+            }
+            let p = a[col * n + col];
+            for k in 0..n {
+                a[col * n + k] /= p;
+                inv[col * n + k] /= p;
+            }
+            for row in (0..n).filter(|&r| r != col) {
+                let factor = a[row * n + col];
+                for k in 0..n {
+                    a[row * n + k] -= factor * a[col * n + k];
+                    inv[row * n + k] -= factor * inv[col * n + k];
+                }
+            }
+        }
+        Some(Matrix { n, data: inv })
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Built-in state-space models for the mid price
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KalmanModel {
+    LocalLevel, // Random-walk fair value
+    #[default]
+    LocalTrend, // Fair value plus a random-walk velocity (per tick)
+}
+
+impl KalmanModel {
+    /// Parse "level" | "trend" (case-insensitive)
+    pub fn parse(name: &str) -> Result<Self, EngineError> {
+        match name.to_ascii_lowercase().replace(['_', ' '], "-").as_str() {
+            "level" | "local-level" => Ok(KalmanModel::LocalLevel),
+            "trend" | "local-trend" | "local-linear-trend" => Ok(KalmanModel::LocalTrend),
+            other => Err(EngineError::InvalidConfig(format!(
+                "unknown Kalman model '{}' (expected level | trend)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Fair-value filter configuration (noise terms are variances in price² per tick)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct KalmanConfig {
+    pub model: KalmanModel,
+    pub level_noise: f64,       // Q for the fair value
+    pub velocity_noise: f64,    // Q for the velocity (LocalTrend only)
+    pub observation_noise: f64, // R, the microstructure noise on the observed mid
+    pub band_sigma: f64,        // Uncertainty band half-width in state stddevs
+    pub em_iterations: usize,   // EM passes to re-estimate Q and R before filtering (0 = use as given)
+    pub smooth: bool,           // Report RTS-smoothed states (non-causal) instead of filtered
+}
+
+impl Default for KalmanConfig {
+    fn default() -> Self {
+        KalmanConfig {
+            model: KalmanModel::LocalTrend,
+            level_noise: 1e-4,
+            velocity_noise: 1e-6,
+            observation_noise: 1e-2,
+            band_sigma: 2.0,
+            em_iterations: 0,
+            smooth: false,
+        }
+    }
+}
+
+impl KalmanConfig {
+    fn validate(&self) -> Result<(), EngineError> {
+        let noises = [self.level_noise, self.velocity_noise, self.observation_noise];
+        if noises.iter().any(|v| !(v.is_finite() && *v >= 0.0)) || self.observation_noise == 0.0 {
+            return Err(EngineError::InvalidConfig(format!(
+                "Kalman noise must be finite and non-negative with observation_noise > 0, got {:?}",
+                noises
+            )));
+        }
+        if !(self.band_sigma.is_finite() && self.band_sigma >= 0.0) {
+            return Err(EngineError::InvalidConfig(format!("band_sigma must be non-negative, got {}", self.band_sigma)));
+        }
+        Ok(())
+    }
+}
+
+/// Innovation of one measurement update
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Innovation {
+    pub value: f64,    // y - H x⁻
+    pub variance: f64, // H P⁻ H' + R
+}
+
+/// Generic linear Kalman filter with a scalar observation y = H x + v
+#[derive(Debug, Clone)]
+pub struct LinearKalman {
+    f: Matrix,
+    h: Vec<f64>,
+    q: Matrix,
+    r: f64,
+    x: Vec<f64>,
+    p: Matrix,
+}
+
+impl LinearKalman {
+    /// `transition` and `process_noise` are n×n rows; `observation` has length n
+    pub fn new(
+        transition: &[Vec<f64>],
+        observation: &[f64],
+        process_noise: &[Vec<f64>],
+        observation_noise: f64,
+        initial_state: &[f64],
+        initial_covariance: &[Vec<f64>],
+    ) -> Result<Self, EngineError> {
+        let n = initial_state.len();
+        let square = |m: &[Vec<f64>]| m.len() == n && m.iter().all(|row| row.len() == n);
+        if n == 0 || !square(transition) || !square(process_noise) || !square(initial_covariance) || observation.len() != n {
+            return Err(EngineError::InvalidConfig(format!("Kalman matrices must all be {}-dimensional", n)));
+        }
+        if !(observation_noise.is_finite() && observation_noise > 0.0) {
+            return Err(EngineError::InvalidConfig(format!(
+                "observation_noise must be positive, got {}",
+                observation_noise
+            )));
+        }
+
+        Ok(LinearKalman {
+            f: Matrix::from_rows(transition),
+            h: observation.to_vec(),
+            q: Matrix::from_rows(process_noise),
+            r: observation_noise,
+            x: initial_state.to_vec(),
+            p: Matrix::from_rows(initial_covariance),
+        })
+    }
+
+    /// Level or level+velocity model starting at `first` with the observation noise as prior variance
+    pub fn for_model(config: &KalmanConfig, first: f64) -> Self {
+        let r = config.observation_noise;
+        match config.model {
+            KalmanModel::LocalLevel => LinearKalman {
+                f: Matrix::diag(&[1.0]),
+                h: vec![1.0],
+                q: Matrix::diag(&[config.level_noise]),
+                r,
+                x: vec![first],
+                p: Matrix::diag(&[r]),
+            },
+            KalmanModel::LocalTrend => LinearKalman {
+                f: Matrix::from_rows(&[vec![1.0, 1.0], vec![0.0, 1.0]]),
+                h: vec![1.0, 0.0],
+                q: Matrix::diag(&[config.level_noise, config.velocity_noise]),
+                r,
+                x: vec![first, 0.0],
+                p: Matrix::diag(&[r, r]),
+            },
+        }
+    }
+
+    pub fn state(&self) -> &[f64] {
+        &self.x
+    }
+
+    /// Variance of state component `i`
+    pub fn variance(&self, i: usize) -> f64 {
+        self.p.get(i, i)
+    }
+
+    fn predict(&mut self) {
+        self.x = self.f.mul_vec(&self.x);
+        self.p = self.f.mul(&self.p).mul(&self.f.transpose()).add(&self.q);
+    }
+
+    fn update(&mut self, y: f64) -> Innovation {
+        let ph = self.p.mul_vec(&self.h);
+        let variance = dot(&self.h, &ph) + self.r;
+        let value = y - dot(&self.h, &self.x);
+        let gain: Vec<f64> = ph.iter().map(|v| v / variance).collect();
+
+        self.x = self.x.iter().zip(&gain).map(|(x, k)| x + k * value).collect();
+        // Joseph form keeps P symmetric positive semi-definite
+        let n = self.x.len();
+        let mut i_kh = Matrix::diag(&vec![1.0; n]);
+        for (i, k) in gain.iter().enumerate() {
+            for (j, h) in self.h.iter().enumerate() {
+                i_kh.data[i * n + j] -= k * h;
+            }
+        }
+        self.p = i_kh
+            .mul(&self.p)
+            .mul(&i_kh.transpose())
+            .add(&Matrix::outer(&gain, &gain).scale(self.r));
+
+        Innovation { value, variance }
+    }
+
+    /// Predict then update with one observation (non-finite `y` is rejected, the state is untouched)
+    pub fn step(&mut self, y: f64) -> Result<Innovation, EngineError> {
+        if !y.is_finite() {
+            return Err(EngineError::InvalidSnapshot { index: 0, error: ValidationError::NonFinite });
+        }
+        self.predict();
+        Ok(self.update(y))
+    }
+}
+
+/// Fair value per tick
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct KalmanPoint {
+    pub timestamp: u64,
+    pub observation: f64,   // Observed mid
+    pub price: f64,         // Fair value estimate
+    pub velocity: f64,      // Price change per tick (0 for LocalLevel)
+    pub std: f64,           // Fair value stddev
+    pub lower: f64,         // price - band_sigma * std
+    pub upper: f64,
+    pub innovation: f64,    // Mid minus one-step prediction
+    pub z_score: f64,       // innovation / sqrt(innovation variance)
+}
+
+/// Filtered series with the noise parameters it ran with
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KalmanSeries {
+    pub points: Vec<KalmanPoint>,
+    pub level_noise: f64,
+    pub velocity_noise: f64,
+    pub observation_noise: f64,
+    pub log_likelihood: f64, // Gaussian innovation log-likelihood (first tick excluded)
+}
+
+/// One filtered tick with everything the smoother needs
+struct FilterPass {
+    predicted: Vec<(Vec<f64>, Matrix)>, // x⁻, P⁻ (index 0 is the prior)
+    filtered: Vec<(Vec<f64>, Matrix)>,
+    innovations: Vec<Innovation>,
+    log_likelihood: f64,
+}
+
+fn filter_pass(config: &KalmanConfig, mids: &[f64]) -> (LinearKalman, FilterPass) {
+    let mut kf = LinearKalman::for_model(config, mids[0]);
+    let mut pass = FilterPass {
+        predicted: vec![(kf.x.clone(), kf.p.clone())],
+        filtered: vec![(kf.x.clone(), kf.p.clone())],
+        innovations: vec![Innovation { value: 0.0, variance: kf.r }],
+        log_likelihood: 0.0,
+    };
+
+    for &y in &mids[1..] {
+        kf.predict();
+        pass.predicted.push((kf.x.clone(), kf.p.clone()));
+        let innovation = kf.update(y);
+        pass.log_likelihood -= 0.5 * ((2.0 * PI * innovation.variance).ln() + innovation.value.powi(2) / innovation.variance);
+        pass.filtered.push((kf.x.clone(), kf.p.clone()));
+        pass.innovations.push(innovation);
+    }
+    (kf, pass)
+}
+
+/// Rauch-Tung-Striebel smoother: smoothed states and lag-one covariances Cov(x_t, x_{t-1} | T)
+fn smooth(kf: &LinearKalman, pass: &FilterPass) -> (Vec<(Vec<f64>, Matrix)>, Vec<Matrix>) {
+    let t_len = pass.filtered.len();
+    let mut smoothed = pass.filtered.clone();
+    let mut lag_one = vec![Matrix::zeros(kf.x.len()); t_len];
+    let ft = kf.f.transpose();
+
+    for t in (0..t_len - 1).rev() {
+        let (x_f, p_f) = &pass.filtered[t];
+        let (x_p, p_p) = &pass.predicted[t + 1];
+        let Some(p_p_inv) = p_p.inverse() else {
+            continue; // Degenerate prediction: keep the filtered state
+        };
+        let j = p_f.mul(&ft).mul(&p_p_inv);
+
+        let (x_next, p_next) = smoothed[t + 1].clone();
+        let dx: Vec<f64> = x_next.iter().zip(x_p).map(|(a, b)| a - b).collect();
+        let x_s: Vec<f64> = x_f.iter().zip(j.mul_vec(&dx)).map(|(a, b)| a + b).collect();
+        let p_s = p_f.add(&j.mul(&p_next.sub(p_p)).mul(&j.transpose()));
+
+        lag_one[t + 1] = p_next.mul(&j.transpose());
+        smoothed[t] = (x_s, p_s);
+    }
+
+    (smoothed, lag_one)
+}
+
+/// Shumway-Stoffer EM for Q (diagonal) and R with F and H fixed
+fn em_step(config: &mut KalmanConfig, mids: &[f64]) {
+    let (kf, pass) = filter_pass(config, mids);
+    let (smoothed, lag_one) = smooth(&kf, &pass);
+    let t_len = mids.len() as f64;
+
+    let r: f64 = mids
+        .iter()
+        .zip(&smoothed)
+        .map(|(y, (x, p))| (y - dot(&kf.h, x)).powi(2) + dot(&kf.h, &p.mul_vec(&kf.h)))
+        .sum::<f64>()
+        / t_len;
+
+    let n = kf.x.len();
+    let ft = kf.f.transpose();
+    let mut q = Matrix::zeros(n);
+    for t in 1..mids.len() {
+        let (x1, p1) = &smoothed[t];
+        let (x0, p0) = &smoothed[t - 1];
+        let s11 = Matrix::outer(x1, x1).add(p1);
+        let s10 = Matrix::outer(x1, x0).add(&lag_one[t]);
+        let s00 = Matrix::outer(x0, x0).add(p0);
+        let term = s11
+            .sub(&s10.mul(&ft))
+            .sub(&kf.f.mul(&s10.transpose()))
+            .add(&kf.f.mul(&s00).mul(&ft));
+        q = q.add(&term);
+    }
+    let q = q.scale(1.0 / (t_len - 1.0));
+
+    const FLOOR: f64 = 1e-12;
+    config.observation_noise = r.max(FLOOR);
+    config.level_noise = q.get(0, 0).max(FLOOR);
+    if config.model == KalmanModel::LocalTrend {
+        config.velocity_noise = q.get(1, 1).max(FLOOR);
+    }
+}
+
+/// Fair value over a mid series (optionally EM-estimated noise, optionally RTS-smoothed)
+pub fn kalman_series(timestamps: &[u64], mids: &[f64], config: &KalmanConfig) -> Result<KalmanSeries, EngineError> {
+    config.validate()?;
+    if timestamps.len() != mids.len() {
+        return Err(EngineError::InvalidConfig(format!(
+            "timestamps and mids differ in length: {} vs {}",
+            timestamps.len(),
+            mids.len()
+        )));
+    }
+    if let Some(bad) = mids.iter().position(|m| !m.is_finite()) {
+        return Err(EngineError::InvalidSnapshot { index: bad, error: ValidationError::NonFinite });
+    }
+
+    let mut config = *config;
+    if mids.is_empty() {
+        return Ok(KalmanSeries {
+            points: Vec::new(),
+            level_noise: config.level_noise,
+            velocity_noise: config.velocity_noise,
+            observation_noise: config.observation_noise,
+            log_likelihood: 0.0,
+        });
+    }
+
+    if mids.len() >= 3 {
+        for _ in 0..config.em_iterations {
+            em_step(&mut config, mids);
+        }
+    }
+
+    let (kf, pass) = filter_pass(&config, mids);
+    let states = if config.smooth { smooth(&kf, &pass).0 } else { pass.filtered.clone() };
+
+    let points = states
+        .iter()
+        .zip(&pass.innovations)
+        .zip(timestamps.iter().zip(mids))
+        .map(|(((x, p), innovation), (&timestamp, &observation))| {
+            let std = p.get(0, 0).max(0.0).sqrt();
+            KalmanPoint {
+                timestamp,
+                observation,
+                price: x[0],
+                velocity: x.get(1).copied().unwrap_or(0.0),
+                std,
+                lower: x[0] - config.band_sigma * std,
+                upper: x[0] + config.band_sigma * std,
+                innovation: innovation.value,
+                z_score: innovation.value / innovation.variance.sqrt(),
+            }
+        })
+        .collect();
+
+    Ok(KalmanSeries {
+        points,
+        level_noise: config.level_noise,
+        velocity_noise: config.velocity_noise,
+        observation_noise: config.observation_noise,
+        log_likelihood: pass.log_likelihood,
+    })
+}
+
+/// Mid prices of snapshots with their timestamps
+pub fn mid_series(snapshots: &[OrderBookSnapshot]) -> (Vec<u64>, Vec<f64>) {
+    snapshots
+        .iter()
+        .map(|s| (s.timestamp, (s.bid_price + s.ask_price) / 2.0))
+        .unzip()
+}
+
+/// Streaming fair-value filter (filtered states only)
+#[derive(Debug, Clone)]
+pub struct FairValueFilter {
+    config: KalmanConfig,
+    filter: Option<LinearKalman>,
+}
+
+impl FairValueFilter {
+    pub fn new(config: KalmanConfig) -> Result<Self, EngineError> {
+        config.validate()?;
+        Ok(FairValueFilter { config, filter: None })
+    }
+
+    /// Add one mid; the first initialises the state (innovation 0).
+    /// Non-finite mids are rejected and leave the state untouched.
+    pub fn push(&mut self, timestamp: u64, mid: f64) -> Result<KalmanPoint, EngineError> {
+        let config = self.config;
+        match self.filter.as_mut() {
+            None => {
+                if !mid.is_finite() {
+                    return Err(EngineError::InvalidSnapshot { index: 0, error: ValidationError::NonFinite });
+                }
+                let kf = LinearKalman::for_model(&config, mid);
+                let innovation = Innovation { value: 0.0, variance: kf.r };
+                Ok(Self::point(&config, self.filter.insert(kf), timestamp, mid, innovation))
+            }
+            Some(kf) => {
+                let innovation = kf.step(mid)?;
+                Ok(Self::point(&config, kf, timestamp, mid, innovation))
+            }
+        }
+    }
+
+    fn point(config: &KalmanConfig, kf: &LinearKalman, timestamp: u64, mid: f64, innovation: Innovation) -> KalmanPoint {
+        let std = kf.variance(0).max(0.0).sqrt();
+        let price = kf.state()[0];
+        KalmanPoint {
+            timestamp,
+            observation: mid,
+            price,
+            velocity: kf.state().get(1).copied().unwrap_or(0.0),
+            std,
+            lower: price - config.band_sigma * std,
+            upper: price + config.band_sigma * std,
+            innovation: innovation.value,
+            z_score: innovation.value / innovation.variance.sqrt(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.filter = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_fair_value_filter_smoother_and_em() {
+        // Drifting fair value observed through bid/ask bounce noise
+        let mut rng = StdRng::seed_from_u64(11);
+        let truth: Vec<f64> = (0..400).map(|t| 100.0 + 0.01 * t as f64).collect();
+        let mids: Vec<f64> = truth.iter().map(|p| p + (rng.gen::<f64>() - 0.5) * 0.4).collect();
+        let timestamps: Vec<u64> = (0..400).collect();
+
+        let rmse = |points: &[KalmanPoint]| {
+            (points[50..].iter().zip(&truth[50..]).map(|(p, t)| (p.price - t).powi(2)).sum::<f64>() / 350.0).sqrt()
+        };
+        let raw_rmse = (mids[50..].iter().zip(&truth[50..]).map(|(m, t)| (m - t).powi(2)).sum::<f64>() / 350.0).sqrt();
+
+        // Trend model tracks the drift and beats the raw mid
+        let trend = kalman_series(&timestamps, &mids, &KalmanConfig::default()).unwrap();
+        assert!(rmse(&trend.points) < raw_rmse * 0.6);
+        assert!((trend.points[399].velocity - 0.01).abs() < 0.005);
+        let p = &trend.points[399];
+        assert!(p.lower < p.price && p.price < p.upper);
+
+        // RTS smoothing is at least as accurate as filtering
+        let smoothed = kalman_series(&timestamps, &mids, &KalmanConfig { smooth: true, ..Default::default() }).unwrap();
+        assert!(rmse(&smoothed.points) <= rmse(&trend.points));
+
+        // EM moves R towards the true noise variance (uniform ±0.2: 0.04 / 3) and raises the likelihood
+        let em = kalman_series(&timestamps, &mids, &KalmanConfig { em_iterations: 30, ..Default::default() }).unwrap();
+        assert!((em.observation_noise - 0.04 / 3.0).abs() < 0.005, "R = {}", em.observation_noise);
+        assert!(em.log_likelihood > trend.log_likelihood);
+
+        // A single-tick spike shows up as a large innovation z-score; streaming matches batch
+        let mut spiked = mids.clone();
+        spiked[200] += 2.0;
+        let mut live = FairValueFilter::new(KalmanConfig { model: KalmanModel::LocalLevel, ..Default::default() }).unwrap();
+        let streamed: Vec<KalmanPoint> = spiked.iter().enumerate().map(|(t, &m)| live.push(t as u64, m).unwrap()).collect();
+        assert!(streamed[200].z_score > 5.0);
+        assert!(streamed[199].z_score.abs() < 4.0);
+        let batch = kalman_series(&timestamps, &spiked, &KalmanConfig { model: KalmanModel::LocalLevel, ..Default::default() }).unwrap();
+        assert!((batch.points[300].price - streamed[300].price).abs() < 1e-12);
+        // A rejected tick leaves the streaming state untouched
+        let mut untouched = live.clone();
+        assert!(matches!(
+            live.push(400, f64::NAN),
+            Err(EngineError::InvalidSnapshot { index: 0, error: ValidationError::NonFinite })
+        ));
+        assert_eq!(live.push(401, 100.0).unwrap(), untouched.push(401, 100.0).unwrap());
+        let mut cold = FairValueFilter::new(KalmanConfig::default()).unwrap();
+        assert!(cold.push(0, f64::INFINITY).is_err());
+        assert!(cold.push(1, 100.0).unwrap().std.is_finite());
+
+        // Generic filter: a 1-D constant model is a running mean
+        let mut constant = LinearKalman::new(&[vec![1.0]], &[1.0], &[vec![0.0]], 1.0, &[0.0], &[vec![1e12]]).unwrap();
+        for y in [1.0, 2.0, 3.0] {
+            constant.step(y).unwrap();
+        }
+        assert!((constant.state()[0] - 2.0).abs() < 1e-6);
+        // A NaN observation is rejected instead of poisoning the state
+        assert!(constant.step(f64::NAN).is_err());
+        assert!((constant.state()[0] - 2.0).abs() < 1e-6);
+
+        assert!(KalmanModel::parse("Local_Level").is_ok());
+        assert!(matches!(
+            kalman_series(&timestamps[..2], &[100.0, f64::NAN], &KalmanConfig::default()),
+            Err(EngineError::InvalidSnapshot { index: 1, error: ValidationError::NonFinite })
+        ));
+        assert!(kalman_series(&timestamps[..399], &mids, &KalmanConfig::default()).is_err());
+    }
+}
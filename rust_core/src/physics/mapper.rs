@@ -0,0 +1,314 @@
+// MAPPER.rs - Mapper Graph (lens, overlapping interval cover, single-linkage clusters)
+// COMPLEXITY: O(c * m^2) for c cover elements of m points each (Prim MST per element)
+// DETERMINISTIC: Nodes ordered by (interval, smallest member), edges by (source, target)
+
+use crate::physics::obi_engine::EngineError;
+use serde::{Deserialize, Serialize};
+
+/// Cover and clustering parameters
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct MapperConfig {
+    pub intervals: usize,         // Cover elements over the lens range
+    pub overlap: f64,             // Fraction of each interval shared with its neighbour, [0, 1)
+    pub cluster_eps: Option<f64>, // Single-linkage cut height (None = first empty histogram bin)
+    pub histogram_bins: usize,    // Bins of merge heights for the automatic cut
+}
+
+impl Default for MapperConfig {
+    fn default() -> Self {
+        MapperConfig {
+            intervals: 10,
+            overlap: 0.3,
+            cluster_eps: None,
+            histogram_bins: 10,
+        }
+    }
+}
+
+/// One cluster of one cover element
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MapperNode {
+    pub id: usize,
+    pub interval: usize,
+    pub members: Vec<usize>, // Indices into the input points, ascending
+    pub lens_mean: f64,
+    pub centroid: Vec<f64>,
+}
+
+/// Nodes sharing at least one member
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct MapperEdge {
+    pub source: usize,
+    pub target: usize,
+    pub shared: usize,
+    pub jaccard: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MapperGraph {
+    pub nodes: Vec<MapperNode>,
+    pub edges: Vec<MapperEdge>,
+    pub components: usize, // Connected components (isolated nodes count)
+}
+
+impl MapperGraph {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("graph fields are plain numbers")
+    }
+}
+
+fn count_components(nodes: usize, edges: &[MapperEdge]) -> usize {
+    let mut parent: Vec<usize> = (0..nodes).collect();
+    let mut components = nodes;
+    for edge in edges {
+        let (a, b) = (find(&mut parent, edge.source), find(&mut parent, edge.target));
+        if a != b {
+            parent[a] = b;
+            components -= 1;
+        }
+    }
+    components
+}
+
+fn find(parent: &mut [usize], mut x: usize) -> usize {
+    while parent[x] != x {
+        parent[x] = parent[parent[x]];
+        x = parent[x];
+    }
+    x
+}
+
+fn distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt()
+}
+
+/// Projection onto the first principal component (power iteration on the covariance)
+pub fn principal_lens(points: &[Vec<f64>]) -> Vec<f64> {
+    let dims = points.first().map_or(0, Vec::len);
+    let n = points.len().max(1) as f64;
+    let mean: Vec<f64> = (0..dims).map(|k| points.iter().map(|p| p[k]).sum::<f64>() / n).collect();
+    let centred: Vec<Vec<f64>> = points
+        .iter()
+        .map(|p| p.iter().zip(&mean).map(|(x, m)| x - m).collect())
+        .collect();
+
+    let mut axis = vec![1.0 / (dims.max(1) as f64).sqrt(); dims];
+    for _ in 0..100 {
+        let mut next = vec![0.0; dims];
+        for p in &centred {
+            let proj: f64 = p.iter().zip(&axis).map(|(x, a)| x * a).sum();
+            for (nk, x) in next.iter_mut().zip(p) {
+                *nk += proj * x;
+            }
+        }
+        let norm = next.iter().map(|v| v * v).sum::<f64>().sqrt();
+        if norm == 0.0 {
+            break;
+        }
+        next.iter_mut().for_each(|v| *v /= norm);
+        let converged = next.iter().zip(&axis).map(|(a, b)| (a - b).abs()).fold(0.0, f64::max) < 1e-12;
+        axis = next;
+        if converged {
+            break;
+        }
+    }
+    centred
+        .iter()
+        .map(|p| p.iter().zip(&axis).map(|(x, a)| x * a).sum())
+        .collect()
+}
+
+/// Single-linkage clusters of `members`: Prim MST, cut above `eps` (or the automatic height)
+fn single_linkage(points: &[Vec<f64>], members: &[usize], config: &MapperConfig) -> Vec<Vec<usize>> {
+    let m = members.len();
+    if m <= 1 {
+        return vec![members.to_vec()];
+    }
+
+    // MST edges (child, parent, height)
+    let mut in_tree = vec![false; m];
+    let mut best = vec![(f64::INFINITY, 0usize); m];
+    let mut tree = Vec::with_capacity(m - 1);
+    let mut current = 0;
+    in_tree[0] = true;
+    for _ in 1..m {
+        let mut next = None;
+        for j in 0..m {
+            if in_tree[j] {
+                continue;
+            }
+            let d = distance(&points[members[current]], &points[members[j]]);
+            if d < best[j].0 {
+                best[j] = (d, current);
+            }
+            if next.is_none_or(|n: usize| best[j].0 < best[n].0) {
+                next = Some(j);
+            }
+        }
+        let j = next.expect("a vertex remains outside the tree");
+        in_tree[j] = true;
+        tree.push((j, best[j].1, best[j].0));
+        current = j;
+    }
+
+    let eps = config.cluster_eps.unwrap_or_else(|| {
+        // Singh et al.: histogram the merge heights and cut at the first empty bin
+        let max = tree.iter().map(|e| e.2).fold(0.0, f64::max);
+        let bins = config.histogram_bins.max(1);
+        let mut counts = vec![0usize; bins];
+        for e in &tree {
+            let b = if max > 0.0 { ((e.2 / max * bins as f64) as usize).min(bins - 1) } else { 0 };
+            counts[b] += 1;
+        }
+        counts
+            .iter()
+            .position(|&c| c == 0)
+            .map_or(f64::INFINITY, |b| max * b as f64 / bins as f64)
+    });
+
+    let mut parent: Vec<usize> = (0..m).collect();
+    for &(a, b, height) in &tree {
+        if height <= eps {
+            let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+            parent[ra] = rb;
+        }
+    }
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+    let mut slot = vec![usize::MAX; m];
+    for (i, &member) in members.iter().enumerate() {
+        let root = find(&mut parent, i);
+        if slot[root] == usize::MAX {
+            slot[root] = clusters.len();
+            clusters.push(Vec::new());
+        }
+        clusters[slot[root]].push(member);
+    }
+    clusters
+}
+
+/// Mapper graph of `points` under `lens` (one lens value per point)
+pub fn mapper(points: &[Vec<f64>], lens: &[f64], config: &MapperConfig) -> Result<MapperGraph, EngineError> {
+    if config.intervals == 0 || !(0.0..1.0).contains(&config.overlap) {
+        return Err(EngineError::InvalidConfig(format!(
+            "mapper needs intervals >= 1 and overlap in [0, 1), got {} / {}",
+            config.intervals, config.overlap
+        )));
+    }
+    if config.cluster_eps.is_some_and(|eps| eps.is_nan() || eps < 0.0) {
+        return Err(EngineError::InvalidConfig("cluster_eps must be non-negative".to_string()));
+    }
+    if lens.len() != points.len() || lens.iter().chain(points.iter().flatten()).any(|v| !v.is_finite()) {
+        return Err(EngineError::InvalidConfig(format!(
+            "mapper needs one finite lens value per finite point, got {} lens values for {} points",
+            lens.len(),
+            points.len()
+        )));
+    }
+    if points.is_empty() {
+        return Ok(MapperGraph::default());
+    }
+
+    let (min, max) = lens.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    // A constant lens has nothing to cover: one element holds every point
+    let intervals = if max > min { config.intervals } else { 1 };
+    let n = intervals as f64;
+    let length = (max - min) / (n - (n - 1.0) * config.overlap);
+    let stride = length * (1.0 - config.overlap);
+
+    let mut nodes: Vec<MapperNode> = Vec::new();
+    for interval in 0..intervals {
+        let start = min + interval as f64 * stride;
+        // Last interval closes at max so rounding never drops the top point
+        let end = if interval + 1 == intervals { max } else { start + length };
+        let members: Vec<usize> = (0..points.len()).filter(|&i| lens[i] >= start && lens[i] <= end).collect();
+        if members.is_empty() {
+            continue;
+        }
+        for mut cluster in single_linkage(points, &members, config) {
+            cluster.sort_unstable();
+            let size = cluster.len() as f64;
+            let dims = points[cluster[0]].len();
+            nodes.push(MapperNode {
+                id: 0,
+                interval,
+                lens_mean: cluster.iter().map(|&i| lens[i]).sum::<f64>() / size,
+                centroid: (0..dims).map(|k| cluster.iter().map(|&i| points[i][k]).sum::<f64>() / size).collect(),
+                members: cluster,
+            });
+        }
+    }
+    nodes.sort_by_key(|node| (node.interval, node.members[0]));
+    for (id, node) in nodes.iter_mut().enumerate() {
+        node.id = id;
+    }
+
+    // Overlaps only occur between nearby intervals, but membership lists are small: compare pairs
+    let mut edges = Vec::new();
+    for a in 0..nodes.len() {
+        for b in a + 1..nodes.len() {
+            let (x, y) = (&nodes[a].members, &nodes[b].members);
+            let shared = x.iter().filter(|i| y.binary_search(i).is_ok()).count();
+            if shared > 0 {
+                edges.push(MapperEdge {
+                    source: a,
+                    target: b,
+                    shared,
+                    jaccard: shared as f64 / (x.len() + y.len() - shared) as f64,
+                });
+            }
+        }
+    }
+
+    Ok(MapperGraph {
+        components: count_components(nodes.len(), &edges),
+        nodes,
+        edges,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mapper_recovers_circle_and_branches() {
+        // A circle under the height lens: the graph is a single loop (nodes = edges)
+        let circle: Vec<Vec<f64>> = (0..120)
+            .map(|i| {
+                let a = 2.0 * std::f64::consts::PI * i as f64 / 120.0;
+                vec![a.cos(), a.sin()]
+            })
+            .collect();
+        let lens: Vec<f64> = circle.iter().map(|p| p[1]).collect();
+        let config = MapperConfig { intervals: 6, overlap: 0.25, cluster_eps: Some(0.2), ..Default::default() };
+        let graph = mapper(&circle, &lens, &config).unwrap();
+        assert_eq!(graph.components, 1);
+        assert_eq!(graph.edges.len(), graph.nodes.len()); // One independent cycle
+        assert_eq!(graph.nodes.iter().filter(|n| n.interval == 0).count(), 1);
+        assert_eq!(graph.nodes.iter().filter(|n| n.interval == 2).count(), 2); // Left and right arcs
+        let covered: std::collections::BTreeSet<usize> = graph.nodes.iter().flat_map(|n| n.members.clone()).collect();
+        assert_eq!(covered.len(), 120);
+
+        // Two separated clusters are split by the automatic cut
+        let mut blobs: Vec<Vec<f64>> = (0..20).map(|i| vec![i as f64 * 0.01, 0.0]).collect();
+        blobs.extend((0..20).map(|i| vec![i as f64 * 0.01, 5.0]));
+        let flat = vec![0.0; 40];
+        let graph = mapper(&blobs, &flat, &MapperConfig { intervals: 1, ..Default::default() }).unwrap();
+        assert_eq!(graph.nodes.len(), 2);
+        assert!(graph.edges.is_empty());
+
+        // A constant lens is one cover element, not `intervals` copies of each cluster
+        let graph = mapper(&blobs, &flat, &MapperConfig { intervals: 5, ..Default::default() }).unwrap();
+        assert_eq!(graph.nodes.len(), 2);
+        assert!(graph.nodes.iter().all(|n| n.interval == 0));
+        assert!(graph.edges.is_empty());
+
+        // PCA lens follows the long axis
+        let lens = principal_lens(&blobs);
+        assert!((lens[0].abs() - 2.5).abs() < 0.01 && (lens[20].abs() - 2.5).abs() < 0.01);
+
+        let json = graph.to_json();
+        assert!(json.contains(r#""nodes""#) && json.contains(r#""members":[0,1,2"#));
+        assert!(mapper(&blobs, &flat[..3], &MapperConfig::default()).is_err());
+    }
+}
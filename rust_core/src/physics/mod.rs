@@ -1,3 +1,19 @@
+pub mod backend;
+pub mod consolidated;
+pub mod dimension;
+pub mod entropy;
+pub mod fixed;
+pub mod instrument;
+pub mod kalman;
+pub mod mapper;
+pub mod obi_columns;
 pub mod obi_engine;
-pub mod obi_engine;
+pub mod persistence;
+pub mod regime;
+pub mod streaming_tda;
+pub mod takens;
 pub mod tda;
+pub mod trades;
+pub mod validation;
+pub mod volatility;
+pub mod vpin;
@@ -0,0 +1,231 @@
+// OBI_COLUMNS.rs - Struct-of-Arrays OBI Kernel for Large Replays
+// COMPLEXITY: O(n), branch-free inner loop that LLVM auto-vectorises
+// DETERMINISTIC: Same f64 operations as the per-snapshot path (bit-identical results)
+
+use crate::physics::entropy;
+use crate::physics::obi_engine::{EngineError, SignalThresholds};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Rows per parallel chunk (large enough to amortise scheduling, small enough for L2)
+pub const COLUMN_CHUNK: usize = 16 * 1024;
+
+/// OBI signal without a heap allocation per result
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i8)]
+pub enum Signal {
+    SellPressure = -1,
+    Neutral = 0,
+    BuyPressure = 1,
+}
+
+impl Signal {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Signal::BuyPressure => "BUY_PRESSURE",
+            Signal::SellPressure => "SELL_PRESSURE",
+            Signal::Neutral => "NEUTRAL",
+        }
+    }
+}
+
+/// Borrowed order book columns (one row per snapshot)
+#[derive(Debug, Clone, Copy)]
+pub struct OrderBookColumns<'a> {
+    pub bid_price: &'a [f64],
+    pub bid_volume: &'a [f64],
+    pub ask_price: &'a [f64],
+    pub ask_volume: &'a [f64],
+}
+
+impl<'a> OrderBookColumns<'a> {
+    /// All four columns must have the same length
+    pub fn new(
+        bid_price: &'a [f64],
+        bid_volume: &'a [f64],
+        ask_price: &'a [f64],
+        ask_volume: &'a [f64],
+    ) -> Result<Self, EngineError> {
+        let n = bid_price.len();
+        if bid_volume.len() != n || ask_price.len() != n || ask_volume.len() != n {
+            return Err(EngineError::InvalidConfig(format!(
+                "column length mismatch: bid_price={}, bid_volume={}, ask_price={}, ask_volume={}",
+                n,
+                bid_volume.len(),
+                ask_price.len(),
+                ask_volume.len()
+            )));
+        }
+
+        Ok(OrderBookColumns {
+            bid_price,
+            bid_volume,
+            ask_price,
+            ask_volume,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.bid_price.len()
+    }
+
+    /// Rows [start, end)
+    pub fn slice(&self, start: usize, end: usize) -> OrderBookColumns<'a> {
+        OrderBookColumns {
+            bid_price: &self.bid_price[start..end],
+            bid_volume: &self.bid_volume[start..end],
+            ask_price: &self.ask_price[start..end],
+            ask_volume: &self.ask_volume[start..end],
+        }
+    }
+}
+
+/// Columnar OBI output
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ObiColumns {
+    pub obi: Vec<f64>,
+    pub entropy: Vec<f64>,         // Shannon entropy (bits) of [bid, ask] volume
+    pub relative_spread: Vec<f64>, // Spread / avg_price
+    pub signal: Vec<Signal>,
+}
+
+impl ObiColumns {
+    fn zeroed(n: usize) -> Self {
+        ObiColumns {
+            obi: vec![0.0; n],
+            entropy: vec![0.0; n],
+            relative_spread: vec![0.0; n],
+            signal: vec![Signal::Neutral; n],
+        }
+    }
+}
+
+/// Mutable output slices for one chunk
+struct ColumnsOut<'a> {
+    obi: &'a mut [f64],
+    entropy: &'a mut [f64],
+    relative_spread: &'a mut [f64],
+    signal: &'a mut [Signal],
+}
+
+/// Kernel over one chunk: writes into pre-sized output slices
+fn obi_kernel(input: OrderBookColumns<'_>, thresholds: &SignalThresholds, out: ColumnsOut<'_>) {
+    let rows = input
+        .bid_price
+        .iter()
+        .zip(input.bid_volume)
+        .zip(input.ask_price)
+        .zip(input.ask_volume);
+
+    for ((((bid_price, bid_volume), ask_price), ask_volume), (obi, relative_spread)) in
+        rows.zip(out.obi.iter_mut().zip(out.relative_spread.iter_mut()))
+    {
+        let total_volume = bid_volume + ask_volume;
+        *obi = if total_volume > 0.0 {
+            (bid_volume - ask_volume) / total_volume
+        } else {
+            0.0
+        };
+
+        let avg_price = (bid_price + ask_price) / 2.0;
+        let spread = (ask_price - bid_price).abs();
+        *relative_spread = if avg_price > 0.0 { spread / avg_price } else { 1.0 };
+    }
+
+    // Separate passes keep the arithmetic loop free of log2 calls and enum stores
+    for ((bid_volume, ask_volume), entropy) in input.bid_volume.iter().zip(input.ask_volume).zip(out.entropy.iter_mut()) {
+        *entropy = entropy::binary_entropy(*bid_volume, *ask_volume);
+    }
+    for (obi, signal) in out.obi.iter().zip(out.signal.iter_mut()) {
+        *signal = thresholds.classify_signal(*obi);
+    }
+}
+
+/// Sequential columnar OBI (reference)
+pub fn calculate_obi_columns(input: OrderBookColumns<'_>, thresholds: &SignalThresholds) -> ObiColumns {
+    let mut out = ObiColumns::zeroed(input.len());
+    obi_kernel(
+        input,
+        thresholds,
+        ColumnsOut {
+            obi: &mut out.obi,
+            entropy: &mut out.entropy,
+            relative_spread: &mut out.relative_spread,
+            signal: &mut out.signal,
+        },
+    );
+    out
+}
+
+/// Parallel columnar OBI: COLUMN_CHUNK-sized chunks across the current Rayon pool
+pub fn calculate_obi_columns_par(input: OrderBookColumns<'_>, thresholds: &SignalThresholds) -> ObiColumns {
+    let mut out = ObiColumns::zeroed(input.len());
+
+    out.obi
+        .par_chunks_mut(COLUMN_CHUNK)
+        .zip(out.entropy.par_chunks_mut(COLUMN_CHUNK))
+        .zip(out.relative_spread.par_chunks_mut(COLUMN_CHUNK))
+        .zip(out.signal.par_chunks_mut(COLUMN_CHUNK))
+        .enumerate()
+        .for_each(|(i, (((obi, entropy), relative_spread), signal))| {
+            let start = i * COLUMN_CHUNK;
+            let chunk = input.slice(start, start + obi.len());
+            obi_kernel(
+                chunk,
+                thresholds,
+                ColumnsOut {
+                    obi,
+                    entropy,
+                    relative_spread,
+                    signal,
+                },
+            );
+        });
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::obi_engine::{EngineConfig, ObiConfig, OrderBookSnapshot, PhysicsEngine};
+
+    #[test]
+    fn test_columns_match_snapshot_path() {
+        let n = COLUMN_CHUNK * 2 + 123;
+        let snapshots: Vec<OrderBookSnapshot> = (0..n)
+            .map(|i| {
+                let x = i as f64;
+                OrderBookSnapshot {
+                    timestamp: i as u64,
+                    bid_volume: (x * 0.37).sin().abs() * 100.0,
+                    ask_volume: (x * 0.11).cos().abs() * 100.0,
+                    bid_price: 100.0 + (x * 0.01).sin(),
+                    ask_price: 100.1 + (x * 0.01).sin() + (i % 3) as f64 * 0.01,
+                    last_trade_price: None,
+                    symbol: None,
+                    venue: None,
+                }
+            })
+            .collect();
+
+        let column = |f: fn(&OrderBookSnapshot) -> f64| -> Vec<f64> { snapshots.iter().map(f).collect() };
+        let (bid_price, bid_volume) = (column(|s| s.bid_price), column(|s| s.bid_volume));
+        let (ask_price, ask_volume) = (column(|s| s.ask_price), column(|s| s.ask_volume));
+        let input = OrderBookColumns::new(&bid_price, &bid_volume, &ask_price, &ask_volume).unwrap();
+
+        let engine = PhysicsEngine::new(EngineConfig::default()).unwrap();
+        let reference = engine.calculate_obi_batch(&snapshots, &ObiConfig::default()).unwrap();
+        let columns = engine.calculate_obi_columns(input).unwrap();
+
+        assert_eq!(columns.obi.len(), n);
+        for (i, r) in reference.iter().enumerate() {
+            assert_eq!(r.obi.to_bits(), columns.obi[i].to_bits());
+            assert_eq!(r.entropy.to_bits(), columns.entropy[i].to_bits());
+            assert_eq!(r.relative_spread.to_bits(), columns.relative_spread[i].to_bits());
+            assert_eq!(r.signal, columns.signal[i].as_str());
+        }
+
+        assert!(OrderBookColumns::new(&bid_price, &bid_volume[1..], &ask_price, &ask_volume).is_err());
+    }
+}
@@ -1,13 +1,21 @@
 // OBI_ENGINE.rs - Order Book Imbalance Calculator with GPU Acceleration
 // COMPLEXITY: O(n) for CPU, O(1) for GPU batch processing
-// DETERMINISTIC: Uses fixed-point arithmetic for financial calculations
+// DETERMINISTIC: Fixed-point arithmetic mode (ArithmeticMode::FixedPoint) for ledger-sealed results
 
+use crate::physics::backend::{BackendRegistry, ComputeBackend, RayonBackend};
+use crate::physics::consolidated::{self, ConsolidatedResult, MarketState};
+use crate::physics::entropy;
+use crate::physics::fixed::{self, Fixed, FixedOverflow, FIXED_DECIMALS};
+use crate::physics::instrument::{self, InstrumentKey, ObiGroup, SymbolStats};
+use crate::physics::obi_columns::{ObiColumns, OrderBookColumns, Signal};
+use crate::physics::trades::{self, Trade, TradeClassifierConfig, TradeFlow};
+use crate::physics::validation::{self, ValidationConfig, ValidationCounts, ValidationError, ValidationMode};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
-
-#[cfg(feature = "cuda")]
-use cudarc::driver::{CudaDevice, CudaSlice};
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::Arc;
 
 /// Order Book Snapshot for OBI calculation
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -17,65 +25,613 @@ pub struct OrderBookSnapshot {
     pub ask_volume: f64,
     pub bid_price: f64,
     pub ask_price: f64,
+    #[serde(default)]
+    pub last_trade_price: Option<f64>, // For effective spread (None if no trade seen)
+    #[serde(default)]
+    pub symbol: Option<String>, // Instrument, e.g. "BTC-USD" (None = untagged)
+    #[serde(default)]
+    pub venue: Option<String>,  // Exchange the book came from
+}
+
+/// Single price level of a depth-aware order book
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct PriceLevel {
+    pub price: f64,
+    pub volume: f64,
+}
+
+/// Depth-aware Order Book Snapshot (best level first on each side)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DepthSnapshot {
+    pub timestamp: u64,
+    pub bids: Vec<PriceLevel>,
+    pub asks: Vec<PriceLevel>,
+    #[serde(default)]
+    pub last_trade_price: Option<f64>,
+    #[serde(default)]
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub venue: Option<String>,
+}
+
+impl DepthSnapshot {
+    /// Mid price from the best bid/ask, None if either side is empty
+    pub fn mid_price(&self) -> Option<f64> {
+        match (self.bids.first(), self.asks.first()) {
+            (Some(bid), Some(ask)) => Some((bid.price + ask.price) / 2.0),
+            _ => None,
+        }
+    }
+
+    /// Collapse to the top-of-book snapshot used by the single-level path
+    pub fn top_of_book(&self) -> OrderBookSnapshot {
+        let bid = self.bids.first().copied().unwrap_or(PriceLevel { price: 0.0, volume: 0.0 });
+        let ask = self.asks.first().copied().unwrap_or(PriceLevel { price: 0.0, volume: 0.0 });
+
+        OrderBookSnapshot {
+            timestamp: self.timestamp,
+            bid_volume: bid.volume,
+            ask_volume: ask.volume,
+            bid_price: bid.price,
+            ask_price: ask.price,
+            last_trade_price: self.last_trade_price,
+            symbol: self.symbol.clone(),
+            venue: self.venue.clone(),
+        }
+    }
+}
+
+/// Depth weighting for multi-level OBI
+/// Each level is weighted by exp(-decay * distance_from_mid_in_bps)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct DepthObiConfig {
+    pub levels: usize, // Max levels per side to include
+    pub decay: f64,    // Decay per basis point of distance from mid (0 = flat weighting)
+}
+
+impl Default for DepthObiConfig {
+    fn default() -> Self {
+        DepthObiConfig {
+            levels: 10,
+            decay: 0.1,
+        }
+    }
 }
 
-/// OBI Result with entropy evaluation
+/// Per-batch OBI configuration
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct ObiConfig {
+    pub tick_size: f64,   // Minimum price increment, used for spread_ticks
+    pub renyi_alpha: f64, // Order of the reported Rényi entropy (2 = collision entropy)
+}
+
+impl Default for ObiConfig {
+    fn default() -> Self {
+        ObiConfig {
+            tick_size: 0.01,
+            renyi_alpha: 2.0,
+        }
+    }
+}
+
+/// Numeric path used by an engine for OBI and entropy
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArithmeticMode {
+    #[default]
+    Float, // f64 (fast, platform-dependent in the last ulp)
+    FixedPoint, // 9-decimal integer math (bit-identical everywhere); top-of-book OBI only
+}
+
+/// Raw fixed-point OBI/entropy, exactly re-verifiable from the sealed inputs
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedObi {
+    pub obi: i64,             // OBI * 10^decimals
+    pub entropy: i64,         // Shannon entropy (bits) of [bid, ask] * 10^decimals, integer-only
+    pub relative_spread: i64, // Relative spread * 10^decimals
+    pub decimals: u32,        // Scale of the raw values
+}
+
+/// OBI Result with entropy evaluation and spread analytics.
+/// Migration: `entropy` used to hold spread / avg_price. That value is now `relative_spread`
+/// (`FixedObi::relative_spread` when sealed); `entropy` is the Shannon entropy of the book.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ObiResult {
     pub timestamp: u64,
     pub obi: f64,     // Order Book Imbalance: (bid_vol - ask_vol) / (bid_vol + ask_vol)
-    pub entropy: f64, // Market entropy: spread / avg_price
+    pub entropy: f64,       // Shannon entropy (bits) of the normalised volume distribution across levels
+    pub renyi_entropy: f64, // Rényi entropy (bits) of order ObiConfig::renyi_alpha
+    pub kl_divergence: Option<f64>, // D(this book || previous book of the same symbol/venue), bits; None for the first
+    pub relative_spread: f64, // Spread / avg_price (1.0 when there is no price)
     pub signal: String, // BUY_PRESSURE | SELL_PRESSURE | NEUTRAL
+    pub microprice: f64,   // (bid_p * ask_v + ask_p * bid_v) / (bid_v + ask_v)
+    pub weighted_mid: f64, // Microprice of the per-side depth VWAPs (= microprice for top-of-book)
+    pub spread_bps: f64,   // Spread / mid * 10_000
+    pub spread_ticks: f64, // Spread / tick_size
+    pub effective_spread: Option<f64>, // 2 * |last_trade - mid|, None without a trade
+    pub fixed: Option<FixedObi>, // Set in ArithmeticMode::FixedPoint
+    #[serde(default)]
+    pub validation: Option<ValidationError>, // Set for invalid snapshots in ValidationMode::Flag
+}
+
+/// Validated OBI batch: results plus the counters from the validation pass
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ValidatedObiBatch {
+    pub results: Vec<ObiResult>,
+    pub indices: Vec<usize>, // Input index of each result (differs from 0..n in ValidationMode::Skip)
+    pub counts: ValidationCounts,
 }
 
-/// Physics Engine Singleton (Thread-Safe)
-static PHYSICS_ENGINE: Mutex<Option<PhysicsEngine>> = Mutex::new(None);
+/// Validated columnar OBI: output columns hold the kept rows only
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ValidatedObiColumns {
+    pub columns: ObiColumns,
+    pub indices: Vec<usize>,                      // Input row of each output row
+    pub counts: ValidationCounts,
+    pub flagged: Vec<(usize, ValidationError)>, // Invalid input rows kept in ValidationMode::Flag
+}
+
+/// OFI aggregation configuration
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct OfiConfig {
+    pub bucket_ms: u64, // Time bucket width (0 = every tick is its own bucket)
+}
+
+/// Order Flow Imbalance point, aligned with the snapshot timestamp
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OfiResult {
+    pub timestamp: u64,
+    pub ofi: f64,          // Cont-style OFI contribution e_n of this tick (0 for the first)
+    pub bucket_start: u64, // Start of the time bucket this tick falls in
+    pub bucket_ofi: f64,   // Running OFI within the bucket up to and including this tick
+    #[serde(default)]
+    pub validation: Option<ValidationError>, // Set for invalid snapshots in ValidationMode::Flag
+}
+
+/// Validated OFI batch: results plus the counters from the validation pass
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ValidatedOfiBatch {
+    pub results: Vec<OfiResult>,
+    pub indices: Vec<usize>, // Input index of each result (differs from 0..n in ValidationMode::Skip)
+    pub counts: ValidationCounts,
+}
+
+/// Signal thresholds: obi > buy => BUY_PRESSURE, obi < sell => SELL_PRESSURE
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct SignalThresholds {
+    pub buy: f64,
+    pub sell: f64,
+}
 
+impl Default for SignalThresholds {
+    fn default() -> Self {
+        SignalThresholds {
+            buy: 0.3,
+            sell: -0.3,
+        }
+    }
+}
+
+impl SignalThresholds {
+    /// Signal Classification: BUY_PRESSURE | SELL_PRESSURE | NEUTRAL
+    pub fn classify(&self, obi: f64) -> String {
+        self.classify_signal(obi).as_str().to_string()
+    }
+
+    /// Allocation-free classification for columnar batches
+    pub fn classify_signal(&self, obi: f64) -> Signal {
+        if obi > self.buy {
+            Signal::BuyPressure
+        } else if obi < self.sell {
+            Signal::SellPressure
+        } else {
+            Signal::Neutral
+        }
+    }
+}
+
+/// Per-instance engine configuration
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EngineConfig {
+    pub thresholds: SignalThresholds,
+    pub backend: String, // Registry name ("scalar" | "rayon" | "cuda" | ...) or "auto"
+    pub arithmetic: ArithmeticMode,
+    pub threads: usize, // Rayon pool size (0 = one thread per core)
+    #[serde(default)]
+    pub instrument_thresholds: HashMap<String, SignalThresholds>, // "SYMBOL" or "SYMBOL@VENUE" overrides
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        EngineConfig {
+            thresholds: SignalThresholds::default(),
+            backend: "auto".to_string(),
+            arithmetic: ArithmeticMode::Float,
+            threads: 0,
+            instrument_thresholds: HashMap::new(),
+        }
+    }
+}
+
+/// Typed engine errors
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineError {
+    NotInitialized,             // Engine used before init()
+    BackendUnavailable(String), // Requested backend cannot be brought up
+    ThreadPool(String),         // Rayon pool construction failed
+    InvalidConfig(String),
+    InvalidSnapshot { index: usize, error: ValidationError }, // Bad input row (index 0 for a single streamed tick)
+    FixedPointOverflow { index: usize, value: f64 },          // Input outside the fixed-point range
+    SymbolMismatch { index: usize, expected: String, found: String }, // Consolidating books of different symbols
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::NotInitialized => {
+                write!(f, "ENGINE_NOT_INITIALIZED: call init() before using the engine")
+            }
+            EngineError::BackendUnavailable(msg) => write!(f, "BACKEND_UNAVAILABLE: {}", msg),
+            EngineError::ThreadPool(msg) => write!(f, "THREAD_POOL: {}", msg),
+            EngineError::InvalidConfig(msg) => write!(f, "INVALID_CONFIG: {}", msg),
+            EngineError::InvalidSnapshot { index, error } => {
+                write!(f, "INVALID_SNAPSHOT: snapshot {}: {}", index, error)
+            }
+            EngineError::FixedPointOverflow { index, value } => {
+                write!(f, "FIXED_POINT_OVERFLOW: snapshot {}: {}", index, FixedOverflow(*value))
+            }
+            EngineError::SymbolMismatch { index, expected, found } => {
+                write!(f, "SYMBOL_MISMATCH: book {} is {}, expected {}", index, found, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
+/// Physics Engine instance: owns its config, thread pool and backend.
+/// Independent instances can coexist in one process.
 pub struct PhysicsEngine {
-    #[cfg(feature = "cuda")]
-    gpu_device: Option<CudaDevice>,
-    cpu_fallback: bool,
+    config: EngineConfig,
+    pool: rayon::ThreadPool,
+    backend: Arc<dyn ComputeBackend>,
+    fallback: Arc<dyn ComputeBackend>, // Used when the active backend rejects a batch
 }
 
 impl PhysicsEngine {
-    /// Initialize GPU or fallback to CPU
-    pub fn init() -> Result<(), String> {
-        let mut engine = PHYSICS_ENGINE.lock().unwrap();
-
-        #[cfg(feature = "cuda")]
-        {
-            match CudaDevice::new(0) {
-                Ok(device) => {
-                    println!(
-                        "[PHYSICS_ENGINE] ✓ CUDA Device Initialized: {}",
-                        device.name()
-                    );
-                    *engine = Some(PhysicsEngine {
-                        gpu_device: Some(device),
-                        cpu_fallback: false,
-                    });
-                    return Ok(());
-                }
-                Err(e) => {
-                    eprintln!("[PHYSICS_ENGINE] ⚠ CUDA Init Failed: {:?}", e);
-                    eprintln!("[PHYSICS_ENGINE] → Falling back to CPU (Rayon)");
-                }
+    /// Initialize with the default backend registry
+    pub fn new(config: EngineConfig) -> Result<Self, EngineError> {
+        Self::with_registry(config, &BackendRegistry::with_defaults())
+    }
+
+    /// Initialize, selecting the backend from `registry`.
+    /// "auto" prefers CUDA when registered with working kernels (Float only), otherwise Rayon.
+    pub fn with_registry(config: EngineConfig, registry: &BackendRegistry) -> Result<Self, EngineError> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(config.threads)
+            .build()
+            .map_err(|e| EngineError::ThreadPool(e.to_string()))?;
+
+        let fallback = registry
+            .get("rayon")
+            .unwrap_or_else(|| Arc::new(RayonBackend));
+
+        let backend = if config.backend.eq_ignore_ascii_case("auto") {
+            registry
+                .get("cuda")
+                .filter(|cuda| cuda.has_kernels() && config.arithmetic == ArithmeticMode::Float)
+                .unwrap_or_else(|| fallback.clone())
+        } else {
+            registry.get(&config.backend).ok_or_else(|| {
+                EngineError::BackendUnavailable(format!(
+                    "unknown backend '{}' (registered: {})",
+                    config.backend,
+                    registry.names().join(", ")
+                ))
+            })?
+        };
+
+        if config.arithmetic == ArithmeticMode::FixedPoint && !backend.supports_fixed_point() {
+            return Err(EngineError::InvalidConfig(format!(
+                "backend '{}' does not support fixed-point arithmetic",
+                backend.name()
+            )));
+        }
+
+        println!(
+            "[PHYSICS_ENGINE] ✓ {} backend active ({} threads, {:?} arithmetic)",
+            backend.name(),
+            pool.current_num_threads(),
+            config.arithmetic
+        );
+
+        Ok(PhysicsEngine {
+            config,
+            pool,
+            backend,
+            fallback,
+        })
+    }
+
+    /// Name of the backend in use
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.name()
+    }
+
+    /// Run a kernel on the active backend inside this engine's pool,
+    /// falling back to Rayon if the backend cannot run it (input errors are returned as-is)
+    fn dispatch<T: Send>(
+        &self,
+        kernel: impl Fn(&dyn ComputeBackend) -> Result<T, EngineError> + Sync,
+    ) -> Result<T, EngineError> {
+        self.pool.install(|| match kernel(self.backend.as_ref()) {
+            Ok(results) => Ok(results),
+            Err(e @ EngineError::BackendUnavailable(_)) if !Arc::ptr_eq(&self.backend, &self.fallback) => {
+                eprintln!(
+                    "[OBI_ENGINE] {} failed: {}, using {}",
+                    self.backend.name(),
+                    e,
+                    self.fallback.name()
+                );
+                kernel(self.fallback.as_ref())
+            }
+            Err(e) => Err(e),
+        })
+    }
+
+    /// Calculate OBI batch on this engine's backend and thread pool
+    pub fn calculate_obi_batch(
+        &self,
+        snapshots: &[OrderBookSnapshot],
+        config: &ObiConfig,
+    ) -> Result<Vec<ObiResult>, EngineError> {
+        self.obi_batch_with(snapshots, config, &self.config.thresholds)
+    }
+
+    /// OBI batch in the engine's arithmetic mode with explicit thresholds
+    fn obi_batch_with(
+        &self,
+        snapshots: &[OrderBookSnapshot],
+        config: &ObiConfig,
+        thresholds: &SignalThresholds,
+    ) -> Result<Vec<ObiResult>, EngineError> {
+        let mut results = match self.config.arithmetic {
+            ArithmeticMode::FixedPoint => {
+                self.dispatch(|backend| backend.obi_batch_fixed(snapshots, config, thresholds))?
             }
+            ArithmeticMode::Float => self.dispatch(|backend| backend.obi_batch(snapshots, config, thresholds))?,
+        };
+
+        let distributions: Vec<Vec<f64>> = snapshots.iter().map(entropy::top_of_book_distribution).collect();
+        attach_kl(&mut results, &distributions, snapshots.iter().map(InstrumentKey::of));
+        Ok(results)
+    }
+
+    /// Split a mixed batch by symbol/venue and classify each instrument with its own thresholds
+    pub fn calculate_grouped_obi_batch(
+        &self,
+        snapshots: &[OrderBookSnapshot],
+        config: &ObiConfig,
+    ) -> Result<Vec<ObiGroup>, EngineError> {
+        instrument::group_indices(snapshots)
+            .into_iter()
+            .map(|(key, indices)| {
+                let group: Vec<OrderBookSnapshot> = indices.iter().map(|&i| snapshots[i].clone()).collect();
+                let thresholds = key.thresholds(&self.config.instrument_thresholds, self.config.thresholds);
+
+                let results = self.obi_batch_with(&group, config, &thresholds)?;
+
+                Ok(ObiGroup {
+                    stats: SymbolStats::from_results(&results),
+                    key,
+                    thresholds,
+                    indices,
+                    results,
+                })
+            })
+            .collect()
+    }
+
+    /// Merge venue-tagged depth books into an NBBO book, then compute OBI/microprice on it
+    /// and flag cross-venue locked/crossed markets
+    pub fn calculate_consolidated_obi(
+        &self,
+        books: &[DepthSnapshot],
+        obi_config: &ObiConfig,
+        depth_config: &DepthObiConfig,
+    ) -> Result<ConsolidatedResult, EngineError> {
+        let book = consolidated::consolidate(books, obi_config.tick_size)?;
+        let key = InstrumentKey {
+            symbol: book.symbol.clone(),
+            venue: None,
+        };
+        let thresholds = key.thresholds(&self.config.instrument_thresholds, self.config.thresholds);
+
+        // Same backend dispatch and arithmetic guard as every other depth batch
+        self.depth_float_only()?;
+        let snapshot = book.to_depth_snapshot();
+        let obi = self
+            .dispatch(|backend| {
+                backend.depth_obi_batch(std::slice::from_ref(&snapshot), obi_config, depth_config, &thresholds)
+            })?
+            .pop()
+            .expect("one consolidated book in, one result out");
+        let arbitrage = consolidated::arbitrage_candidates(books, obi_config.tick_size);
+        let state = match arbitrage.first() {
+            Some(best) if best.edge > 0.0 => MarketState::Crossed,
+            Some(_) => MarketState::Locked,
+            None => MarketState::Normal,
+        };
+
+        Ok(ConsolidatedResult {
+            book,
+            obi,
+            state,
+            arbitrage,
+        })
+    }
+
+    /// Validate, then calculate OBI. Strict rejects the batch, Skip drops invalid
+    /// snapshots, Flag keeps them and attaches the error to their result.
+    pub fn calculate_obi_batch_validated(
+        &self,
+        snapshots: &[OrderBookSnapshot],
+        config: &ObiConfig,
+        validation: &ValidationConfig,
+    ) -> Result<ValidatedObiBatch, EngineError> {
+        let (outcomes, counts) = validation::validate_batch(snapshots, validation);
+        let indices = validated_rows(&outcomes, validation.mode)?;
+
+        let mut results = self.calculate_obi_batch(&select_rows(snapshots, &indices), config)?;
+        for (result, &index) in results.iter_mut().zip(&indices) {
+            result.validation = outcomes[index].err();
         }
+        Ok(ValidatedObiBatch { results, indices, counts })
+    }
 
-        // CPU Fallback
-        *engine = Some(PhysicsEngine {
-            #[cfg(feature = "cuda")]
-            gpu_device: None,
-            cpu_fallback: true,
+    /// `calculate_obi_batch_validated` for depth books; every level is checked
+    pub fn calculate_depth_obi_batch_validated(
+        &self,
+        snapshots: &[DepthSnapshot],
+        obi_config: &ObiConfig,
+        config: &DepthObiConfig,
+        validation: &ValidationConfig,
+    ) -> Result<ValidatedObiBatch, EngineError> {
+        let (outcomes, counts) = validation::validate_depth_batch(snapshots, validation);
+        let indices = validated_rows(&outcomes, validation.mode)?;
+
+        let mut results = self.calculate_depth_obi_batch(&select_rows(snapshots, &indices), obi_config, config)?;
+        for (result, &index) in results.iter_mut().zip(&indices) {
+            result.validation = outcomes[index].err();
+        }
+        Ok(ValidatedObiBatch { results, indices, counts })
+    }
+
+    /// Validate, then calculate OFI. In Skip mode each kept snapshot pairs with the previous kept one.
+    pub fn calculate_ofi_batch_validated(
+        &self,
+        snapshots: &[OrderBookSnapshot],
+        config: &OfiConfig,
+        validation: &ValidationConfig,
+    ) -> Result<ValidatedOfiBatch, EngineError> {
+        let (outcomes, counts) = validation::validate_batch(snapshots, validation);
+        let indices = validated_rows(&outcomes, validation.mode)?;
+
+        let mut results = self.calculate_ofi_batch(&select_rows(snapshots, &indices), config);
+        for (result, &index) in results.iter_mut().zip(&indices) {
+            result.validation = outcomes[index].err();
+        }
+        Ok(ValidatedOfiBatch { results, indices, counts })
+    }
+
+    /// Validate, then calculate columnar OBI. Rows have no timestamps, so only prices and volumes are checked.
+    pub fn calculate_obi_columns_validated(
+        &self,
+        input: OrderBookColumns<'_>,
+        mode: ValidationMode,
+    ) -> Result<ValidatedObiColumns, EngineError> {
+        let (outcomes, counts) = validation::validate_columns(&input);
+        let indices = validated_rows(&outcomes, mode)?;
+
+        let columns = if indices.len() == input.len() {
+            self.calculate_obi_columns(input)?
+        } else {
+            let gather = |column: &[f64]| -> Vec<f64> { indices.iter().map(|&i| column[i]).collect() };
+            let (bid_price, bid_volume) = (gather(input.bid_price), gather(input.bid_volume));
+            let (ask_price, ask_volume) = (gather(input.ask_price), gather(input.ask_volume));
+            self.calculate_obi_columns(OrderBookColumns::new(&bid_price, &bid_volume, &ask_price, &ask_volume)?)?
+        };
+
+        let flagged = indices
+            .iter()
+            .filter_map(|&i| outcomes[i].err().map(|error| (i, error)))
+            .collect();
+        Ok(ValidatedObiColumns {
+            columns,
+            indices,
+            counts,
+            flagged,
+        })
+    }
+
+    /// Calculate OBI over struct-of-arrays input (float mode only)
+    pub fn calculate_obi_columns(&self, input: OrderBookColumns<'_>) -> Result<ObiColumns, EngineError> {
+        if self.config.arithmetic == ArithmeticMode::FixedPoint {
+            return Err(EngineError::InvalidConfig(
+                "columnar OBI is float-only; use calculate_obi_batch in fixed-point mode".to_string(),
+            ));
+        }
+        self.dispatch(|backend| backend.obi_columns(input, &self.config.thresholds))
+    }
+
+    /// Calculate depth-weighted OBI batch (float mode only)
+    pub fn calculate_depth_obi_batch(
+        &self,
+        snapshots: &[DepthSnapshot],
+        obi_config: &ObiConfig,
+        config: &DepthObiConfig,
+    ) -> Result<Vec<ObiResult>, EngineError> {
+        self.depth_float_only()?;
+        let mut results = self.dispatch(|backend| {
+            backend.depth_obi_batch(snapshots, obi_config, config, &self.config.thresholds)
+        })?;
+
+        let distributions: Vec<Vec<f64>> = snapshots
+            .iter()
+            .map(|snapshot| entropy::depth_distribution(snapshot, config.levels))
+            .collect();
+        let keys = snapshots.iter().map(|snapshot| InstrumentKey {
+            symbol: snapshot.symbol.clone(),
+            venue: snapshot.venue.clone(),
         });
+        attach_kl(&mut results, &distributions, keys);
+        Ok(results)
+    }
 
-        println!("[PHYSICS_ENGINE] ✓ CPU Mode Active (Rayon Parallelism)");
+    fn depth_float_only(&self) -> Result<(), EngineError> {
+        if self.config.arithmetic == ArithmeticMode::FixedPoint {
+            return Err(EngineError::InvalidConfig(
+                "depth OBI weights levels with exp() and is float-only; use calculate_obi_batch in fixed-point mode"
+                    .to_string(),
+            ));
+        }
         Ok(())
     }
 
+    /// Calculate per-snapshot manifold curvature
+    pub fn calculate_curvature(&self, snapshots: &[OrderBookSnapshot]) -> Result<Vec<f64>, EngineError> {
+        self.dispatch(|backend| backend.curvature_batch(snapshots))
+    }
+
+    /// Calculate OFI series from consecutive snapshots
+    pub fn calculate_ofi_batch(&self, snapshots: &[OrderBookSnapshot], config: &OfiConfig) -> Vec<OfiResult> {
+        self.pool
+            .install(|| Self::calculate_ofi_batch_cpu(snapshots, config))
+    }
+
+    /// Classify a trade tape by aggressor and bucket its signed volume.
+    /// `quotes` (timestamp ordered) supply the Lee-Ready context and may be empty.
+    pub fn calculate_trade_flow(
+        &self,
+        trades: &[Trade],
+        quotes: &[OrderBookSnapshot],
+        config: &TradeClassifierConfig,
+        bucket_ms: u64,
+    ) -> Result<TradeFlow, EngineError> {
+        trades::validate_tape(trades, quotes, config)?;
+        let classified = trades::classify_trades(trades, quotes, config);
+        let buckets = trades::aggregate_trade_flow(&classified, bucket_ms);
+        Ok(TradeFlow {
+            trades: classified,
+            buckets,
+        })
+    }
+
     /// Calculate OBI for a single snapshot
-    fn calculate_single_obi(snapshot: &OrderBookSnapshot) -> ObiResult {
+    pub(crate) fn calculate_single_obi(
+        snapshot: &OrderBookSnapshot,
+        config: &ObiConfig,
+        thresholds: &SignalThresholds,
+    ) -> ObiResult {
         let total_volume = snapshot.bid_volume + snapshot.ask_volume;
 
         // OBI Formula: (Bid Volume - Ask Volume) / Total Volume
@@ -85,113 +641,501 @@ impl PhysicsEngine {
             0.0
         };
 
-        // Entropy: Spread / Average Price
+        // Relative spread: Spread / Average Price
         let avg_price = (snapshot.bid_price + snapshot.ask_price) / 2.0;
         let spread = (snapshot.ask_price - snapshot.bid_price).abs();
-        let entropy = if avg_price > 0.0 {
+        let relative_spread = if avg_price > 0.0 {
             spread / avg_price
         } else {
-            1.0 // Max entropy (no liquidity)
+            1.0 // No price
         };
 
-        // Signal Classification
-        let signal = if obi > 0.3 {
-            "BUY_PRESSURE".to_string()
-        } else if obi < -0.3 {
-            "SELL_PRESSURE".to_string()
+        // Microprice: mid skewed towards the side with less resting volume
+        let microprice = if total_volume > 0.0 {
+            (snapshot.bid_price * snapshot.ask_volume + snapshot.ask_price * snapshot.bid_volume)
+                / total_volume
         } else {
-            "NEUTRAL".to_string()
+            avg_price
         };
 
+        let spread_bps = if avg_price > 0.0 {
+            spread / avg_price * 10_000.0
+        } else {
+            0.0
+        };
+        let spread_ticks = if config.tick_size > 0.0 {
+            spread / config.tick_size
+        } else {
+            0.0
+        };
+
+        // Effective spread: round-trip cost implied by the last trade
+        let effective_spread = snapshot
+            .last_trade_price
+            .map(|trade| 2.0 * (trade - avg_price).abs());
+
         ObiResult {
             timestamp: snapshot.timestamp,
             obi,
-            entropy,
-            signal,
+            entropy: entropy::binary_entropy(snapshot.bid_volume, snapshot.ask_volume),
+            renyi_entropy: entropy::renyi_entropy(&entropy::top_of_book_distribution(snapshot), config.renyi_alpha),
+            kl_divergence: None,
+            relative_spread,
+            signal: thresholds.classify(obi),
+            microprice,
+            weighted_mid: microprice,
+            spread_bps,
+            spread_ticks,
+            effective_spread,
+            fixed: None,
+            validation: None,
+        }
+    }
+
+    /// Calculate OBI for a single snapshot with deterministic fixed-point math.
+    /// OBI, Shannon entropy, relative spread and the signal come from integer arithmetic only;
+    /// Rényi entropy, KL divergence and the other spread analytics are still reported in f64.
+    /// Volumes and prices are quantized into i128, so only non-finite or |x| > 1e18 inputs fail.
+    pub(crate) fn calculate_single_obi_fixed(
+        snapshot: &OrderBookSnapshot,
+        config: &ObiConfig,
+        thresholds: &SignalThresholds,
+    ) -> Result<ObiResult, FixedOverflow> {
+        let bid_volume = fixed::quantize_wide(snapshot.bid_volume)?;
+        let ask_volume = fixed::quantize_wide(snapshot.ask_volume)?;
+        let bid_price = fixed::quantize_wide(snapshot.bid_price)?;
+        let ask_price = fixed::quantize_wide(snapshot.ask_price)?;
+
+        // OBI = (bid_vol - ask_vol) / (bid_vol + ask_vol)
+        let total_volume = bid_volume + ask_volume;
+        let obi = if total_volume > 0 {
+            Fixed::from_ratio(bid_volume - ask_volume, total_volume)
+        } else {
+            Fixed::ZERO
+        };
+        let entropy = entropy::binary_entropy_fixed(bid_volume, ask_volume);
+
+        // Relative spread = spread / ((bid + ask) / 2) = 2 * spread / (bid + ask)
+        let price_sum = bid_price + ask_price;
+        let relative_spread = if price_sum > 0 {
+            Fixed::from_ratio(2 * (ask_price - bid_price).abs(), price_sum)
+        } else {
+            Fixed::from_ratio(1, 1) // No price
+        };
+
+        // Thresholds are quantized, then compared exactly in fixed-point
+        let signal = if obi > Fixed::from_f64(thresholds.buy)? {
+            "BUY_PRESSURE".to_string()
+        } else if obi < Fixed::from_f64(thresholds.sell)? {
+            "SELL_PRESSURE".to_string()
+        } else {
+            "NEUTRAL".to_string()
+        };
+
+        let mut result = Self::calculate_single_obi(snapshot, config, thresholds);
+        result.obi = obi.to_f64();
+        result.entropy = entropy.to_f64();
+        result.relative_spread = relative_spread.to_f64();
+        result.signal = signal;
+        result.fixed = Some(FixedObi {
+            obi: obi.raw(),
+            entropy: entropy.raw(),
+            relative_spread: relative_spread.raw(),
+            decimals: FIXED_DECIMALS,
+        });
+        Ok(result)
+    }
+
+    /// Calculate depth-weighted OBI for a single multi-level snapshot
+    pub(crate) fn calculate_depth_obi(
+        snapshot: &DepthSnapshot,
+        obi_config: &ObiConfig,
+        config: &DepthObiConfig,
+        thresholds: &SignalThresholds,
+    ) -> ObiResult {
+        // Spreads stay anchored to top-of-book; entropy covers every included level
+        let mut result = Self::calculate_single_obi(&snapshot.top_of_book(), obi_config, thresholds);
+        let distribution = entropy::depth_distribution(snapshot, config.levels);
+        result.entropy = entropy::shannon_entropy(&distribution);
+        result.renyi_entropy = entropy::renyi_entropy(&distribution, obi_config.renyi_alpha);
+
+        let mid = match snapshot.mid_price() {
+            Some(mid) if mid > 0.0 => mid,
+            _ => return result,
+        };
+
+        // Weight: exp(-decay * |price - mid| in bps)
+        let weighted_volume = |levels: &[PriceLevel]| -> f64 {
+            levels
+                .iter()
+                .take(config.levels)
+                .map(|level| {
+                    let distance_bps = (level.price - mid).abs() / mid * 10_000.0;
+                    level.volume * (-config.decay * distance_bps).exp()
+                })
+                .sum()
+        };
+
+        let bid_weighted = weighted_volume(&snapshot.bids);
+        let ask_weighted = weighted_volume(&snapshot.asks);
+        let total_weighted = bid_weighted + ask_weighted;
+
+        let obi = if total_weighted > 0.0 {
+            (bid_weighted - ask_weighted) / total_weighted
+        } else {
+            0.0
+        };
+
+        // Weighted mid: microprice of each side's VWAP over all of its own levels, so a
+        // deeper side is never truncated to the shorter one
+        let side = |levels: &[PriceLevel]| -> (f64, f64) {
+            levels
+                .iter()
+                .take(config.levels)
+                .fold((0.0, 0.0), |(pv, v), level| (pv + level.price * level.volume, v + level.volume))
+        };
+        let (bid_pv, bid_volume) = side(&snapshot.bids);
+        let (ask_pv, ask_volume) = side(&snapshot.asks);
+        if bid_volume > 0.0 && ask_volume > 0.0 {
+            let (bid_vwap, ask_vwap) = (bid_pv / bid_volume, ask_pv / ask_volume);
+            result.weighted_mid = (bid_vwap * ask_volume + ask_vwap * bid_volume) / (bid_volume + ask_volume);
         }
+
+        result.obi = obi;
+        result.signal = thresholds.classify(obi);
+        result
     }
 
     /// Batch OBI calculation (CPU with Rayon)
-    pub fn calculate_obi_batch_cpu(snapshots: &[OrderBookSnapshot]) -> Vec<ObiResult> {
+    pub fn calculate_obi_batch_cpu(
+        snapshots: &[OrderBookSnapshot],
+        config: &ObiConfig,
+        thresholds: &SignalThresholds,
+    ) -> Vec<ObiResult> {
         snapshots
             .par_iter()
-            .map(Self::calculate_single_obi)
+            .map(|snapshot| Self::calculate_single_obi(snapshot, config, thresholds))
             .collect()
     }
 
-    /// Batch OBI calculation (GPU with CUDA)
-    #[cfg(feature = "cuda")]
-    pub fn calculate_obi_batch_gpu(
-        device: &CudaDevice,
+    /// Batch OBI calculation in deterministic fixed-point (CPU with Rayon)
+    pub fn calculate_obi_batch_fixed(
         snapshots: &[OrderBookSnapshot],
-    ) -> Result<Vec<ObiResult>, String> {
-        // Extract data for GPU transfer
-        let bid_volumes: Vec<f32> = snapshots.iter().map(|s| s.bid_volume as f32).collect();
-        let ask_volumes: Vec<f32> = snapshots.iter().map(|s| s.ask_volume as f32).collect();
-        let bid_prices: Vec<f32> = snapshots.iter().map(|s| s.bid_price as f32).collect();
-        let ask_prices: Vec<f32> = snapshots.iter().map(|s| s.ask_price as f32).collect();
-
-        // Allocate GPU memory
-        let d_bid_vol = device
-            .htod_copy(bid_volumes)
-            .map_err(|e| format!("GPU alloc failed: {:?}", e))?;
-        let d_ask_vol = device
-            .htod_copy(ask_volumes)
-            .map_err(|e| format!("GPU alloc failed: {:?}", e))?;
-        let d_bid_price = device
-            .htod_copy(bid_prices)
-            .map_err(|e| format!("GPU alloc failed: {:?}", e))?;
-        let d_ask_price = device
-            .htod_copy(ask_prices)
-            .map_err(|e| format!("GPU alloc failed: {:?}", e))?;
-
-        // Allocate output buffers
-        let mut d_obi: CudaSlice<f32> = device
-            .alloc_zeros(snapshots.len())
-            .map_err(|e| format!("GPU alloc failed: {:?}", e))?;
-        let mut d_entropy: CudaSlice<f32> = device
-            .alloc_zeros(snapshots.len())
-            .map_err(|e| format!("GPU alloc failed: {:?}", e))?;
-
-        // TODO: Launch CUDA kernel here (requires .cu file compilation)
-        // For now, fallback to CPU
-        drop(d_bid_vol);
-        drop(d_ask_vol);
-        drop(d_bid_price);
-        drop(d_ask_price);
-        drop(d_obi);
-        drop(d_entropy);
-
-        Err("GPU kernel not implemented yet, use CPU fallback".to_string())
-    }
-}
-
-/// Public API: Calculate OBI batch
-pub fn calculate_obi_batch(snapshots: &[OrderBookSnapshot]) -> Vec<ObiResult> {
-    let engine = PHYSICS_ENGINE.lock().unwrap();
-
-    match &*engine {
-        Some(eng) => {
-            #[cfg(feature = "cuda")]
-            {
-                if let Some(ref device) = eng.gpu_device {
-                    match PhysicsEngine::calculate_obi_batch_gpu(device, snapshots) {
-                        Ok(results) => return results,
-                        Err(e) => {
-                            eprintln!("[OBI_ENGINE] GPU failed: {}, using CPU", e);
-                        }
-                    }
-                }
+        config: &ObiConfig,
+        thresholds: &SignalThresholds,
+    ) -> Result<Vec<ObiResult>, EngineError> {
+        snapshots
+            .par_iter()
+            .enumerate()
+            .map(|(index, snapshot)| Self::fixed_or_overflow(index, snapshot, config, thresholds))
+            .collect()
+    }
+
+    /// Fixed-point OBI of snapshot `index`, tagging overflow with its position in the batch
+    pub(crate) fn fixed_or_overflow(
+        index: usize,
+        snapshot: &OrderBookSnapshot,
+        config: &ObiConfig,
+        thresholds: &SignalThresholds,
+    ) -> Result<ObiResult, EngineError> {
+        Self::calculate_single_obi_fixed(snapshot, config, thresholds)
+            .map_err(|FixedOverflow(value)| EngineError::FixedPointOverflow { index, value })
+    }
+
+    /// Batch depth-weighted OBI calculation (CPU with Rayon)
+    pub fn calculate_depth_obi_batch_cpu(
+        snapshots: &[DepthSnapshot],
+        obi_config: &ObiConfig,
+        config: &DepthObiConfig,
+        thresholds: &SignalThresholds,
+    ) -> Vec<ObiResult> {
+        snapshots
+            .par_iter()
+            .map(|snapshot| Self::calculate_depth_obi(snapshot, obi_config, config, thresholds))
+            .collect()
+    }
+
+    /// Cont-Kukanov-Stoikov OFI contribution between two consecutive snapshots:
+    /// e_n = 1{Pb_n >= Pb_n-1} qb_n - 1{Pb_n <= Pb_n-1} qb_n-1
+    ///     - 1{Pa_n <= Pa_n-1} qa_n + 1{Pa_n >= Pa_n-1} qa_n-1
+    fn calculate_ofi_contribution(prev: &OrderBookSnapshot, curr: &OrderBookSnapshot) -> f64 {
+        let mut e = 0.0;
+
+        // Bid side: price up = new demand, price down = demand withdrawn
+        if curr.bid_price >= prev.bid_price {
+            e += curr.bid_volume;
+        }
+        if curr.bid_price <= prev.bid_price {
+            e -= prev.bid_volume;
+        }
+
+        // Ask side: price down = new supply, price up = supply withdrawn
+        if curr.ask_price <= prev.ask_price {
+            e -= curr.ask_volume;
+        }
+        if curr.ask_price >= prev.ask_price {
+            e += prev.ask_volume;
+        }
+
+        e
+    }
+
+    /// Batch OFI calculation over consecutive snapshots (CPU with Rayon)
+    pub fn calculate_ofi_batch_cpu(snapshots: &[OrderBookSnapshot], config: &OfiConfig) -> Vec<OfiResult> {
+        if snapshots.is_empty() {
+            return vec![];
+        }
+
+        // Per-tick contributions are independent once paired
+        let contributions: Vec<f64> = std::iter::once(0.0)
+            .chain(
+                snapshots
+                    .par_windows(2)
+                    .map(|pair| Self::calculate_ofi_contribution(&pair[0], &pair[1]))
+                    .collect::<Vec<f64>>(),
+            )
+            .collect();
+
+        // Bucket aggregation is sequential (running sum)
+        let mut results = Vec::with_capacity(snapshots.len());
+        let mut current_bucket: Option<u64> = None;
+        let mut bucket_ofi = 0.0;
+
+        for (snapshot, ofi) in snapshots.iter().zip(contributions) {
+            let bucket_start = if config.bucket_ms > 0 {
+                snapshot.timestamp - snapshot.timestamp % config.bucket_ms
+            } else {
+                snapshot.timestamp
+            };
+
+            if config.bucket_ms == 0 || current_bucket != Some(bucket_start) {
+                current_bucket = Some(bucket_start);
+                bucket_ofi = 0.0;
             }
+            bucket_ofi += ofi;
 
-            // CPU Fallback
-            PhysicsEngine::calculate_obi_batch_cpu(snapshots)
+            results.push(OfiResult {
+                timestamp: snapshot.timestamp,
+                ofi,
+                bucket_start,
+                bucket_ofi,
+                validation: None,
+            });
         }
-        None => {
-            eprintln!("[OBI_ENGINE] ⚠ Engine not initialized! Call init() first.");
-            vec![]
+
+        results
+    }
+}
+
+/// Input rows a batch computes on: Strict fails on the first invalid row,
+/// Skip keeps the valid rows, Flag keeps every row
+fn validated_rows(outcomes: &[Result<(), ValidationError>], mode: ValidationMode) -> Result<Vec<usize>, EngineError> {
+    match mode {
+        ValidationMode::Strict => match outcomes.iter().position(Result::is_err) {
+            Some(index) => Err(EngineError::InvalidSnapshot {
+                index,
+                error: outcomes[index].unwrap_err(),
+            }),
+            None => Ok((0..outcomes.len()).collect()),
+        },
+        ValidationMode::Skip => Ok((0..outcomes.len()).filter(|&i| outcomes[i].is_ok()).collect()),
+        ValidationMode::Flag => Ok((0..outcomes.len()).collect()),
+    }
+}
+
+/// The rows at `indices`, borrowed when every row is kept
+fn select_rows<'a, T: Clone>(items: &'a [T], indices: &[usize]) -> Cow<'a, [T]> {
+    if indices.len() == items.len() {
+        Cow::Borrowed(items)
+    } else {
+        Cow::Owned(indices.iter().map(|&i| items[i].clone()).collect())
+    }
+}
+
+/// KL divergence of each book from the previous book of the same instrument,
+/// written into each result (None for an instrument's first book in the batch)
+fn attach_kl(results: &mut [ObiResult], distributions: &[Vec<f64>], keys: impl Iterator<Item = InstrumentKey>) {
+    let mut previous: HashMap<InstrumentKey, usize> = HashMap::new();
+    for ((i, result), key) in results.iter_mut().enumerate().zip(keys) {
+        result.kl_divergence = previous
+            .insert(key, i)
+            .map(|prev| entropy::kl_divergence(&distributions[i], &distributions[prev]));
+    }
+}
+
+/// Engine slot that may not be initialised yet (backs the NAPI class)
+#[derive(Default)]
+pub struct EngineHandle {
+    config: EngineConfig,
+    engine: Option<Arc<PhysicsEngine>>,
+}
+
+impl EngineHandle {
+    pub fn new(config: EngineConfig) -> Self {
+        EngineHandle {
+            config,
+            engine: None,
         }
     }
+
+    /// Build (or rebuild) the engine from the stored config
+    pub fn init(&mut self) -> Result<Arc<PhysicsEngine>, EngineError> {
+        let engine = Arc::new(PhysicsEngine::new(self.config.clone())?);
+        self.engine = Some(engine.clone());
+        Ok(engine)
+    }
+
+    /// The live engine, or EngineError::NotInitialized
+    pub fn engine(&self) -> Result<Arc<PhysicsEngine>, EngineError> {
+        self.engine.clone().ok_or(EngineError::NotInitialized)
+    }
+}
+
+/// Streaming OBI configuration
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct StreamingObiConfig {
+    pub ewma_alpha: f64, // EWMA smoothing factor in (0, 1]
+    pub window: usize,   // Rolling window length (ticks) for mean/stddev
+    pub thresholds: SignalThresholds,
+}
+
+impl Default for StreamingObiConfig {
+    fn default() -> Self {
+        StreamingObiConfig {
+            ewma_alpha: 0.2,
+            window: 100,
+            thresholds: SignalThresholds::default(),
+        }
+    }
+}
+
+/// Per-tick output of the streaming engine
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StreamingObiState {
+    pub timestamp: u64,
+    pub obi: f64,          // Raw OBI of this tick
+    pub ewma_obi: f64,     // EWMA-smoothed OBI
+    pub rolling_mean: f64, // Mean of raw OBI over the window
+    pub rolling_std: f64,  // Population stddev of raw OBI over the window
+    pub z_score: f64,      // (obi - rolling_mean) / rolling_std, 0 if std is 0
+    pub persistence: u32,  // Consecutive ticks the smoothed signal has held
+    pub signal: String,    // Signal classified from the smoothed OBI
+}
+
+/// Rolling state kept for one symbol
+#[derive(Debug, Default)]
+struct SymbolStream {
+    ewma: Option<f64>,
+    window: VecDeque<f64>,
+    mean: f64,               // Welford running mean of the window
+    m2: f64,                 // Welford sum of squared deviations
+    since_recompute: usize,  // Evictions since the exact two-pass refresh
+    signal: String,
+    persistence: u32,
+}
+
+/// Stateful per-symbol OBI engine: push snapshots one at a time
+#[derive(Debug, Default)]
+pub struct StreamingObiEngine {
+    config: StreamingObiConfig,
+    streams: HashMap<String, SymbolStream>,
+}
+
+impl StreamingObiEngine {
+    pub fn new(config: StreamingObiConfig) -> Self {
+        StreamingObiEngine {
+            config,
+            streams: HashMap::new(),
+        }
+    }
+
+    /// Feed one snapshot for `symbol` and return the updated rolling state.
+    /// Non-finite snapshots are rejected so they cannot poison the rolling statistics.
+    pub fn push(&mut self, symbol: &str, snapshot: &OrderBookSnapshot) -> Result<StreamingObiState, EngineError> {
+        let fields = [snapshot.bid_price, snapshot.ask_price, snapshot.bid_volume, snapshot.ask_volume];
+        if fields.iter().any(|v| !v.is_finite()) {
+            return Err(EngineError::InvalidSnapshot {
+                index: 0,
+                error: ValidationError::NonFinite,
+            });
+        }
+        let obi = PhysicsEngine::calculate_single_obi(snapshot, &ObiConfig::default(), &self.config.thresholds).obi;
+        let config = self.config;
+        let stream = self.streams.entry(symbol.to_string()).or_default();
+
+        // EWMA: seeded with the first observation
+        let alpha = config.ewma_alpha.clamp(f64::EPSILON, 1.0);
+        let ewma = match stream.ewma {
+            Some(prev) => alpha * obi + (1.0 - alpha) * prev,
+            None => obi,
+        };
+        stream.ewma = Some(ewma);
+
+        // Rolling window with sliding Welford updates, refreshed exactly once per window
+        stream.window.push_back(obi);
+        let delta = obi - stream.mean;
+        stream.mean += delta / stream.window.len() as f64;
+        stream.m2 += delta * (obi - stream.mean);
+        while stream.window.len() > config.window.max(1) {
+            if let Some(old) = stream.window.pop_front() {
+                let delta = old - stream.mean;
+                stream.mean -= delta / stream.window.len() as f64;
+                stream.m2 -= delta * (old - stream.mean);
+                stream.since_recompute += 1;
+            }
+        }
+        if stream.since_recompute >= config.window.max(1) {
+            let n = stream.window.len() as f64;
+            stream.mean = stream.window.iter().sum::<f64>() / n;
+            stream.m2 = stream.window.iter().map(|x| (x - stream.mean).powi(2)).sum();
+            stream.since_recompute = 0;
+        }
+
+        let n = stream.window.len() as f64;
+        let rolling_mean = stream.mean;
+        // Only rounding between refreshes can take m2 below zero
+        let rolling_std = (stream.m2 / n).max(0.0).sqrt();
+        let z_score = if rolling_std > 1e-12 {
+            (obi - rolling_mean) / rolling_std
+        } else {
+            0.0
+        };
+
+        // Persistence: how long the smoothed signal has held
+        let signal = config.thresholds.classify(ewma);
+        if signal == stream.signal {
+            stream.persistence += 1;
+        } else {
+            stream.signal = signal.clone();
+            stream.persistence = 1;
+        }
+
+        Ok(StreamingObiState {
+            timestamp: snapshot.timestamp,
+            obi,
+            ewma_obi: ewma,
+            rolling_mean,
+            rolling_std,
+            z_score,
+            persistence: stream.persistence,
+            signal,
+        })
+    }
+
+    /// Drop rolling state for one symbol, or for all symbols if None
+    pub fn reset(&mut self, symbol: Option<&str>) {
+        match symbol {
+            Some(symbol) => {
+                self.streams.remove(symbol);
+            }
+            None => self.streams.clear(),
+        }
+    }
+
+    /// Symbols currently tracked
+    pub fn symbols(&self) -> Vec<String> {
+        self.streams.keys().cloned().collect()
+    }
 }
 
 #[cfg(test)]
@@ -200,7 +1144,7 @@ mod tests {
 
     #[test]
     fn test_obi_calculation() {
-        PhysicsEngine::init().unwrap();
+        let engine = PhysicsEngine::new(EngineConfig::default()).unwrap();
 
         let snapshot = OrderBookSnapshot {
             timestamp: 1000,
@@ -208,11 +1152,376 @@ mod tests {
             ask_volume: 50.0,
             bid_price: 99.0,
             ask_price: 101.0,
+            last_trade_price: None,
+            symbol: None,
+            venue: None,
         };
 
-        let results = calculate_obi_batch(&[snapshot]);
+        let results = engine.calculate_obi_batch(&[snapshot], &ObiConfig::default()).unwrap();
         assert_eq!(results.len(), 1);
         assert!(results[0].obi > 0.0); // Buy pressure
         assert_eq!(results[0].signal, "BUY_PRESSURE");
     }
+
+    #[test]
+    fn test_depth_obi_weights_levels_by_distance() {
+        let engine = PhysicsEngine::new(EngineConfig::default()).unwrap();
+
+        let level = |price: f64, volume: f64| PriceLevel { price, volume };
+
+        // Top of book leans to the ask, but a deep bid stack sits close to mid
+        let snapshot = DepthSnapshot {
+            timestamp: 2000,
+            bids: vec![level(99.99, 10.0), level(99.98, 200.0), level(99.97, 200.0)],
+            asks: vec![level(100.01, 50.0), level(101.00, 5000.0)],
+            last_trade_price: None,
+            symbol: None,
+            venue: None,
+        };
+
+        let top = engine
+            .calculate_obi_batch(&[snapshot.top_of_book()], &ObiConfig::default())
+            .unwrap();
+        assert_eq!(top[0].signal, "SELL_PRESSURE");
+
+        // Far ask wall (~100 bps away) is decayed to almost nothing
+        let config = DepthObiConfig { levels: 10, decay: 0.1 };
+        let results = engine
+            .calculate_depth_obi_batch(std::slice::from_ref(&snapshot), &ObiConfig::default(), &config)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].signal, "BUY_PRESSURE");
+        assert_eq!(results[0].relative_spread, top[0].relative_spread);
+
+        // Flat weighting over a single level reduces to top-of-book OBI
+        let flat = DepthObiConfig { levels: 1, decay: 0.0 };
+        let results = engine
+            .calculate_depth_obi_batch(std::slice::from_ref(&snapshot), &ObiConfig::default(), &flat)
+            .unwrap();
+        assert!((results[0].obi - top[0].obi).abs() < 1e-12);
+        assert_eq!(results[0].weighted_mid, top[0].microprice);
+
+        // Unequal depths: the third bid level still counts.
+        // Bid VWAP (99.99*10 + 99.98*200 + 99.97*200) / 410, ask VWAP (100.01*50 + 101*5000) / 5050
+        let results = engine
+            .calculate_depth_obi_batch(std::slice::from_ref(&snapshot), &ObiConfig::default(), &config)
+            .unwrap();
+        let bid_vwap = (99.99 * 10.0 + 99.98 * 200.0 + 99.97 * 200.0) / 410.0;
+        let ask_vwap = (100.01 * 50.0 + 101.00 * 5000.0) / 5050.0;
+        let expected = (bid_vwap * 5050.0 + ask_vwap * 410.0) / 5460.0;
+        assert!((results[0].weighted_mid - expected).abs() < 1e-9);
+        let two_levels = DepthObiConfig { levels: 2, ..config };
+        let truncated = engine
+            .calculate_depth_obi_batch(std::slice::from_ref(&snapshot), &ObiConfig::default(), &two_levels)
+            .unwrap();
+        assert!(truncated[0].weighted_mid != results[0].weighted_mid);
+    }
+
+    #[test]
+    fn test_depth_entropy_and_consecutive_kl() {
+        let book = |timestamp: u64, volumes: [f64; 4]| DepthSnapshot {
+            timestamp,
+            bids: vec![
+                PriceLevel { price: 99.9, volume: volumes[0] },
+                PriceLevel { price: 99.8, volume: volumes[1] },
+            ],
+            asks: vec![
+                PriceLevel { price: 100.1, volume: volumes[2] },
+                PriceLevel { price: 100.2, volume: volumes[3] },
+            ],
+            last_trade_price: None,
+            symbol: None,
+            venue: None,
+        };
+
+        let engine = PhysicsEngine::new(EngineConfig::default()).unwrap();
+        let books = [book(1, [5.0; 4]), book(2, [5.0; 4]), book(3, [17.0, 1.0, 1.0, 1.0])];
+        let results = engine
+            .calculate_depth_obi_batch(&books, &ObiConfig::default(), &DepthObiConfig::default())
+            .unwrap();
+
+        // Evenly spread volume over 4 levels: 2 bits, whatever the Rényi order
+        assert!((results[0].entropy - 2.0).abs() < 1e-12);
+        assert!((results[0].renyi_entropy - 2.0).abs() < 1e-12);
+        assert!(results[2].entropy < results[0].entropy);
+
+        assert_eq!(results[0].kl_divergence, None);
+        assert!(results[1].kl_divergence.unwrap().abs() < 1e-12);
+        assert!(results[2].kl_divergence.unwrap() > 0.1);
+
+        // The old metric survives as relative spread: 0.2 / 100
+        assert!((results[0].relative_spread - 0.002).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_kl_compares_books_of_the_same_instrument() {
+        let tick = |symbol: &str, bid_volume: f64, ask_volume: f64| OrderBookSnapshot {
+            timestamp: 1,
+            bid_volume,
+            ask_volume,
+            bid_price: 99.0,
+            ask_price: 101.0,
+            last_trade_price: None,
+            symbol: Some(symbol.to_string()),
+            venue: Some("X".to_string()),
+        };
+
+        // Interleaved BTC/ETH rows: each book is compared with its own predecessor only
+        let engine = PhysicsEngine::new(EngineConfig::default()).unwrap();
+        let batch = [tick("BTC", 90.0, 10.0), tick("ETH", 10.0, 90.0), tick("BTC", 90.0, 10.0), tick("ETH", 10.0, 90.0)];
+        let results = engine.calculate_obi_batch(&batch, &ObiConfig::default()).unwrap();
+
+        assert_eq!(results[0].kl_divergence, None);
+        assert_eq!(results[1].kl_divergence, None);
+        assert!(results[2].kl_divergence.unwrap().abs() < 1e-12);
+        assert!(results[3].kl_divergence.unwrap().abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_streaming_obi_tracks_symbols_independently() {
+        let mut engine = StreamingObiEngine::new(StreamingObiConfig {
+            ewma_alpha: 0.5,
+            window: 3,
+            ..Default::default()
+        });
+
+        let tick = |timestamp: u64, bid_volume: f64, ask_volume: f64| OrderBookSnapshot {
+            timestamp,
+            bid_volume,
+            ask_volume,
+            bid_price: 99.0,
+            ask_price: 101.0,
+            last_trade_price: None,
+            symbol: None,
+            venue: None,
+        };
+
+        // Sustained buy pressure on BTC: signal persists across ticks
+        let mut last = engine.push("BTC", &tick(1, 100.0, 20.0)).unwrap();
+        for t in 2..=5 {
+            last = engine.push("BTC", &tick(t, 100.0, 20.0)).unwrap();
+        }
+        assert_eq!(last.signal, "BUY_PRESSURE");
+        assert_eq!(last.persistence, 5);
+        assert_eq!(last.rolling_std, 0.0);
+        assert_eq!(last.z_score, 0.0);
+
+        // ETH has its own state
+        let eth = engine.push("ETH", &tick(1, 20.0, 100.0)).unwrap();
+        assert_eq!(eth.signal, "SELL_PRESSURE");
+        assert_eq!(eth.persistence, 1);
+
+        // A single opposing tick moves the EWMA only halfway
+        let flip = engine.push("BTC", &tick(6, 20.0, 100.0)).unwrap();
+        assert!((flip.ewma_obi - 0.0).abs() < 1e-12);
+        assert_eq!(flip.signal, "NEUTRAL");
+        assert_eq!(flip.persistence, 1);
+        assert!(flip.z_score < 0.0);
+
+        // Window [2/3, 2/3, -2/3]: mean 2/9, std 4√2/9
+        assert!((flip.rolling_mean - 2.0 / 9.0).abs() < 1e-12);
+        assert!((flip.rolling_std - 4.0 * 2f64.sqrt() / 9.0).abs() < 1e-12);
+
+        // An infinite volume is rejected and leaves the rolling state usable
+        assert!(matches!(
+            engine.push("BTC", &tick(7, f64::INFINITY, 20.0)),
+            Err(EngineError::InvalidSnapshot { index: 0, error: ValidationError::NonFinite })
+        ));
+        let alternating = |t: u64| tick(t, [100.0, 20.0][(t % 2) as usize], 60.0);
+        let mut last = flip;
+        for t in 8..1000 {
+            last = engine.push("BTC", &alternating(t)).unwrap();
+        }
+        // Window [-0.5, 0.25, -0.5] (ticks 997-999): mean -1/4, std √2/4
+        assert!((last.rolling_mean + 0.25).abs() < 1e-12);
+        assert!((last.rolling_std - 2f64.sqrt() / 4.0).abs() < 1e-12);
+
+        engine.reset(Some("BTC"));
+        assert_eq!(engine.symbols(), vec!["ETH".to_string()]);
+    }
+
+    #[test]
+    fn test_ofi_price_moves_and_buckets() {
+        let engine = PhysicsEngine::new(EngineConfig::default()).unwrap();
+
+        let tick = |timestamp, bid_price, bid_volume, ask_price, ask_volume| OrderBookSnapshot {
+            timestamp,
+            bid_volume,
+            ask_volume,
+            bid_price,
+            ask_price,
+            last_trade_price: None,
+            symbol: None,
+            venue: None,
+        };
+
+        let snapshots = vec![
+            tick(1_000, 99.0, 10.0, 101.0, 10.0),
+            tick(1_400, 99.0, 15.0, 101.0, 10.0), // Bid size +5
+            tick(1_900, 100.0, 4.0, 101.0, 10.0), // Bid price up: +4
+            tick(2_100, 100.0, 4.0, 100.5, 3.0),  // Ask price down: -3
+            tick(2_500, 100.0, 4.0, 101.0, 8.0),  // Ask price up: +3 (old ask withdrawn)
+        ];
+
+        let results = engine.calculate_ofi_batch(&snapshots, &OfiConfig { bucket_ms: 1_000 });
+        let ofi: Vec<f64> = results.iter().map(|r| r.ofi).collect();
+        assert_eq!(ofi, vec![0.0, 5.0, 4.0, -3.0, 3.0]);
+
+        // Buckets [1000, 2000) and [2000, 3000)
+        assert_eq!(results[2].bucket_start, 1_000);
+        assert_eq!(results[2].bucket_ofi, 9.0);
+        assert_eq!(results[3].bucket_start, 2_000);
+        assert_eq!(results[4].bucket_ofi, 0.0);
+    }
+
+    #[test]
+    fn test_spread_analytics() {
+        let engine = PhysicsEngine::new(EngineConfig::default()).unwrap();
+
+        let snapshot = OrderBookSnapshot {
+            timestamp: 3000,
+            bid_volume: 300.0,
+            ask_volume: 100.0,
+            bid_price: 99.95,
+            ask_price: 100.05,
+            last_trade_price: Some(100.05),
+            symbol: None,
+            venue: None,
+        };
+
+        let results = engine
+            .calculate_obi_batch(&[snapshot], &ObiConfig { tick_size: 0.05, ..Default::default() })
+            .unwrap();
+        let r = &results[0];
+
+        // Heavy bid side pulls the microprice towards the ask
+        assert!((r.microprice - 100.025).abs() < 1e-9);
+        assert_eq!(r.weighted_mid, r.microprice);
+        assert!((r.spread_bps - 10.0).abs() < 1e-9);
+        assert!((r.spread_ticks - 2.0).abs() < 1e-9);
+        assert!((r.effective_spread.unwrap() - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fixed_point_obi_is_exact() {
+        let snapshot = OrderBookSnapshot {
+            timestamp: 4000,
+            bid_volume: 2.0,
+            ask_volume: 1.0,
+            bid_price: 99.0,
+            ask_price: 101.0,
+            last_trade_price: None,
+            symbol: None,
+            venue: None,
+        };
+
+        let engine = PhysicsEngine::new(EngineConfig {
+            arithmetic: ArithmeticMode::FixedPoint,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let results = engine.calculate_obi_batch(&[snapshot], &ObiConfig::default()).unwrap();
+        let fixed = results[0].fixed.unwrap();
+
+        // 1/3 and 2/100 rounded half away from zero at 9 decimals
+        assert_eq!(fixed.obi, 333_333_333);
+        assert_eq!(fixed.relative_spread, 20_000_000);
+        // H(2/3, 1/3) = 0.918295834 bits, from integer math only
+        assert_eq!(fixed.entropy, 918_295_834);
+        assert_eq!(results[0].entropy, fixed.entropy as f64 / 1e9);
+        assert_eq!(fixed.decimals, 9);
+        assert_eq!(results[0].obi.to_bits(), (333_333_333f64 / 1e9).to_bits());
+        assert_eq!(results[0].signal, "BUY_PRESSURE");
+    }
+
+    #[test]
+    fn test_fixed_point_large_volumes_and_overflow() {
+        let snapshot = |bid_volume: f64, ask_volume: f64| OrderBookSnapshot {
+            timestamp: 4500,
+            bid_volume,
+            ask_volume,
+            bid_price: 99.0,
+            ask_price: 101.0,
+            last_trade_price: None,
+            symbol: None,
+            venue: None,
+        };
+        let engine = PhysicsEngine::new(EngineConfig {
+            arithmetic: ArithmeticMode::FixedPoint,
+            ..Default::default()
+        })
+        .unwrap();
+        let config = ObiConfig::default();
+
+        // Volumes past i64::MAX / 1e9 (~9.22e9) no longer saturate to OBI = 0
+        let results = engine.calculate_obi_batch(&[snapshot(2e10, 1e10)], &config).unwrap();
+        let fixed = results[0].fixed.unwrap();
+        assert_eq!(fixed.obi, 333_333_333);
+        assert_eq!(fixed.entropy, 918_295_834);
+        assert_eq!(results[0].signal, "BUY_PRESSURE");
+
+        // Unrepresentable input is an error naming the snapshot, not a silent clamp
+        let err = engine
+            .calculate_obi_batch(&[snapshot(1.0, 1.0), snapshot(1e30, 1.0)], &config)
+            .unwrap_err();
+        assert_eq!(err, EngineError::FixedPointOverflow { index: 1, value: 1e30 });
+        assert!(engine.calculate_obi_batch(&[snapshot(f64::NAN, 1.0)], &config).is_err());
+
+        // Depth OBI is float-only
+        let depth = DepthSnapshot {
+            timestamp: 4500,
+            bids: vec![PriceLevel { price: 99.0, volume: 1.0 }],
+            asks: vec![PriceLevel { price: 101.0, volume: 1.0 }],
+            last_trade_price: None,
+            symbol: None,
+            venue: None,
+        };
+        assert!(matches!(
+            engine.calculate_depth_obi_batch(&[depth], &config, &DepthObiConfig::default()),
+            Err(EngineError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_independent_engine_instances() {
+        let snapshot = OrderBookSnapshot {
+            timestamp: 5000,
+            bid_volume: 60.0,
+            ask_volume: 40.0,
+            bid_price: 99.0,
+            ask_price: 101.0,
+            last_trade_price: None,
+            symbol: None,
+            venue: None,
+        };
+
+        // OBI = 0.2: NEUTRAL under default thresholds, BUY on a sensitive engine
+        let default_engine = PhysicsEngine::new(EngineConfig::default()).unwrap();
+        let sensitive_engine = PhysicsEngine::new(EngineConfig {
+            thresholds: SignalThresholds { buy: 0.1, sell: -0.1 },
+            backend: "scalar".to_string(),
+            threads: 2,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let config = ObiConfig::default();
+        let snapshots = std::slice::from_ref(&snapshot);
+        assert_eq!(default_engine.calculate_obi_batch(snapshots, &config).unwrap()[0].signal, "NEUTRAL");
+        assert_eq!(
+            sensitive_engine.calculate_obi_batch(snapshots, &config).unwrap()[0].signal,
+            "BUY_PRESSURE"
+        );
+        assert_eq!(sensitive_engine.backend_name(), "scalar");
+    }
+
+    #[test]
+    fn test_engine_handle_requires_init() {
+        let mut handle = EngineHandle::new(EngineConfig::default());
+        assert_eq!(handle.engine().err(), Some(EngineError::NotInitialized));
+
+        handle.init().unwrap();
+        assert!(handle.engine().is_ok());
+    }
 }
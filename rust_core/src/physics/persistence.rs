@@ -0,0 +1,510 @@
+// PERSISTENCE.rs - Vietoris-Rips Persistent Homology (H0/H1) over Z/2
+// COMPLEXITY: O(n^3) simplices for n points, column reduction worst case cubic in simplices
+// DETERMINISTIC: Simplices ordered by (filtration value, dimension, vertex indices)
+
+use serde::{Deserialize, Serialize};
+
+/// One topological feature: born at `birth`, filled in at `death` (∞ = never, essential)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PersistencePair {
+    pub dimension: usize, // 0 = connected component, 1 = loop ("hole")
+    pub birth: f64,
+    pub death: f64,
+}
+
+impl PersistencePair {
+    pub fn persistence(&self) -> f64 {
+        self.death - self.birth
+    }
+
+    pub fn is_essential(&self) -> bool {
+        self.death.is_infinite()
+    }
+}
+
+/// H0 and H1 persistence diagrams, each sorted by persistence (longest first)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PersistenceDiagram {
+    pub h0: Vec<PersistencePair>,
+    pub h1: Vec<PersistencePair>,
+}
+
+impl PersistenceDiagram {
+    pub fn pairs(&self, dimension: usize) -> &[PersistencePair] {
+        match dimension {
+            0 => &self.h0,
+            _ => &self.h1,
+        }
+    }
+
+    /// Σ (death - birth)^p over finite pairs of one dimension
+    pub fn total_persistence(&self, dimension: usize, p: f64) -> f64 {
+        self.pairs(dimension)
+            .iter()
+            .filter(|pair| !pair.is_essential())
+            .map(|pair| pair.persistence().powf(p))
+            .sum()
+    }
+
+    /// Longest finite persistence in one dimension (0 if none)
+    pub fn max_persistence(&self, dimension: usize) -> f64 {
+        self.pairs(dimension)
+            .iter()
+            .filter(|pair| !pair.is_essential())
+            .map(PersistencePair::persistence)
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Time-delay (Takens) embedding of one or more aligned channels:
+/// point t = [c_0(t), c_0(t + τ), .., c_0(t + (m-1)τ), c_1(t), ..]
+pub fn delay_embedding(channels: &[Vec<f64>], dimension: usize, delay: usize) -> Vec<Vec<f64>> {
+    let len = channels.iter().map(Vec::len).min().unwrap_or(0);
+    let span = dimension.saturating_sub(1) * delay;
+    if dimension == 0 || channels.is_empty() || len <= span {
+        return Vec::new();
+    }
+
+    (0..len - span)
+        .map(|t| {
+            channels
+                .iter()
+                .flat_map(|c| (0..dimension).map(move |k| c[t + k * delay]))
+                .collect()
+        })
+        .collect()
+}
+
+/// Scale a channel to zero mean and unit variance (constant channels become all zeros)
+pub fn standardize(values: &[f64]) -> Vec<f64> {
+    let n = values.len().max(1) as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let std = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+    values
+        .iter()
+        .map(|v| if std > 0.0 { (v - mean) / std } else { 0.0 })
+        .collect()
+}
+
+/// Euclidean distance matrix (row-major n×n)
+pub fn distance_matrix(points: &[Vec<f64>]) -> Vec<f64> {
+    let n = points.len();
+    let mut d = vec![0.0; n * n];
+    for i in 0..n {
+        for j in i + 1..n {
+            let dist = points[i]
+                .iter()
+                .zip(&points[j])
+                .map(|(a, b)| (a - b).powi(2))
+                .sum::<f64>()
+                .sqrt();
+            d[i * n + j] = dist;
+            d[j * n + i] = dist;
+        }
+    }
+    d
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Simplex {
+    value: f64,
+    dim: usize,
+    vertices: [usize; 3], // Unused trailing slots are 0
+}
+
+/// XOR of two sorted index columns (addition over Z/2)
+fn add_columns(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let mut out = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => {
+                out.push(a[i]);
+                i += 1;
+            }
+            std::cmp::Ordering::Greater => {
+                out.push(b[j]);
+                j += 1;
+            }
+            std::cmp::Ordering::Equal => {
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    out.extend_from_slice(&a[i..]);
+    out.extend_from_slice(&b[j..]);
+    out
+}
+
+/// Persistence of the Vietoris-Rips filtration up to 2-simplices, from a distance
+/// matrix. Edges longer than `max_edge` are left out (use ∞ for the full filtration).
+/// Zero-persistence pairs are dropped.
+pub fn rips_persistence_from_distances(distances: &[f64], n: usize, max_edge: f64) -> PersistenceDiagram {
+    let mut simplices: Vec<Simplex> = (0..n)
+        .map(|v| Simplex {
+            value: 0.0,
+            dim: 0,
+            vertices: [v, 0, 0],
+        })
+        .collect();
+
+    for i in 0..n {
+        for j in i + 1..n {
+            let value = distances[i * n + j];
+            if value <= max_edge {
+                simplices.push(Simplex { value, dim: 1, vertices: [i, j, 0] });
+            }
+        }
+    }
+    for i in 0..n {
+        for j in i + 1..n {
+            for k in j + 1..n {
+                let value = distances[i * n + j].max(distances[i * n + k]).max(distances[j * n + k]);
+                if value <= max_edge {
+                    simplices.push(Simplex { value, dim: 2, vertices: [i, j, k] });
+                }
+            }
+        }
+    }
+
+    // Faces precede cofaces: equal values are ordered by dimension
+    simplices.sort_by(|a, b| {
+        a.value
+            .total_cmp(&b.value)
+            .then(a.dim.cmp(&b.dim))
+            .then(a.vertices.cmp(&b.vertices))
+    });
+
+    let mut edge_index = vec![usize::MAX; n * n];
+    for (idx, s) in simplices.iter().enumerate().filter(|(_, s)| s.dim == 1) {
+        edge_index[s.vertices[0] * n + s.vertices[1]] = idx;
+    }
+
+    let boundary = |s: &Simplex| -> Vec<usize> {
+        let [a, b, c] = s.vertices;
+        let mut col = match s.dim {
+            1 => vec![a, b], // Vertices sit at their own index (value 0, dim 0 sort first)
+            2 => vec![edge_index[a * n + b], edge_index[a * n + c], edge_index[b * n + c]],
+            _ => Vec::new(),
+        };
+        col.sort_unstable();
+        col
+    };
+
+    let total = simplices.len();
+    let mut pivot_owner: Vec<Option<usize>> = vec![None; total];
+    let mut reduced: Vec<Vec<usize>> = vec![Vec::new(); total];
+    let mut paired = vec![false; total];
+    let mut diagram = PersistenceDiagram::default();
+
+    // Triangles first so edges that give birth to H1 can be cleared, then edges
+    for dim in [2, 1] {
+        for j in (0..total).filter(|&j| simplices[j].dim == dim) {
+            if paired[j] {
+                continue; // Cleared: a positive simplex killed by a higher-dimensional column
+            }
+            let mut col = boundary(&simplices[j]);
+            while let Some(&low) = col.last() {
+                match pivot_owner[low] {
+                    Some(owner) => col = add_columns(&col, &reduced[owner]),
+                    None => break,
+                }
+            }
+
+            if let Some(&low) = col.last() {
+                pivot_owner[low] = Some(j);
+                paired[low] = true;
+                paired[j] = true;
+                let pair = PersistencePair {
+                    dimension: dim - 1,
+                    birth: simplices[low].value,
+                    death: simplices[j].value,
+                };
+                if pair.persistence() > 0.0 {
+                    if dim == 2 {
+                        diagram.h1.push(pair);
+                    } else {
+                        diagram.h0.push(pair);
+                    }
+                }
+            }
+            reduced[j] = col;
+        }
+    }
+
+    // Unpaired vertices and cycle-creating edges are essential
+    for (idx, s) in simplices.iter().enumerate() {
+        if paired[idx] || s.dim == 2 {
+            continue;
+        }
+        let essential = PersistencePair {
+            dimension: s.dim,
+            birth: s.value,
+            death: f64::INFINITY,
+        };
+        match s.dim {
+            0 => diagram.h0.push(essential),
+            _ if reduced[idx].is_empty() => diagram.h1.push(essential),
+            _ => {}
+        }
+    }
+
+    for pairs in [&mut diagram.h0, &mut diagram.h1] {
+        pairs.sort_by(|a, b| b.persistence().total_cmp(&a.persistence()).then(a.birth.total_cmp(&b.birth)));
+    }
+    diagram
+}
+
+/// Rips persistence of a point cloud
+pub fn rips_persistence(points: &[Vec<f64>], max_edge: f64) -> PersistenceDiagram {
+    rips_persistence_from_distances(&distance_matrix(points), points.len(), max_edge)
+}
+
+/// Replace essential deaths with `horizon` so every pair is a finite point
+pub fn clip_essential(pairs: &[PersistencePair], horizon: f64) -> Vec<PersistencePair> {
+    pairs
+        .iter()
+        .map(|p| PersistencePair {
+            death: if p.is_essential() { horizon.max(p.birth) } else { p.death },
+            ..*p
+        })
+        .collect()
+}
+
+/// L∞ ground distance between two diagram points
+fn point_distance(a: &PersistencePair, b: &PersistencePair) -> f64 {
+    (a.birth - b.birth).abs().max((a.death - b.death).abs())
+}
+
+/// L∞ distance from a point to the diagonal
+fn diagonal_distance(p: &PersistencePair) -> f64 {
+    p.persistence() / 2.0
+}
+
+/// Cost between slot i of A ∪ diag(B) and slot j of B ∪ diag(A)
+fn slot_distance(a: &[PersistencePair], b: &[PersistencePair], i: usize, j: usize) -> f64 {
+    match (a.get(i), b.get(j)) {
+        (Some(x), Some(y)) => point_distance(x, y),
+        (Some(x), None) => diagonal_distance(x),
+        (None, Some(y)) => diagonal_distance(y),
+        (None, None) => 0.0,
+    }
+}
+
+/// Kuhn augmenting path over edges with cost <= t
+fn augment(u: usize, size: usize, allowed: &dyn Fn(usize, usize) -> bool, seen: &mut [bool], matched: &mut [Option<usize>]) -> bool {
+    for v in 0..size {
+        if seen[v] || !allowed(u, v) {
+            continue;
+        }
+        seen[v] = true;
+        if matched[v].is_none_or(|w| augment(w, size, allowed, seen, matched)) {
+            matched[v] = Some(u);
+            return true;
+        }
+    }
+    false
+}
+
+/// Bottleneck distance between two finite diagrams (clip essentials first): the smallest
+/// t such that a perfect matching (points may go to the diagonal) has every cost <= t
+pub fn bottleneck_distance(a: &[PersistencePair], b: &[PersistencePair]) -> f64 {
+    let size = a.len() + b.len();
+    let mut candidates: Vec<f64> = (0..size)
+        .flat_map(|i| (0..size).map(move |j| (i, j)))
+        .map(|(i, j)| slot_distance(a, b, i, j))
+        .collect();
+    candidates.sort_by(f64::total_cmp);
+    candidates.dedup();
+
+    let perfect = |t: f64| {
+        let allowed = |i: usize, j: usize| slot_distance(a, b, i, j) <= t;
+        let mut matched = vec![None; size];
+        (0..size).all(|u| augment(u, size, &allowed, &mut vec![false; size], &mut matched))
+    };
+
+    // Matching feasibility is monotone in t
+    let (mut lo, mut hi) = (0, candidates.len().saturating_sub(1));
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if perfect(candidates[mid]) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    candidates.get(lo).copied().unwrap_or(0.0)
+}
+
+/// p-Wasserstein distance between two finite diagrams (clip essentials first), solved
+/// exactly with the Hungarian algorithm on the diagonal-augmented cost matrix
+pub fn wasserstein_distance(a: &[PersistencePair], b: &[PersistencePair], p: f64) -> f64 {
+    let size = a.len() + b.len();
+    if size == 0 {
+        return 0.0;
+    }
+    let cost = |i: usize, j: usize| slot_distance(a, b, i - 1, j - 1).powf(p);
+
+    // 1-indexed potentials formulation (row 0 / column 0 are sentinels)
+    let mut u = vec![0.0; size + 1];
+    let mut v = vec![0.0; size + 1];
+    let mut row_of = vec![0usize; size + 1];
+    let mut way = vec![0usize; size + 1];
+    for row in 1..=size {
+        row_of[0] = row;
+        let mut col = 0;
+        let mut min_to = vec![f64::INFINITY; size + 1];
+        let mut used = vec![false; size + 1];
+        loop {
+            used[col] = true;
+            let i = row_of[col];
+            let mut delta = f64::INFINITY;
+            let mut next = 0;
+            for j in 1..=size {
+                if used[j] {
+                    continue;
+                }
+                let reduced = cost(i, j) - u[i] - v[j];
+                if reduced < min_to[j] {
+                    min_to[j] = reduced;
+                    way[j] = col;
+                }
+                if min_to[j] < delta {
+                    delta = min_to[j];
+                    next = j;
+                }
+            }
+            for j in 0..=size {
+                if used[j] {
+                    u[row_of[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min_to[j] -= delta;
+                }
+            }
+            col = next;
+            if row_of[col] == 0 {
+                break;
+            }
+        }
+        while col != 0 {
+            let prev = way[col];
+            row_of[col] = row_of[prev];
+            col = prev;
+        }
+    }
+
+    let total: f64 = (1..=size).map(|j| cost(row_of[j], j)).sum();
+    total.max(0.0).powf(1.0 / p)
+}
+
+/// Betti curve: number of features alive (birth <= t < death) at each grid value
+pub fn betti_curve(pairs: &[PersistencePair], grid: &[f64]) -> Vec<f64> {
+    grid.iter()
+        .map(|&t| pairs.iter().filter(|p| p.birth <= t && t < p.death).count() as f64)
+        .collect()
+}
+
+/// Persistence landscape λ_1..λ_levels sampled on the grid, flattened level-major
+/// (λ_k(t) = k-th largest of max(0, min(t - birth, death - t)))
+pub fn persistence_landscape(pairs: &[PersistencePair], levels: usize, grid: &[f64]) -> Vec<f64> {
+    let mut out = vec![0.0; levels * grid.len()];
+    let mut tents = Vec::with_capacity(pairs.len());
+    for (g, &t) in grid.iter().enumerate() {
+        tents.clear();
+        tents.extend(pairs.iter().map(|p| (t - p.birth).min(p.death - t).max(0.0)));
+        tents.sort_by(|x, y| y.total_cmp(x));
+        for (k, tent) in tents.iter().take(levels).enumerate() {
+            out[k * grid.len() + g] = *tent;
+        }
+    }
+    out
+}
+
+/// `resolution` evenly spaced filtration values over [0, horizon]
+pub fn filtration_grid(horizon: f64, resolution: usize) -> Vec<f64> {
+    match resolution {
+        0 => Vec::new(),
+        1 => vec![0.0],
+        _ => (0..resolution)
+            .map(|i| horizon * i as f64 / (resolution - 1) as f64)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_rips_persistence_circle_and_clusters() {
+        // 12 points on a unit circle: one long H1 loop, one essential component
+        let circle: Vec<Vec<f64>> = (0..12)
+            .map(|i| {
+                let a = 2.0 * PI * i as f64 / 12.0;
+                vec![a.cos(), a.sin()]
+            })
+            .collect();
+        let diagram = rips_persistence(&circle, f64::INFINITY);
+
+        let chord = 2.0 * (PI / 12.0).sin(); // Neighbour spacing
+        assert_eq!(diagram.h0.len(), 12); // 11 merges + 1 essential
+        assert!(diagram.h0[0].is_essential());
+        assert!(diagram.h0[1..].iter().all(|p| (p.death - chord).abs() < 1e-12));
+
+        assert_eq!(diagram.h1.len(), 1);
+        let hole = diagram.h1[0];
+        assert!((hole.birth - chord).abs() < 1e-12);
+        // The loop fills once the triangles spanning the circle appear (chord over 4 steps = √3)
+        assert!((hole.death - 3f64.sqrt()).abs() < 1e-12, "death {}", hole.death);
+        assert!((diagram.total_persistence(1, 1.0) - (hole.death - hole.birth)).abs() < 1e-12);
+
+        // Truncated filtration keeps the loop open
+        let truncated = rips_persistence(&circle, 1.0);
+        assert!(truncated.h1[0].is_essential());
+
+        // Two clusters: one H0 pair dies at the gap, no loops
+        let clusters = vec![vec![0.0], vec![0.1], vec![5.0], vec![5.2]];
+        let diagram = rips_persistence(&clusters, f64::INFINITY);
+        assert!((diagram.h0[1].death - 4.9).abs() < 1e-12);
+        assert!(diagram.h1.is_empty());
+
+        // Delay embedding of a sine wave traces a loop
+        let sine: Vec<f64> = (0..40).map(|t| (2.0 * PI * t as f64 / 20.0).sin()).collect();
+        let cloud = delay_embedding(&[sine], 2, 5);
+        assert_eq!(cloud.len(), 35);
+        assert!(rips_persistence(&cloud, f64::INFINITY).max_persistence(1) > 0.5);
+    }
+
+    #[test]
+    fn test_diagram_distances_and_vectorisations() {
+        let pair = |birth: f64, death: f64| PersistencePair { dimension: 1, birth, death };
+        let a = vec![pair(0.0, 4.0), pair(1.0, 1.5)];
+        let b = vec![pair(0.5, 3.0)];
+
+        // Best matching: (0,4)↔(0.5,3) costs 1; (1,1.5) goes to the diagonal for 0.25
+        assert!((bottleneck_distance(&a, &b) - 1.0).abs() < 1e-12);
+        assert!((wasserstein_distance(&a, &b, 1.0) - 1.25).abs() < 1e-12);
+        assert!((wasserstein_distance(&a, &b, 2.0) - (1.0f64 + 0.0625).sqrt()).abs() < 1e-12);
+        assert_eq!(bottleneck_distance(&a, &a), 0.0);
+        assert!(wasserstein_distance(&a, &a, 2.0).abs() < 1e-9);
+        // Against an empty diagram every point goes to the diagonal
+        assert!((bottleneck_distance(&a, &[]) - 2.0).abs() < 1e-12);
+        assert!((wasserstein_distance(&a, &[], 1.0) - 2.25).abs() < 1e-12);
+        assert_eq!(wasserstein_distance(&[], &[], 2.0), 0.0);
+
+        let clipped = clip_essential(&[pair(0.5, f64::INFINITY)], 2.0);
+        assert_eq!(clipped[0].death, 2.0);
+
+        let grid = filtration_grid(4.0, 5); // 0, 1, 2, 3, 4
+        assert_eq!(grid, vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(betti_curve(&a, &grid), vec![1.0, 2.0, 1.0, 1.0, 0.0]);
+
+        let landscape = persistence_landscape(&a, 2, &grid);
+        assert_eq!(landscape.len(), 10);
+        assert_eq!(&landscape[..5], &[0.0, 1.0, 2.0, 1.0, 0.0]); // λ_1: the long bar's tent
+        assert_eq!(&landscape[5..], &[0.0, 0.0, 0.0, 0.0, 0.0]); // λ_2: short bar peaks between samples
+    }
+}
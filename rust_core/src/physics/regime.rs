@@ -0,0 +1,587 @@
+// REGIME.rs - Gaussian Hidden Markov Model for Market Regime Detection
+// COMPLEXITY: O(T * K^2 + T * K * D) per Baum-Welch iteration / Viterbi pass (K states, D features)
+// DETERMINISTIC: Seeded k-means++ initialisation, sequential scaled recursions
+
+use crate::physics::obi_engine::EngineError;
+use crate::physics::validation::ValidationError;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+pub const LABEL_TRENDING: &str = "TRENDING";
+pub const LABEL_MEAN_REVERTING: &str = "MEAN_REVERTING";
+pub const LABEL_ILLIQUID: &str = "ILLIQUID";
+
+/// Baum-Welch configuration
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct HmmConfig {
+    pub states: usize,
+    pub max_iter: usize,
+    pub tolerance: f64,    // Stop when the log-likelihood gains less than this
+    pub min_variance: f64, // Per-feature variance floor (keeps a state from collapsing on one point)
+    pub seed: u64,         // k-means++ initialisation seed
+}
+
+impl Default for HmmConfig {
+    fn default() -> Self {
+        HmmConfig {
+            states: 3,
+            max_iter: 100,
+            tolerance: 1e-6,
+            min_variance: 1e-6,
+            seed: 42,
+        }
+    }
+}
+
+/// Outcome of a Baum-Welch fit
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct HmmFitReport {
+    pub log_likelihood: f64,
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+/// Gaussian HMM with diagonal covariances; serialises to JSON for offline training
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GaussianHmm {
+    pub features: Vec<String>,     // Feature name per dimension (drives automatic labels)
+    pub labels: Vec<String>,       // Regime label per state
+    pub initial: Vec<f64>,         // π
+    pub transition: Vec<Vec<f64>>, // A[i][j] = P(state j | state i)
+    pub means: Vec<Vec<f64>>,
+    pub variances: Vec<Vec<f64>>,
+}
+
+fn invalid(message: String) -> EngineError {
+    EngineError::InvalidConfig(message)
+}
+
+fn check_observations(observations: &[Vec<f64>], dims: Option<usize>) -> Result<usize, EngineError> {
+    let dims = dims.or_else(|| observations.first().map(Vec::len)).unwrap_or(0);
+    if dims == 0 {
+        return Err(invalid("regime features must have at least one dimension".to_string()));
+    }
+    for (t, x) in observations.iter().enumerate() {
+        if x.len() != dims {
+            return Err(invalid(format!("observation {} has {} features, expected {}", t, x.len(), dims)));
+        }
+        if x.iter().any(|v| !v.is_finite()) {
+            return Err(EngineError::InvalidSnapshot { index: t, error: ValidationError::NonFinite });
+        }
+    }
+    Ok(dims)
+}
+
+/// Per-dimension mean and (floored) standard deviation
+fn column_stats(observations: &[Vec<f64>], dims: usize) -> (Vec<f64>, Vec<f64>) {
+    let n = observations.len().max(1) as f64;
+    let mean: Vec<f64> = (0..dims).map(|d| observations.iter().map(|x| x[d]).sum::<f64>() / n).collect();
+    let std = (0..dims)
+        .map(|d| {
+            let var = observations.iter().map(|x| (x[d] - mean[d]).powi(2)).sum::<f64>() / n;
+            var.sqrt().max(1e-12)
+        })
+        .collect();
+    (mean, std)
+}
+
+fn normalize_in_place(p: &mut [f64]) -> f64 {
+    let total: f64 = p.iter().sum();
+    if total > 0.0 {
+        p.iter_mut().for_each(|v| *v /= total);
+    } else {
+        let uniform = 1.0 / p.len() as f64;
+        p.iter_mut().for_each(|v| *v = uniform);
+    }
+    total
+}
+
+impl GaussianHmm {
+    pub fn states(&self) -> usize {
+        self.initial.len()
+    }
+
+    pub fn dims(&self) -> usize {
+        self.means.first().map_or(0, Vec::len)
+    }
+
+    /// Parse and shape-check a model produced by `to_json`
+    pub fn from_json(json: &str) -> Result<Self, EngineError> {
+        let model: GaussianHmm =
+            serde_json::from_str(json).map_err(|e| invalid(format!("regime model JSON: {}", e)))?;
+        model.validate()?;
+        Ok(model)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("model fields are plain numbers and strings")
+    }
+
+    fn validate(&self) -> Result<(), EngineError> {
+        let (k, d) = (self.states(), self.dims());
+        let shaped = k > 0
+            && d > 0
+            && self.labels.len() == k
+            && self.features.len() == d
+            && self.transition.len() == k
+            && self.transition.iter().all(|row| row.len() == k)
+            && self.means.len() == k
+            && self.variances.len() == k
+            && self.means.iter().chain(&self.variances).all(|row| row.len() == d);
+        if !shaped {
+            return Err(invalid(format!("regime model is not a consistent {}-state, {}-feature HMM", k, d)));
+        }
+
+        let stochastic = |p: &[f64]| p.iter().all(|&v| v >= 0.0) && (p.iter().sum::<f64>() - 1.0).abs() < 1e-6;
+        if !stochastic(&self.initial) || !self.transition.iter().all(|row| stochastic(row)) {
+            return Err(invalid("regime model probabilities must be non-negative and sum to 1".to_string()));
+        }
+        if self.variances.iter().flatten().any(|&v| !(v.is_finite() && v > 0.0)) {
+            return Err(invalid("regime model variances must be positive".to_string()));
+        }
+        Ok(())
+    }
+
+    /// ln N(x | μ_k, diag σ²_k)
+    fn log_emission(&self, state: usize, x: &[f64]) -> f64 {
+        self.means[state]
+            .iter()
+            .zip(&self.variances[state])
+            .zip(x)
+            .map(|((mu, var), v)| -0.5 * ((2.0 * PI * var).ln() + (v - mu).powi(2) / var))
+            .sum()
+    }
+
+    /// Emission likelihoods per tick, scaled by the per-tick max (offset returned in log space)
+    fn scaled_emissions(&self, observations: &[Vec<f64>]) -> (Vec<Vec<f64>>, Vec<f64>) {
+        observations
+            .iter()
+            .map(|x| {
+                let logs: Vec<f64> = (0..self.states()).map(|k| self.log_emission(k, x)).collect();
+                let max = logs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                (logs.iter().map(|l| (l - max).exp()).collect(), max)
+            })
+            .unzip()
+    }
+
+    /// Fit with Baum-Welch from a seeded k-means++ start
+    pub fn fit(
+        observations: &[Vec<f64>],
+        features: &[String],
+        config: &HmmConfig,
+    ) -> Result<(Self, HmmFitReport), EngineError> {
+        let dims = check_observations(observations, None)?;
+        let k = config.states;
+        if k == 0 || observations.len() < k {
+            return Err(invalid(format!(
+                "need at least {} observations for a {}-state HMM, got {}",
+                k.max(1),
+                k,
+                observations.len()
+            )));
+        }
+        if !features.is_empty() && features.len() != dims {
+            return Err(invalid(format!("{} feature names for {} features", features.len(), dims)));
+        }
+
+        let mut model = Self::initialise(observations, dims, features, config);
+        let mut report = HmmFitReport {
+            log_likelihood: f64::NEG_INFINITY,
+            iterations: 0,
+            converged: false,
+        };
+
+        while report.iterations < config.max_iter {
+            let log_likelihood = model.baum_welch_step(observations, config.min_variance);
+            report.iterations += 1;
+            let gain = log_likelihood - report.log_likelihood;
+            report.log_likelihood = log_likelihood;
+            if gain.abs() < config.tolerance {
+                report.converged = true;
+                break;
+            }
+        }
+
+        model.labels = model.auto_labels(observations);
+        Ok((model, report))
+    }
+
+    fn initialise(observations: &[Vec<f64>], dims: usize, features: &[String], config: &HmmConfig) -> Self {
+        let k = config.states;
+        let (mean, std) = column_stats(observations, dims);
+        let z: Vec<Vec<f64>> = observations
+            .iter()
+            .map(|x| (0..dims).map(|d| (x[d] - mean[d]) / std[d]).collect())
+            .collect();
+        let dist = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f64>();
+
+        // k-means++ seeding on standardised features
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let mut centers = vec![z[rng.gen_range(0..z.len())].clone()];
+        while centers.len() < k {
+            let d2: Vec<f64> = z
+                .iter()
+                .map(|x| centers.iter().map(|c| dist(x, c)).fold(f64::INFINITY, f64::min))
+                .collect();
+            let total: f64 = d2.iter().sum();
+            let next = if total > 0.0 {
+                let mut target = rng.gen::<f64>() * total;
+                d2.iter().position(|&w| {
+                    target -= w;
+                    target <= 0.0
+                })
+                .unwrap_or(z.len() - 1)
+            } else {
+                rng.gen_range(0..z.len())
+            };
+            centers.push(z[next].clone());
+        }
+
+        // A few Lloyd passes, then per-cluster moments in the original units
+        let mut assignment = vec![0; z.len()];
+        for _ in 0..10 {
+            for (t, x) in z.iter().enumerate() {
+                assignment[t] = (0..k)
+                    .min_by(|&a, &b| dist(x, &centers[a]).total_cmp(&dist(x, &centers[b])))
+                    .expect("k > 0");
+            }
+            for (c, center) in centers.iter_mut().enumerate() {
+                let members: Vec<&Vec<f64>> = z.iter().zip(&assignment).filter(|(_, &a)| a == c).map(|(x, _)| x).collect();
+                if !members.is_empty() {
+                    *center = (0..dims)
+                        .map(|d| members.iter().map(|x| x[d]).sum::<f64>() / members.len() as f64)
+                        .collect();
+                }
+            }
+        }
+
+        let mut means = Vec::with_capacity(k);
+        let mut variances = Vec::with_capacity(k);
+        for (c, center) in centers.iter().enumerate() {
+            let members: Vec<Vec<f64>> = observations
+                .iter()
+                .zip(&assignment)
+                .filter(|(_, &a)| a == c)
+                .map(|(x, _)| x.clone())
+                .collect();
+            if members.is_empty() {
+                means.push((0..dims).map(|d| mean[d] + center[d] * std[d]).collect());
+                variances.push(std.iter().map(|s| (s * s).max(config.min_variance)).collect());
+            } else {
+                let (m, s) = column_stats(&members, dims);
+                means.push(m);
+                variances.push(
+                    s.iter()
+                        .zip(&std)
+                        .map(|(si, global)| if members.len() > 1 { si * si } else { global * global }.max(config.min_variance))
+                        .collect(),
+                );
+            }
+        }
+
+        let stay = if k > 1 { 0.9 } else { 1.0 };
+        let transition = (0..k)
+            .map(|i| (0..k).map(|j| if i == j { stay } else { (1.0 - stay) / (k - 1) as f64 }).collect())
+            .collect();
+
+        GaussianHmm {
+            features: if features.is_empty() {
+                (0..dims).map(|d| format!("f{}", d)).collect()
+            } else {
+                features.to_vec()
+            },
+            labels: (0..k).map(|i| format!("STATE_{}", i)).collect(),
+            initial: vec![1.0 / k as f64; k],
+            transition,
+            means,
+            variances,
+        }
+    }
+
+    /// Scaled forward pass: normalised alphas, scale factors and log-likelihood
+    fn forward(&self, emissions: &[Vec<f64>], offsets: &[f64]) -> (Vec<Vec<f64>>, Vec<f64>, f64) {
+        let k = self.states();
+        let mut alphas: Vec<Vec<f64>> = Vec::with_capacity(emissions.len());
+        let mut scales = Vec::with_capacity(emissions.len());
+        let mut log_likelihood = 0.0;
+
+        for (t, b) in emissions.iter().enumerate() {
+            let mut alpha: Vec<f64> = match alphas.last() {
+                None => (0..k).map(|j| self.initial[j] * b[j]).collect(),
+                Some(prev) => (0..k)
+                    .map(|j| (0..k).map(|i| prev[i] * self.transition[i][j]).sum::<f64>() * b[j])
+                    .collect(),
+            };
+            let scale = normalize_in_place(&mut alpha).max(f64::MIN_POSITIVE);
+            log_likelihood += scale.ln() + offsets[t];
+            scales.push(scale);
+            alphas.push(alpha);
+        }
+
+        (alphas, scales, log_likelihood)
+    }
+
+    /// One EM iteration; returns the log-likelihood under the parameters before the update
+    fn baum_welch_step(&mut self, observations: &[Vec<f64>], min_variance: f64) -> f64 {
+        let (k, dims, n) = (self.states(), self.dims(), observations.len());
+        let (emissions, offsets) = self.scaled_emissions(observations);
+        let (alphas, scales, log_likelihood) = self.forward(&emissions, &offsets);
+
+        let mut betas = vec![vec![1.0; k]; n];
+        for t in (0..n - 1).rev() {
+            for i in 0..k {
+                betas[t][i] = (0..k)
+                    .map(|j| self.transition[i][j] * emissions[t + 1][j] * betas[t + 1][j])
+                    .sum::<f64>()
+                    / scales[t + 1];
+            }
+        }
+
+        let gammas: Vec<Vec<f64>> = (0..n)
+            .map(|t| {
+                let mut g: Vec<f64> = (0..k).map(|i| alphas[t][i] * betas[t][i]).collect();
+                normalize_in_place(&mut g);
+                g
+            })
+            .collect();
+
+        let mut xi_sum = vec![vec![0.0; k]; k];
+        for t in 0..n - 1 {
+            for (i, row) in xi_sum.iter_mut().enumerate() {
+                for (j, cell) in row.iter_mut().enumerate() {
+                    *cell += alphas[t][i] * self.transition[i][j] * emissions[t + 1][j] * betas[t + 1][j] / scales[t + 1];
+                }
+            }
+        }
+
+        self.initial = gammas[0].clone();
+        for i in 0..k {
+            let outgoing: f64 = xi_sum[i].iter().sum();
+            if outgoing > 0.0 {
+                self.transition[i] = xi_sum[i].iter().map(|x| x / outgoing).collect();
+            }
+
+            let weight: f64 = gammas.iter().map(|g| g[i]).sum();
+            if weight <= 0.0 {
+                continue; // Unvisited state keeps its emission parameters
+            }
+            for d in 0..dims {
+                let mean = gammas.iter().zip(observations).map(|(g, x)| g[i] * x[d]).sum::<f64>() / weight;
+                let var = gammas.iter().zip(observations).map(|(g, x)| g[i] * (x[d] - mean).powi(2)).sum::<f64>() / weight;
+                self.means[i][d] = mean;
+                self.variances[i][d] = var.max(min_variance);
+            }
+        }
+
+        log_likelihood
+    }
+
+    /// Log-likelihood of a sequence under the model
+    pub fn log_likelihood(&self, observations: &[Vec<f64>]) -> Result<f64, EngineError> {
+        check_observations(observations, Some(self.dims()))?;
+        let (emissions, offsets) = self.scaled_emissions(observations);
+        Ok(self.forward(&emissions, &offsets).2)
+    }
+
+    /// Most likely state path (Viterbi, log space)
+    pub fn viterbi(&self, observations: &[Vec<f64>]) -> Result<Vec<usize>, EngineError> {
+        check_observations(observations, Some(self.dims()))?;
+        if observations.is_empty() {
+            return Ok(Vec::new());
+        }
+        let k = self.states();
+        let log_a: Vec<Vec<f64>> = self.transition.iter().map(|row| row.iter().map(|p| p.ln()).collect()).collect();
+
+        let mut delta: Vec<f64> = Vec::new();
+        let mut back: Vec<Vec<usize>> = Vec::with_capacity(observations.len());
+        for (t, x) in observations.iter().enumerate() {
+            let emission: Vec<f64> = (0..k).map(|j| self.log_emission(j, x)).collect();
+            if t == 0 {
+                delta = (0..k).map(|j| self.initial[j].ln() + emission[j]).collect();
+                back.push(vec![0; k]);
+                continue;
+            }
+            let (next, from): (Vec<f64>, Vec<usize>) = (0..k)
+                .map(|j| {
+                    let (best, score) = (0..k)
+                        .map(|i| (i, delta[i] + log_a[i][j]))
+                        .max_by(|a, b| a.1.total_cmp(&b.1))
+                        .expect("k > 0");
+                    (score + emission[j], best)
+                })
+                .unzip();
+            delta = next;
+            back.push(from);
+        }
+
+        let mut state = (0..k).max_by(|&a, &b| delta[a].total_cmp(&delta[b])).unwrap_or(0);
+        let mut path = vec![0; observations.len()];
+        for t in (0..observations.len()).rev() {
+            path[t] = state;
+            state = back[t][state];
+        }
+        Ok(path)
+    }
+
+    /// Label states from feature-name roles: the widest spread/curvature is ILLIQUID, then the
+    /// largest |OBI|/return deviation is TRENDING and the smallest MEAN_REVERTING.
+    /// Deviations are in units of the fitted sequence's stddev; unmatched states keep STATE_i.
+    fn auto_labels(&self, observations: &[Vec<f64>]) -> Vec<String> {
+        let k = self.states();
+        let (mean, std) = column_stats(observations, self.dims());
+        let role = |keys: &[&str]| -> Vec<usize> {
+            (0..self.dims())
+                .filter(|&d| {
+                    let name = self.features[d].to_ascii_lowercase();
+                    keys.iter().any(|key| name.contains(key))
+                })
+                .collect()
+        };
+        let liquidity = role(&["spread", "curvature", "illiq"]);
+        let direction = role(&["obi", "imbalance", "ofi", "return", "trend", "velocity"]);
+        let z = |state: usize, d: usize| (self.means[state][d] - mean[d]) / std[d];
+
+        let mut labels: Vec<String> = (0..k).map(|i| format!("STATE_{}", i)).collect();
+        let mut free: Vec<usize> = (0..k).collect();
+        let mut take = |free: &mut Vec<usize>, score: &dyn Fn(usize) -> f64, highest: bool, label: &str| {
+            let pick = free.iter().copied().max_by(|&a, &b| {
+                let order = score(a).total_cmp(&score(b));
+                if highest { order } else { order.reverse() }
+            });
+            if let Some(state) = pick {
+                labels[state] = label.to_string();
+                free.retain(|&s| s != state);
+            }
+        };
+
+        if !liquidity.is_empty() && k >= 2 {
+            take(&mut free, &|s| liquidity.iter().map(|&d| z(s, d)).sum(), true, LABEL_ILLIQUID);
+        }
+        if !direction.is_empty() {
+            let trend = |s: usize| direction.iter().map(|&d| z(s, d).abs()).sum::<f64>();
+            take(&mut free, &trend, true, LABEL_TRENDING);
+            take(&mut free, &trend, false, LABEL_MEAN_REVERTING);
+        }
+        labels
+    }
+}
+
+/// Online forward filter over a fitted model
+#[derive(Debug, Clone)]
+pub struct HmmFilter {
+    model: GaussianHmm,
+    belief: Option<Vec<f64>>,
+}
+
+impl HmmFilter {
+    pub fn new(model: GaussianHmm) -> Self {
+        HmmFilter { model, belief: None }
+    }
+
+    pub fn model(&self) -> &GaussianHmm {
+        &self.model
+    }
+
+    /// Update with one feature vector; returns P(state | observations so far)
+    pub fn step(&mut self, x: &[f64]) -> Result<Vec<f64>, EngineError> {
+        if x.len() != self.model.dims() {
+            return Err(invalid(format!(
+                "regime filter expects {} features, got {}",
+                self.model.dims(),
+                x.len()
+            )));
+        }
+        if x.iter().any(|v| !v.is_finite()) {
+            return Err(EngineError::InvalidSnapshot { index: 0, error: ValidationError::NonFinite });
+        }
+        let k = self.model.states();
+
+        let prior: Vec<f64> = match &self.belief {
+            None => self.model.initial.clone(),
+            Some(belief) => (0..k)
+                .map(|j| (0..k).map(|i| belief[i] * self.model.transition[i][j]).sum())
+                .collect(),
+        };
+        let logs: Vec<f64> = (0..k).map(|j| self.model.log_emission(j, x)).collect();
+        let max = logs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let mut posterior: Vec<f64> = (0..k).map(|j| prior[j] * (logs[j] - max).exp()).collect();
+        normalize_in_place(&mut posterior);
+
+        self.belief = Some(posterior.clone());
+        Ok(posterior)
+    }
+
+    pub fn reset(&mut self) {
+        self.belief = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmm_fit_decode_filter_and_json() {
+        // Three regimes in blocks: calm (obi ~ 0, tight), trend (obi ~ 0.6), illiquid (wide spread)
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut noise = |scale: f64| (rng.gen::<f64>() - 0.5) * scale;
+        let mut observations = Vec::new();
+        let mut truth = Vec::new();
+        for block in 0..6 {
+            let regime = block % 3;
+            for _ in 0..40 {
+                let (obi, spread) = match regime {
+                    0 => (noise(0.2), 1.0 + noise(0.2)),
+                    1 => (0.6 + noise(0.2), 1.0 + noise(0.2)),
+                    _ => (noise(0.2), 8.0 + noise(1.0)),
+                };
+                observations.push(vec![obi, spread]);
+                truth.push(regime);
+            }
+        }
+
+        let features = vec!["obi".to_string(), "spread_bps".to_string()];
+        let (model, report) = GaussianHmm::fit(&observations, &features, &HmmConfig::default()).unwrap();
+        assert!(report.converged);
+        assert!(report.log_likelihood.is_finite());
+
+        // Viterbi recovers the blocks up to label permutation
+        let path = model.viterbi(&observations).unwrap();
+        assert!(model.viterbi(&[]).unwrap().is_empty());
+        let expected = [LABEL_MEAN_REVERTING, LABEL_TRENDING, LABEL_ILLIQUID];
+        let correct = path
+            .iter()
+            .zip(&truth)
+            .filter(|(&state, &regime)| model.labels[state] == expected[regime])
+            .count();
+        assert!(correct >= observations.len() * 95 / 100, "{} / {}", correct, observations.len());
+
+        // JSON round trip preserves the model; online filter agrees with Viterbi in-block
+        let loaded = GaussianHmm::from_json(&model.to_json()).unwrap();
+        assert_eq!(loaded.labels, model.labels);
+        assert_eq!(loaded.log_likelihood(&observations).unwrap(), model.log_likelihood(&observations).unwrap());
+
+        let mut filter = HmmFilter::new(loaded);
+        let filtered: Vec<Vec<f64>> = observations[..60].iter().map(|x| filter.step(x).unwrap()).collect();
+        let last = &filtered[59];
+        assert!((last.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        let best = (0..last.len()).max_by(|&a, &b| last[a].total_cmp(&last[b])).unwrap();
+        assert_eq!(model.labels[best], LABEL_TRENDING);
+
+        assert!(filter.step(&[0.1]).is_err());
+        assert!(matches!(
+            filter.step(&[f64::NAN, 1.0]),
+            Err(EngineError::InvalidSnapshot { index: 0, error: ValidationError::NonFinite })
+        ));
+        let mut corrupt = observations[..10].to_vec();
+        corrupt[4][1] = f64::INFINITY;
+        assert!(matches!(
+            model.viterbi(&corrupt),
+            Err(EngineError::InvalidSnapshot { index: 4, error: ValidationError::NonFinite })
+        ));
+        assert!(GaussianHmm::from_json("{\"labels\": []}").is_err());
+        assert!(GaussianHmm::fit(&observations[..2], &features, &HmmConfig::default()).is_err());
+    }
+}
@@ -0,0 +1,422 @@
+// STREAMING_TDA.rs - Sliding-Window Persistence with Incremental Distances
+// COMPLEXITY: O(n * d) distances + O(n log n) union-find MST update per tick (O(n^2) worst-case eviction), H1 O(n^3) every `h1_every` ticks
+// DETERMINISTIC: Diagrams depend only on the tick sequence; only the timing metrics vary between runs
+
+use crate::physics::obi_engine::{EngineError, OrderBookSnapshot};
+use crate::physics::persistence::{self, PersistencePair};
+use crate::physics::validation::ValidationError;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Largest window accepted: H1 is cubic and runs on the caller's thread
+pub const MAX_WINDOW: usize = 200;
+
+/// Streaming TDA configuration
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct StreamingTdaConfig {
+    pub window: usize,           // Embedded points kept in the cloud
+    pub dimension: usize,        // Takens embedding dimension m
+    pub delay: usize,            // Embedding delay τ (ticks)
+    pub include_volume: bool,    // Embed top-of-book volume alongside the mid
+    pub ewma_alpha: f64,         // Online z-score smoothing in (0, 1]; fixes each point at insertion
+    pub max_edge: Option<f64>,   // Rips threshold for H1 (None = full filtration)
+    pub h1_every: usize,         // Ticks between H1 recomputations
+    pub latency_budget_ms: f64,  // Per-push budget; H1 is skipped when projected over it, at most one cadence (0 = unlimited)
+    pub hole_persistence: f64,   // H1 persistence that counts as a hole
+}
+
+impl Default for StreamingTdaConfig {
+    fn default() -> Self {
+        StreamingTdaConfig {
+            window: 64,
+            dimension: 3,
+            delay: 1,
+            include_volume: true,
+            ewma_alpha: 0.05,
+            max_edge: None,
+            h1_every: 10,
+            latency_budget_ms: 10.0,
+            hole_persistence: 0.5,
+        }
+    }
+}
+
+impl StreamingTdaConfig {
+    fn validate(&self) -> Result<(), EngineError> {
+        if self.window < 2 || self.dimension == 0 || self.delay == 0 || self.h1_every == 0 {
+            return Err(EngineError::InvalidConfig(format!(
+                "streaming TDA needs window >= 2 and dimension, delay, h1_every >= 1, got {} / {} / {} / {}",
+                self.window, self.dimension, self.delay, self.h1_every
+            )));
+        }
+        if self.window > MAX_WINDOW {
+            return Err(EngineError::InvalidConfig(format!(
+                "streaming TDA window {} exceeds {} (H1 Rips is cubic)",
+                self.window, MAX_WINDOW
+            )));
+        }
+        if !(self.ewma_alpha > 0.0 && self.ewma_alpha <= 1.0) {
+            return Err(EngineError::InvalidConfig(format!(
+                "ewma_alpha must be in (0, 1], got {}",
+                self.ewma_alpha
+            )));
+        }
+        if self.latency_budget_ms.is_nan() || self.latency_budget_ms < 0.0 {
+            return Err(EngineError::InvalidConfig(format!(
+                "latency_budget_ms must be >= 0, got {}",
+                self.latency_budget_ms
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Cumulative timing and scheduling counters
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct StreamingTdaMetrics {
+    pub pushes: u64,
+    pub h1_runs: u64,
+    pub h1_skipped: u64,      // Due H1 runs postponed because they were projected over budget
+    pub budget_overruns: u64, // Pushes that took longer than the budget
+    pub last_push_ms: f64,
+    pub mean_push_ms: f64,
+    pub max_push_ms: f64,
+    pub last_h0_ms: f64,
+    pub last_h1_ms: f64,
+}
+
+/// Per-tick output
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StreamingTdaState {
+    pub timestamp: u64,
+    pub points: usize,              // Embedded points currently in the window
+    pub total_persistence_h0: f64,  // Σ merge heights (finite H0 deaths)
+    pub max_persistence_h0: f64,    // Largest merge height (connectivity scale)
+    pub h1: Vec<PersistencePair>,   // Latest H1 diagram (may be from an earlier tick)
+    pub h1_timestamp: Option<u64>,  // Tick the H1 diagram was computed on
+    pub h1_fresh: bool,             // H1 recomputed on this push
+    pub total_persistence_h1: f64,
+    pub max_persistence_h1: f64,
+    pub holes: usize,
+    pub push_ms: f64,
+    pub budget_exceeded: bool,
+}
+
+/// Exponentially weighted mean/variance for the online z-score
+#[derive(Debug, Clone, Copy, Default)]
+struct Ewma {
+    mean: f64,
+    var: f64,
+    seeded: bool,
+}
+
+impl Ewma {
+    /// z-score against the statistics before this value, then absorb it
+    fn score(&mut self, value: f64, alpha: f64) -> f64 {
+        if !self.seeded {
+            *self = Ewma { mean: value, var: 0.0, seeded: true };
+            return 0.0;
+        }
+        let z = if self.var > 0.0 { (value - self.mean) / self.var.sqrt() } else { 0.0 };
+        let diff = value - self.mean;
+        self.mean += alpha * diff;
+        self.var = (1.0 - alpha) * (self.var + alpha * diff * diff);
+        z
+    }
+}
+
+fn find(parent: &mut [usize], mut x: usize) -> usize {
+    while parent[x] != x {
+        parent[x] = parent[parent[x]];
+        x = parent[x];
+    }
+    x
+}
+
+/// Minimum spanning forest of weighted slot pairs (Kruskal; slots < n)
+fn kruskal(mut edges: Vec<(f64, usize, usize)>, n: usize) -> Vec<(f64, usize, usize)> {
+    edges.sort_by(|x, y| x.0.total_cmp(&y.0));
+    let mut parent: Vec<usize> = (0..n).collect();
+    edges
+        .into_iter()
+        .filter(|&(_, a, b)| {
+            let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+            parent[ra] = rb;
+            ra != rb
+        })
+        .collect()
+}
+
+/// Sliding-window topology engine: points are delay vectors of online z-scores, so a
+/// point never changes once inserted and the distance matrix only gains one row per tick
+pub struct StreamingTopology {
+    config: StreamingTdaConfig,
+    mid_stats: Ewma,
+    volume_stats: Ewma,
+    mids: VecDeque<f64>, // Recent z-scores, enough for one delay vector
+    volumes: VecDeque<f64>,
+    order: VecDeque<(u64, usize)>, // (timestamp, slot) oldest first
+    coords: Vec<Vec<f64>>,         // Point by slot
+    distances: Vec<f64>,           // window × window by slot
+    tree: Vec<(f64, usize, usize)>, // Minimum spanning tree of the window: (length, slot, slot)
+    since_h1: usize,
+    h1: Vec<PersistencePair>,
+    h1_timestamp: Option<u64>,
+    metrics: StreamingTdaMetrics,
+}
+
+impl StreamingTopology {
+    pub fn new(config: StreamingTdaConfig) -> Result<Self, EngineError> {
+        config.validate()?;
+        Ok(StreamingTopology {
+            config,
+            mid_stats: Ewma::default(),
+            volume_stats: Ewma::default(),
+            mids: VecDeque::new(),
+            volumes: VecDeque::new(),
+            order: VecDeque::new(),
+            coords: vec![Vec::new(); config.window],
+            distances: vec![0.0; config.window * config.window],
+            tree: Vec::new(),
+            since_h1: 0,
+            h1: Vec::new(),
+            h1_timestamp: None,
+            metrics: StreamingTdaMetrics::default(),
+        })
+    }
+
+    pub fn metrics(&self) -> StreamingTdaMetrics {
+        self.metrics
+    }
+
+    pub fn reset(&mut self) {
+        *self = StreamingTopology::new(self.config).expect("config was validated on construction");
+    }
+
+    /// Current cloud, oldest point first
+    pub fn points(&self) -> Vec<Vec<f64>> {
+        self.order.iter().map(|&(_, slot)| self.coords[slot].clone()).collect()
+    }
+
+    pub fn push(&mut self, snapshot: &OrderBookSnapshot) -> Result<StreamingTdaState, EngineError> {
+        let started = Instant::now();
+        let mid = (snapshot.bid_price + snapshot.ask_price) / 2.0;
+        let volume = snapshot.bid_volume + snapshot.ask_volume;
+        if !mid.is_finite() || !volume.is_finite() {
+            return Err(EngineError::InvalidSnapshot { index: 0, error: ValidationError::NonFinite });
+        }
+
+        let span = (self.config.dimension - 1) * self.config.delay + 1;
+        self.mids.push_back(self.mid_stats.score(mid, self.config.ewma_alpha));
+        self.volumes.push_back(self.volume_stats.score(volume, self.config.ewma_alpha));
+        if self.mids.len() > span {
+            self.mids.pop_front();
+            self.volumes.pop_front();
+        }
+        let h0_started = Instant::now();
+        if self.mids.len() == span {
+            self.insert(snapshot.timestamp);
+        }
+        let merges: Vec<f64> = self.tree.iter().map(|e| e.0).filter(|&d| d > 0.0).collect();
+        self.metrics.last_h0_ms = h0_started.elapsed().as_secs_f64() * 1000.0;
+
+        // H1 when due, unless the last run predicts a budget overrun; a skipped run is
+        // retried each tick and forced once it is a full cadence late, so one slow run
+        // cannot stop H1 for good. A stale diagram keeps its own timestamp.
+        self.since_h1 += 1;
+        let mut h1_fresh = false;
+        if self.since_h1 >= self.config.h1_every && self.order.len() >= 3 {
+            let budget = self.config.latency_budget_ms;
+            let projected = started.elapsed().as_secs_f64() * 1000.0 + self.metrics.last_h1_ms;
+            let overdue = self.since_h1 >= 2 * self.config.h1_every;
+            if budget > 0.0 && self.metrics.h1_runs > 0 && projected > budget && !overdue {
+                self.metrics.h1_skipped += 1;
+            } else {
+                let h1_started = Instant::now();
+                self.recompute_h1();
+                self.metrics.last_h1_ms = h1_started.elapsed().as_secs_f64() * 1000.0;
+                self.metrics.h1_runs += 1;
+                self.h1_timestamp = Some(snapshot.timestamp);
+                self.since_h1 = 0;
+                h1_fresh = true;
+            }
+        }
+
+        let push_ms = started.elapsed().as_secs_f64() * 1000.0;
+        let budget_exceeded = self.config.latency_budget_ms > 0.0 && push_ms > self.config.latency_budget_ms;
+        let m = &mut self.metrics;
+        m.pushes += 1;
+        m.last_push_ms = push_ms;
+        m.mean_push_ms += (push_ms - m.mean_push_ms) / m.pushes as f64;
+        m.max_push_ms = m.max_push_ms.max(push_ms);
+        m.budget_overruns += budget_exceeded as u64;
+
+        let finite_h1 = || self.h1.iter().filter(|p| !p.is_essential());
+        Ok(StreamingTdaState {
+            timestamp: snapshot.timestamp,
+            points: self.order.len(),
+            total_persistence_h0: merges.iter().sum(),
+            max_persistence_h0: merges.iter().copied().fold(0.0, f64::max),
+            h1: self.h1.clone(),
+            h1_timestamp: self.h1_timestamp,
+            h1_fresh,
+            total_persistence_h1: finite_h1().map(PersistencePair::persistence).sum(),
+            max_persistence_h1: finite_h1().map(PersistencePair::persistence).fold(0.0, f64::max),
+            holes: self.h1.iter().filter(|p| p.persistence() >= self.config.hole_persistence).count(),
+            push_ms,
+            budget_exceeded,
+        })
+    }
+
+    /// Add the newest delay vector, evicting the oldest point and reusing its slot
+    fn insert(&mut self, timestamp: u64) {
+        let (dimension, delay) = (self.config.dimension, self.config.delay);
+        let channels: &[&VecDeque<f64>] = if self.config.include_volume { &[&self.mids, &self.volumes] } else { &[&self.mids] };
+        let point: Vec<f64> = channels
+            .iter()
+            .flat_map(|c| (0..dimension).map(move |k| c[k * delay]))
+            .collect();
+
+        let slot = if self.order.len() == self.config.window {
+            let evicted = self.order.pop_front().expect("window is non-empty").1;
+            self.evict(evicted);
+            evicted
+        } else {
+            self.order.len()
+        };
+        let n = self.config.window;
+        for &(_, other) in &self.order {
+            let d = point
+                .iter()
+                .zip(&self.coords[other])
+                .map(|(a, b)| (a - b).powi(2))
+                .sum::<f64>()
+                .sqrt();
+            self.distances[slot * n + other] = d;
+            self.distances[other * n + slot] = d;
+        }
+        self.distances[slot * n + slot] = 0.0;
+        self.coords[slot] = point;
+
+        // The new tree uses only old tree edges and the new point's edges
+        let mut candidates = std::mem::take(&mut self.tree);
+        candidates.extend(self.order.iter().map(|&(_, other)| (self.distances[slot * n + other], slot, other)));
+        self.tree = kruskal(candidates, n);
+        self.order.push_back((timestamp, slot));
+    }
+
+    /// Drop a slot from the tree and reconnect the pieces it held together: the
+    /// surviving edges stay, and only edges leaving the smaller pieces can rejoin them
+    fn evict(&mut self, slot: usize) {
+        let n = self.config.window;
+        let before = self.tree.len();
+        self.tree.retain(|&(_, a, b)| a != slot && b != slot);
+        if before - self.tree.len() <= 1 {
+            return; // A leaf: the rest is still spanning
+        }
+
+        let mut parent: Vec<usize> = (0..n).collect();
+        for &(_, a, b) in &self.tree {
+            let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+            parent[ra] = rb;
+        }
+        let slots: Vec<usize> = self.order.iter().map(|&(_, s)| s).collect();
+        let roots: Vec<usize> = slots.iter().map(|&s| find(&mut parent, s)).collect();
+        let mut sizes = vec![0usize; n];
+        for &r in &roots {
+            sizes[r] += 1;
+        }
+        let largest = (0..n).max_by_key(|&r| sizes[r]).expect("window is non-empty");
+
+        let mut candidates = std::mem::take(&mut self.tree);
+        for (i, (&a, &ra)) in slots.iter().zip(&roots).enumerate() {
+            if ra == largest {
+                continue;
+            }
+            for (j, (&b, &rb)) in slots.iter().zip(&roots).enumerate() {
+                if rb != ra && (rb == largest || i < j) {
+                    candidates.push((self.distances[a * n + b], a, b));
+                }
+            }
+        }
+        self.tree = kruskal(candidates, n);
+    }
+
+    fn recompute_h1(&mut self) {
+        let n = self.config.window;
+        let slots: Vec<usize> = self.order.iter().map(|&(_, s)| s).collect();
+        let m = slots.len();
+        let mut compact = vec![0.0; m * m];
+        for (i, &a) in slots.iter().enumerate() {
+            for (j, &b) in slots.iter().enumerate() {
+                compact[i * m + j] = self.distances[a * n + b];
+            }
+        }
+        let max_edge = self.config.max_edge.unwrap_or(f64::INFINITY);
+        self.h1 = persistence::rips_persistence_from_distances(&compact, m, max_edge).h1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::tda::tests::oscillating;
+
+    #[test]
+    fn test_streaming_matches_batch_and_respects_cadence() {
+        let config = StreamingTdaConfig { window: 40, h1_every: 5, latency_budget_ms: 0.0, ..Default::default() };
+        let mut stream = StreamingTopology::new(config).unwrap();
+        let mut last = None;
+        for t in 0..200 {
+            let state = stream.push(&oscillating(t, 15.0)).unwrap();
+            if t >= 2 + 39 {
+                assert_eq!(state.points, 40); // Full window, oldest evicted
+            }
+            // The tree stays minimal through every eviction
+            let batch_h0 = persistence::rips_persistence(&stream.points(), f64::INFINITY).total_persistence(0, 1.0);
+            assert!((state.total_persistence_h0 - batch_h0).abs() < 1e-9, "tick {}", t);
+            last = Some(state);
+        }
+        let state = last.unwrap();
+
+        // Incremental distances + union-find tree agree with a from-scratch Rips on the same cloud
+        let batch = persistence::rips_persistence(&stream.points(), f64::INFINITY);
+        let batch_h0: f64 = batch.total_persistence(0, 1.0);
+        assert!((state.total_persistence_h0 - batch_h0).abs() < 1e-9);
+        assert!((state.max_persistence_h0 - batch.max_persistence(0)).abs() < 1e-12);
+
+        // H1 every 5th push once three points exist, on the cycle's loop
+        let metrics = stream.metrics();
+        assert_eq!(metrics.pushes, 200);
+        assert_eq!(metrics.h1_runs, 40);
+        assert_eq!(metrics.h1_skipped, 0);
+        assert!(state.h1_fresh && state.h1_timestamp == Some(199));
+        assert_eq!(state.h1, batch.h1);
+        assert!(state.holes >= 1);
+        assert!(metrics.mean_push_ms > 0.0 && metrics.max_push_ms >= metrics.mean_push_ms);
+
+        // An impossible budget postpones every due H1, but each is forced one cadence
+        // late: the first run (t = 4) calibrates the cost, then t = 14, 24, .., 94
+        let tight = StreamingTdaConfig { latency_budget_ms: 1e-9, ..config };
+        let mut stream = StreamingTopology::new(tight).unwrap();
+        let mut last = None;
+        for t in 0..100 {
+            last = Some(stream.push(&oscillating(t, 15.0)).unwrap());
+        }
+        let metrics = stream.metrics();
+        assert_eq!(metrics.h1_runs, 10);
+        assert_eq!(metrics.h1_skipped, 46); // t = 9..13 before each forced run, and t = 99
+        assert_eq!(metrics.budget_overruns, 100);
+        assert_eq!(last.unwrap().h1_timestamp, Some(94));
+        assert!(matches!(
+            stream.push(&oscillating(100, f64::NAN)),
+            Err(EngineError::InvalidSnapshot { index: 0, error: ValidationError::NonFinite })
+        ));
+
+        stream.reset();
+        assert_eq!(stream.metrics().pushes, 0);
+        assert!(StreamingTopology::new(StreamingTdaConfig { h1_every: 0, ..config }).is_err());
+        assert!(StreamingTopology::new(StreamingTdaConfig { window: MAX_WINDOW + 1, ..config }).is_err());
+    }
+}
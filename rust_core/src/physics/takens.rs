@@ -0,0 +1,281 @@
+// TAKENS.rs - Embedding Parameter Selection (AMI delay, FNN / Cao dimension)
+// COMPLEXITY: O(n * max_delay) for AMI, O(n^2 * max_dimension) for FNN and Cao (brute-force neighbours)
+// DETERMINISTIC: Fixed-width histograms, nearest neighbour ties broken by lowest index
+
+use crate::physics::obi_engine::EngineError;
+use serde::{Deserialize, Serialize};
+
+/// How the embedding dimension is chosen
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DimensionMethod {
+    #[default]
+    FalseNeighbours, // Kennel et al.: first dimension with few false nearest neighbours
+    Cao,             // Cao (1997): first dimension where E1 saturates
+}
+
+impl DimensionMethod {
+    pub fn parse(value: &str) -> Result<Self, EngineError> {
+        match value.to_ascii_lowercase().replace(['_', ' '], "-").as_str() {
+            "fnn" | "false-neighbours" | "false-nearest-neighbours" => Ok(DimensionMethod::FalseNeighbours),
+            "cao" => Ok(DimensionMethod::Cao),
+            other => Err(EngineError::InvalidConfig(format!(
+                "unknown embedding dimension method '{}' (expected fnn or cao)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Search ranges and decision thresholds
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct TakensConfig {
+    pub max_delay: usize,     // Largest delay scanned for the AMI minimum
+    pub bins: usize,          // Histogram bins per axis for mutual information
+    pub max_dimension: usize, // Largest embedding dimension scanned
+    pub method: DimensionMethod,
+    pub fnn_rtol: f64,        // Kennel distance-ratio tolerance
+    pub fnn_atol: f64,        // Kennel attractor-size tolerance (in standard deviations)
+    pub fnn_threshold: f64,   // FNN fraction accepted as "no false neighbours"
+    pub cao_threshold: f64,   // E1 level treated as saturated
+}
+
+impl Default for TakensConfig {
+    fn default() -> Self {
+        TakensConfig {
+            max_delay: 50,
+            bins: 16,
+            max_dimension: 10,
+            method: DimensionMethod::FalseNeighbours,
+            fnn_rtol: 10.0,
+            fnn_atol: 2.0,
+            fnn_threshold: 0.02,
+            cao_threshold: 0.8,
+        }
+    }
+}
+
+/// Chosen parameters plus the curves they were read from
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TakensEmbedding {
+    pub delay: usize,
+    pub dimension: usize,
+    pub mutual_information: Vec<f64>, // AMI(τ) for τ = 0..=max_delay (nats)
+    pub false_neighbours: Vec<f64>,   // FNN fraction for d = 1..=max_dimension (empty with Cao)
+    pub cao_e1: Vec<f64>,             // E1(d) for d = 1..=max_dimension (empty with FNN)
+    pub cao_e2: Vec<f64>,             // E2(d): near 1 for every d on noise (empty with FNN)
+}
+
+/// Histogram estimate of I(x_t; x_{t+τ}) for τ = 0..=max_delay (shorter if the series is)
+pub fn average_mutual_information(series: &[f64], max_delay: usize, bins: usize) -> Vec<f64> {
+    let (min, max) = series.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    if bins == 0 || series.len() < 2 || max <= min {
+        return Vec::new();
+    }
+    let bin = |v: f64| (((v - min) / (max - min) * bins as f64) as usize).min(bins - 1);
+    let labels: Vec<usize> = series.iter().map(|&v| bin(v)).collect();
+
+    (0..=max_delay.min(series.len() - 2))
+        .map(|tau| {
+            let n = labels.len() - tau;
+            let mut joint = vec![0.0; bins * bins];
+            let mut left = vec![0.0; bins];
+            let mut right = vec![0.0; bins];
+            for t in 0..n {
+                let (a, b) = (labels[t], labels[t + tau]);
+                joint[a * bins + b] += 1.0;
+                left[a] += 1.0;
+                right[b] += 1.0;
+            }
+            let n = n as f64;
+            joint
+                .iter()
+                .enumerate()
+                .filter(|(_, &c)| c > 0.0)
+                .map(|(k, &c)| {
+                    let p = c / n;
+                    p * (p / (left[k / bins] / n * right[k % bins] / n)).ln()
+                })
+                .sum()
+        })
+        .collect()
+}
+
+/// First local minimum of the AMI curve (τ >= 1); falls back to the global minimum
+pub fn first_minimum(curve: &[f64]) -> Option<usize> {
+    (1..curve.len().saturating_sub(1))
+        .find(|&t| curve[t] < curve[t - 1] && curve[t] <= curve[t + 1])
+        .or_else(|| (1..curve.len()).min_by(|&a, &b| curve[a].total_cmp(&curve[b])))
+}
+
+/// Delay vector at i: [x_i, x_{i+τ}, .., x_{i+(d-1)τ}]
+fn coordinate(series: &[f64], i: usize, k: usize, delay: usize) -> f64 {
+    series[i + k * delay]
+}
+
+/// Nearest neighbour of i among the first `count` delay vectors of dimension d
+/// (Euclidean or max norm); coincident points are skipped
+fn nearest(series: &[f64], i: usize, count: usize, dimension: usize, delay: usize, max_norm: bool) -> Option<(usize, f64)> {
+    let mut best: Option<(usize, f64)> = None;
+    for j in (0..count).filter(|&j| j != i) {
+        let diffs = (0..dimension).map(|k| (coordinate(series, i, k, delay) - coordinate(series, j, k, delay)).abs());
+        let distance = if max_norm { diffs.fold(0.0, f64::max) } else { diffs.map(|d| d * d).sum::<f64>().sqrt() };
+        if distance > 0.0 && best.is_none_or(|(_, b)| distance < b) {
+            best = Some((j, distance));
+        }
+    }
+    best
+}
+
+/// Kennel false nearest neighbour fraction for d = 1..=max_dimension
+pub fn false_nearest_neighbours(series: &[f64], delay: usize, max_dimension: usize, rtol: f64, atol: f64) -> Vec<f64> {
+    let n = series.len() as f64;
+    let mean = series.iter().sum::<f64>() / n;
+    let std = (series.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+
+    (1..=max_dimension)
+        .map_while(|d| {
+            // Vectors that also have a (d+1)-th coordinate
+            let count = series.len().checked_sub(d * delay).filter(|&c| c >= 2)?;
+            let (mut checked, mut falses) = (0usize, 0usize);
+            for i in 0..count {
+                let Some((j, r_d)) = nearest(series, i, count, d, delay, false) else { continue };
+                let extra = (coordinate(series, i, d, delay) - coordinate(series, j, d, delay)).abs();
+                let r_next = (r_d * r_d + extra * extra).sqrt();
+                checked += 1;
+                if extra / r_d > rtol || (std > 0.0 && r_next / std > atol) {
+                    falses += 1;
+                }
+            }
+            (checked > 0).then(|| falses as f64 / checked as f64)
+        })
+        .collect()
+}
+
+/// Cao's E1(d) and E2(d) for d = 1..=max_dimension (max norm)
+pub fn cao_statistics(series: &[f64], delay: usize, max_dimension: usize) -> (Vec<f64>, Vec<f64>) {
+    // E(d) and E*(d) for d = 1..=max_dimension + 1
+    let mut e = Vec::new();
+    let mut e_star = Vec::new();
+    for d in 1..=max_dimension + 1 {
+        let Some(count) = series.len().checked_sub(d * delay).filter(|&c| c >= 2) else { break };
+        let (mut a_sum, mut star_sum, mut used) = (0.0, 0.0, 0usize);
+        for i in 0..count {
+            let Some((j, r_d)) = nearest(series, i, count, d, delay, true) else { continue };
+            let extra = (coordinate(series, i, d, delay) - coordinate(series, j, d, delay)).abs();
+            a_sum += r_d.max(extra) / r_d;
+            star_sum += extra;
+            used += 1;
+        }
+        if used == 0 {
+            break;
+        }
+        e.push(a_sum / used as f64);
+        e_star.push(star_sum / used as f64);
+    }
+
+    let ratio = |v: &[f64]| -> Vec<f64> {
+        v.windows(2)
+            .map(|w| if w[0] > 0.0 { w[1] / w[0] } else { 1.0 })
+            .collect()
+    };
+    (ratio(&e), ratio(&e_star))
+}
+
+/// Select delay (first AMI minimum) then dimension (FNN or Cao) for one series.
+/// Only the configured dimension criterion is computed.
+pub fn select_embedding(series: &[f64], config: &TakensConfig) -> Result<TakensEmbedding, EngineError> {
+    if config.max_delay == 0 || config.max_dimension == 0 || config.bins < 2 {
+        return Err(EngineError::InvalidConfig(format!(
+            "takens search needs max_delay >= 1, max_dimension >= 1 and bins >= 2, got {} / {} / {}",
+            config.max_delay, config.max_dimension, config.bins
+        )));
+    }
+    if series.iter().any(|v| !v.is_finite()) {
+        return Err(EngineError::InvalidConfig("takens series must be finite".to_string()));
+    }
+
+    let mutual_information = average_mutual_information(series, config.max_delay, config.bins);
+    let delay = first_minimum(&mutual_information).ok_or_else(|| {
+        EngineError::InvalidConfig(format!(
+            "series of {} values is too short or constant for delay selection",
+            series.len()
+        ))
+    })?;
+
+    let (false_neighbours, (cao_e1, cao_e2)) = match config.method {
+        DimensionMethod::FalseNeighbours => (
+            false_nearest_neighbours(series, delay, config.max_dimension, config.fnn_rtol, config.fnn_atol),
+            (Vec::new(), Vec::new()),
+        ),
+        DimensionMethod::Cao => (Vec::new(), cao_statistics(series, delay, config.max_dimension)),
+    };
+
+    // Fall back to the deepest dimension scanned if the criterion is never met
+    let dimension = match config.method {
+        DimensionMethod::FalseNeighbours => false_neighbours
+            .iter()
+            .position(|&f| f <= config.fnn_threshold)
+            .map_or(false_neighbours.len(), |d| d + 1),
+        DimensionMethod::Cao => cao_e1
+            .iter()
+            .position(|&e1| e1 >= config.cao_threshold)
+            .map_or(cao_e1.len(), |d| d + 1),
+    }
+    .max(1);
+
+    Ok(TakensEmbedding {
+        delay,
+        dimension,
+        mutual_information,
+        false_neighbours,
+        cao_e1,
+        cao_e2,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_embedding_on_sine_and_lorenz() {
+        // Sine with an incommensurate period (~41.7 ticks, so no float-noise duplicates):
+        // AMI bottoms out on a plateau around the quarter period, a loop needs 2 dimensions
+        let sine: Vec<f64> = (0..600).map(|t| (2.0 * std::f64::consts::PI * t as f64 / 41.7).sin()).collect();
+        let embedding = select_embedding(&sine, &TakensConfig { max_delay: 30, max_dimension: 5, ..Default::default() }).unwrap();
+        assert!((5..=12).contains(&embedding.delay), "delay {}", embedding.delay);
+        assert_eq!(embedding.dimension, 2, "fnn {:?}", embedding.false_neighbours);
+        assert_eq!(embedding.mutual_information.len(), 31);
+        assert!(embedding.false_neighbours[0] > 0.02); // Folding onto a line crosses the two branches
+
+        let cao = select_embedding(
+            &sine,
+            &TakensConfig { max_delay: 30, max_dimension: 5, method: DimensionMethod::Cao, ..Default::default() },
+        )
+        .unwrap();
+        assert_eq!(cao.delay, embedding.delay);
+        assert_eq!(cao.cao_e1.len(), 5);
+        assert!(cao.false_neighbours.is_empty() && embedding.cao_e1.is_empty());
+        assert!((2..=3).contains(&cao.dimension), "cao dimension {} {:?}", cao.dimension, cao.cao_e1);
+
+        // Lorenz x-coordinate needs 3 dimensions
+        let (mut x, mut y, mut z) = (1.0f64, 1.0f64, 1.0f64);
+        let mut lorenz = Vec::new();
+        for step in 0..8_000 {
+            let dt = 0.005;
+            let (dx, dy, dz) = (10.0 * (y - x), x * (28.0 - z) - y, x * y - 8.0 / 3.0 * z);
+            x += dt * dx;
+            y += dt * dy;
+            z += dt * dz;
+            if step >= 2_000 && step % 10 == 0 {
+                lorenz.push(x);
+            }
+        }
+        let embedding = select_embedding(&lorenz, &TakensConfig { max_dimension: 6, ..Default::default() }).unwrap();
+        assert!(embedding.delay >= 2, "lorenz delay {}", embedding.delay);
+        assert_eq!(embedding.dimension, 3, "fnn {:?}", embedding.false_neighbours);
+
+        assert!(DimensionMethod::parse("false_neighbours").is_ok());
+        assert!(select_embedding(&[1.0; 50], &TakensConfig::default()).is_err());
+    }
+}
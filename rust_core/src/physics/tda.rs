@@ -1,5 +1,267 @@
-use crate::physics::obi_engine::OrderBookSnapshot;
+use crate::physics::dimension::{self, DimensionConfig, DimensionEstimate};
+use crate::physics::mapper::{self, MapperConfig, MapperGraph};
+use crate::physics::obi_engine::{EngineError, OrderBookSnapshot};
+use crate::physics::persistence::{self, PersistenceDiagram, PersistencePair};
+use crate::physics::takens::{self, TakensConfig, TakensEmbedding};
+use crate::physics::validation::ValidationError;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Embedding and filtration parameters for persistent homology
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct TdaConfig {
+    pub dimension: usize,        // Takens embedding dimension m
+    pub delay: usize,            // Embedding delay τ (ticks)
+    pub include_volume: bool,    // Embed total top-of-book volume alongside the mid
+    pub max_edge: Option<f64>,   // Rips threshold in standardised units (None = full filtration)
+    pub max_points: usize,       // Embedded points allowed per diagram (Rips is O(n^3))
+    pub hole_persistence: f64,   // H1 persistence that counts as a liquidity hole
+    pub auto_embedding: bool,    // Pick dimension/delay from `embedding_source` (AMI + FNN / Cao) instead
+    pub embedding_source: EmbeddingSource, // Series searched by auto_embedding
+    pub takens: TakensConfig,    // Search ranges and criterion for auto_embedding
+}
+
+impl Default for TdaConfig {
+    fn default() -> Self {
+        TdaConfig {
+            dimension: 3,
+            delay: 1,
+            include_volume: true,
+            max_edge: None,
+            max_points: 200,
+            hole_persistence: 0.5,
+            auto_embedding: false,
+            embedding_source: EmbeddingSource::Mid,
+            takens: TakensConfig::default(),
+        }
+    }
+}
+
+impl TdaConfig {
+    /// Use a selected Takens embedding (disables further automatic selection)
+    pub fn with_embedding(self, embedding: &TakensEmbedding) -> Self {
+        TdaConfig {
+            dimension: embedding.dimension,
+            delay: embedding.delay,
+            auto_embedding: false,
+            ..self
+        }
+    }
+}
+
+/// Series whose dynamics drive Takens parameter selection
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmbeddingSource {
+    #[default]
+    Mid,
+    Obi, // Top-of-book imbalance (bid - ask) / (bid + ask)
+}
+
+impl EmbeddingSource {
+    pub fn parse(value: &str) -> Result<Self, EngineError> {
+        match value.to_ascii_lowercase().as_str() {
+            "mid" | "price" => Ok(EmbeddingSource::Mid),
+            "obi" | "imbalance" => Ok(EmbeddingSource::Obi),
+            other => Err(EngineError::InvalidConfig(format!(
+                "unknown embedding source '{}' (expected mid or obi)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Persistence diagrams of one embedded window plus summary statistics
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TopologySummary {
+    pub points: usize, // Embedded points
+    pub dimension: usize, // Embedding actually used
+    pub delay: usize,
+    pub diagram: PersistenceDiagram,
+    pub total_persistence_h0: f64, // Finite pairs only
+    pub total_persistence_h1: f64,
+    pub max_persistence_h1: f64,
+    pub holes: usize, // H1 pairs at or above `hole_persistence`
+}
+
+/// Sliding-window settings for the topology time series
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct TopologySeriesConfig {
+    pub window: usize,           // Snapshots per diagram
+    pub step: usize,             // Snapshots between consecutive windows
+    pub wasserstein_p: f64,      // Order of the Wasserstein distance
+    pub landscape_levels: usize, // Landscape functions λ_1..λ_k per dimension
+    pub resolution: usize,       // Grid samples per landscape / Betti curve
+    pub baseline: usize,         // Prior distances used to score a spike
+    pub spike_z: f64,            // Z-score of the H1 Wasserstein distance that flags a spike
+}
+
+impl Default for TopologySeriesConfig {
+    fn default() -> Self {
+        TopologySeriesConfig {
+            window: 50,
+            step: 1,
+            wasserstein_p: 2.0,
+            landscape_levels: 3,
+            resolution: 32,
+            baseline: 50,
+            spike_z: 3.0,
+        }
+    }
+}
+
+/// One window of the topology time series, stamped with its last snapshot
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TopologyPoint {
+    pub timestamp: u64,
+    pub total_persistence_h1: f64,
+    pub max_persistence_h1: f64,
+    pub holes: usize,
+    pub bottleneck_h0: Option<f64>, // Distance to the previous window (None for the first)
+    pub bottleneck_h1: Option<f64>,
+    pub wasserstein_h0: Option<f64>,
+    pub wasserstein_h1: Option<f64>,
+    pub distance_z: Option<f64>, // H1 Wasserstein distance against the trailing baseline
+    pub spike: bool,
+    pub landscape_h0: Vec<f64>, // Level-major, `landscape_levels * resolution`
+    pub landscape_h1: Vec<f64>,
+    pub betti_h0: Vec<f64>, // `resolution` samples
+    pub betti_h1: Vec<f64>,
+}
+
+/// Topology time series plus the shared filtration grid of its feature vectors
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TopologySeries {
+    pub grid: Vec<f64>,
+    pub points: Vec<TopologyPoint>,
+}
+
+/// Liquidity risk on Omega's riskLevel scale
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LiquidityRisk {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl LiquidityRisk {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LiquidityRisk::Low => "LOW",
+            LiquidityRisk::Medium => "MEDIUM",
+            LiquidityRisk::High => "HIGH",
+            LiquidityRisk::Critical => "CRITICAL",
+        }
+    }
+}
+
+/// Void detection and risk thresholds for a price-level × time liquidity surface
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct LiquiditySurfaceConfig {
+    pub void_fraction: f64, // Cells below this fraction of the median non-empty depth are void
+    pub min_void_cells: usize, // Smaller void components are ignored
+    pub medium: f64,        // Share of cells inside voids per risk level
+    pub high: f64,
+    pub critical: f64,
+}
+
+impl Default for LiquiditySurfaceConfig {
+    fn default() -> Self {
+        LiquiditySurfaceConfig {
+            void_fraction: 0.25,
+            min_void_cells: 1,
+            medium: 0.02,
+            high: 0.1,
+            critical: 0.25,
+        }
+    }
+}
+
+/// One connected liquidity void (4-neighbour component of thin cells)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LiquidityVoid {
+    pub cells: usize,
+    pub level_start: usize, // Inclusive price-level range
+    pub level_end: usize,
+    pub time_start: usize, // Inclusive time-column range
+    pub time_end: usize,
+    pub centroid_level: f64,
+    pub centroid_time: f64,
+    pub depth: f64, // 1 - mean cell liquidity / median non-empty depth (1 = fully empty)
+}
+
+/// Differential geometry and voids of a liquidity surface z = L(level, t)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LiquiditySurfaceCurvature {
+    pub gaussian: Vec<Vec<f64>>, // Per cell, same shape as the input
+    pub mean: Vec<Vec<f64>>,
+    pub curvature: f64,          // Mean |H| over all cells
+    pub holes: usize,
+    pub voids: Vec<LiquidityVoid>, // Largest first
+    pub void_share: f64,         // Fraction of cells inside counted voids
+    pub manifold_dimension: f64, // Intrinsic dimension of the depth profiles over time (0 = undefined)
+    pub risk_level: LiquidityRisk,
+}
+
+/// Sliding windows of snapshot features for intrinsic dimension estimates
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct DimensionSeriesConfig {
+    pub window: usize, // Snapshots per point cloud
+    pub step: usize,   // Snapshots between consecutive windows
+    pub estimator: DimensionConfig,
+}
+
+impl Default for DimensionSeriesConfig {
+    fn default() -> Self {
+        DimensionSeriesConfig {
+            window: 100,
+            step: 10,
+            estimator: DimensionConfig::default(),
+        }
+    }
+}
+
+/// Intrinsic dimension of one window, stamped with its last snapshot
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct DimensionPoint {
+    pub timestamp: u64,
+    pub estimate: DimensionEstimate,
+}
+
+/// Filter function the Mapper cover is built over
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MapperLens {
+    #[default]
+    Principal, // First principal component of the state features
+    Mid,
+    Obi,
+    Spread,
+    Time,
+}
+
+impl MapperLens {
+    pub fn parse(value: &str) -> Result<Self, EngineError> {
+        match value.to_ascii_lowercase().as_str() {
+            "pca" | "principal" => Ok(MapperLens::Principal),
+            "mid" | "price" => Ok(MapperLens::Mid),
+            "obi" | "imbalance" => Ok(MapperLens::Obi),
+            "spread" => Ok(MapperLens::Spread),
+            "time" | "timestamp" => Ok(MapperLens::Time),
+            other => Err(EngineError::InvalidConfig(format!(
+                "unknown mapper lens '{}' (expected pca, mid, obi, spread or time)",
+                other
+            ))),
+        }
+    }
+}
+
+/// First snapshot whose prices or volumes are not finite, as an invalid-snapshot error
+fn check_finite(snapshots: &[OrderBookSnapshot]) -> Result<(), EngineError> {
+    match snapshots.iter().position(|s| !(s.bid_price + s.ask_price + s.bid_volume + s.ask_volume).is_finite()) {
+        Some(index) => Err(EngineError::InvalidSnapshot { index, error: ValidationError::NonFinite }),
+        None => Ok(()),
+    }
+}
 
 pub struct TopologicalAnalyzer;
 
@@ -7,39 +269,717 @@ impl TopologicalAnalyzer {
     /// Calculates the "curvature" of the market manifold.
     /// High curvature indicates structural instability (Flash Crash / Pump risk).
     pub fn calculate_curvature(snapshots: &[OrderBookSnapshot]) -> Vec<f64> {
-        snapshots.par_iter().map(|s| {
-            // 1. Manifold Hypothesis: Market data lies on a lower-dimensional manifold.
-            // 2. We measure the "local dimension" or curvature.
-            
-            // Simplified metric:
-            // High Volume + Tight Spread = Flat (Stable)
-            // Low Volume + Wide Spread = High Curvature (Unstable)
-            
-            let total_vol = s.bid_volume + s.ask_volume;
-            let spread = (s.ask_price - s.bid_price).abs();
-            
-            if total_vol == 0.0 {
-                return 1.0; // Max instability (Empty book)
-            }
-            
-            // Curvature formula approximation
-            // C = Spread / Volume_Density
-            let curvature = spread / total_vol;
-            
-            // Normalize to 0-1 range roughly (assuming typical crypto values)
-            // If curvature is high (> 0.1), it's a hole.
-            
-            curvature.min(1.0)
-        }).collect()
+        snapshots.par_iter().map(Self::snapshot_curvature).collect()
     }
-    
-    /// Detects topological "holes" (Persistence Homology approximation)
-    /// Returns true if a significant liquidity void is detected.
-    pub fn detect_holes(snapshots: &[OrderBookSnapshot]) -> bool {
-        let curvature = self::TopologicalAnalyzer::calculate_curvature(snapshots);
-        let avg_curvature: f64 = curvature.iter().sum::<f64>() / curvature.len() as f64;
+
+    /// Curvature of a single snapshot (shared by every compute backend)
+    pub fn snapshot_curvature(s: &OrderBookSnapshot) -> f64 {
+        // 1. Manifold Hypothesis: Market data lies on a lower-dimensional manifold.
+        // 2. We measure the "local dimension" or curvature.
+        
+        // Simplified metric:
+        // High Volume + Tight Spread = Flat (Stable)
+        // Low Volume + Wide Spread = High Curvature (Unstable)
+        
+        let total_vol = s.bid_volume + s.ask_volume;
+        let spread = (s.ask_price - s.bid_price).abs();
+        
+        if total_vol == 0.0 {
+            return 1.0; // Max instability (Empty book)
+        }
+        
+        // Curvature formula approximation
+        // C = Spread / Volume_Density
+        let curvature = spread / total_vol;
         
-        // Threshold for "Flash Crash" warning
-        avg_curvature > 0.05
+        // Normalize to 0-1 range roughly (assuming typical crypto values)
+        // If curvature is high (> 0.1), it's a hole.
+        
+        curvature.min(1.0)
+    }
+    
+    /// Standardised mid (and volume) channels, time-delay embedded
+    pub fn embed(snapshots: &[OrderBookSnapshot], config: &TdaConfig) -> Vec<Vec<f64>> {
+        let mids: Vec<f64> = snapshots.iter().map(|s| (s.bid_price + s.ask_price) / 2.0).collect();
+        let mut channels = vec![persistence::standardize(&mids)];
+        if config.include_volume {
+            let volumes: Vec<f64> = snapshots.iter().map(|s| s.bid_volume + s.ask_volume).collect();
+            channels.push(persistence::standardize(&volumes));
+        }
+        persistence::delay_embedding(&channels, config.dimension, config.delay)
+    }
+
+    /// H0/H1 persistence of the Vietoris-Rips filtration on the delay-embedded series
+    pub fn persistence(snapshots: &[OrderBookSnapshot], config: &TdaConfig) -> Result<TopologySummary, EngineError> {
+        check_finite(snapshots)?;
+        let config = &Self::resolve_embedding(snapshots, config)?;
+        if config.dimension == 0 || config.delay == 0 {
+            return Err(EngineError::InvalidConfig(format!(
+                "embedding needs dimension and delay >= 1, got {} / {}",
+                config.dimension, config.delay
+            )));
+        }
+
+        let cloud = Self::embed(snapshots, config);
+        if cloud.len() > config.max_points {
+            return Err(EngineError::InvalidConfig(format!(
+                "{} embedded points exceed max_points {} (Rips is cubic; window the series)",
+                cloud.len(),
+                config.max_points
+            )));
+        }
+
+        let diagram = persistence::rips_persistence(&cloud, config.max_edge.unwrap_or(f64::INFINITY));
+        Ok(TopologySummary {
+            points: cloud.len(),
+            dimension: config.dimension,
+            delay: config.delay,
+            total_persistence_h0: diagram.total_persistence(0, 1.0),
+            total_persistence_h1: diagram.total_persistence(1, 1.0),
+            max_persistence_h1: diagram.max_persistence(1),
+            holes: diagram
+                .h1
+                .iter()
+                .filter(|p| p.persistence() >= config.hole_persistence)
+                .count(),
+            diagram,
+        })
+    }
+
+    /// Persistence over sliding windows with diagram distances between consecutive
+    /// windows and fixed-length landscape / Betti curve features on a shared grid.
+    /// Essential features are clipped at the grid horizon (max_edge, else the largest
+    /// finite death in the first window that has one, else 1) so every diagram is
+    /// finite and comparable. Auto embedding and the horizon are both fixed by the
+    /// earliest windows, so a point never changes when later snapshots are appended
+    /// (unless every window so far is flat).
+    pub fn topology_series(
+        snapshots: &[OrderBookSnapshot],
+        config: &TdaConfig,
+        series: &TopologySeriesConfig,
+    ) -> Result<TopologySeries, EngineError> {
+        if series.window == 0 || series.step == 0 {
+            return Err(EngineError::InvalidConfig(format!(
+                "topology series needs window and step >= 1, got {} / {}",
+                series.window, series.step
+            )));
+        }
+        if !(series.wasserstein_p >= 1.0 && series.wasserstein_p.is_finite()) {
+            return Err(EngineError::InvalidConfig(format!(
+                "wasserstein_p must be a finite value >= 1, got {}",
+                series.wasserstein_p
+            )));
+        }
+        check_finite(snapshots)?;
+        if snapshots.len() < series.window {
+            return Ok(TopologySeries { grid: Vec::new(), points: Vec::new() });
+        }
+
+        // One embedding for the whole run so consecutive diagrams stay comparable;
+        // chosen from the first window only so the series stays causal
+        let config = &Self::resolve_embedding(&snapshots[..series.window], config)?;
+        let ends: Vec<usize> = (series.window..=snapshots.len()).step_by(series.step).collect();
+        let summaries = ends
+            .par_iter()
+            .map(|&end| Self::persistence(&snapshots[end - series.window..end], config))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // A flat opening window standardises to one point with nothing finite to
+        // measure, so the horizon comes from the first window with a positive death
+        let horizon = config.max_edge.unwrap_or_else(|| {
+            summaries
+                .iter()
+                .map(|s| {
+                    s.diagram
+                        .h0
+                        .iter()
+                        .chain(&s.diagram.h1)
+                        .filter(|p| !p.is_essential())
+                        .map(|p| p.death)
+                        .fold(0.0, f64::max)
+                })
+                .find(|&death| death > 0.0)
+                .unwrap_or(1.0)
+        });
+        let grid = persistence::filtration_grid(horizon, series.resolution);
+        let clipped: Vec<[Vec<PersistencePair>; 2]> = summaries
+            .iter()
+            .map(|s| {
+                [
+                    persistence::clip_essential(&s.diagram.h0, horizon),
+                    persistence::clip_essential(&s.diagram.h1, horizon),
+                ]
+            })
+            .collect();
+
+        let distances: Vec<Option<[f64; 4]>> = (0..clipped.len())
+            .into_par_iter()
+            .map(|i| {
+                let (prev, cur) = (clipped.get(i.checked_sub(1)?)?, &clipped[i]);
+                Some([
+                    persistence::bottleneck_distance(&prev[0], &cur[0]),
+                    persistence::bottleneck_distance(&prev[1], &cur[1]),
+                    persistence::wasserstein_distance(&prev[0], &cur[0], series.wasserstein_p),
+                    persistence::wasserstein_distance(&prev[1], &cur[1], series.wasserstein_p),
+                ])
+            })
+            .collect();
+
+        let mut points = Vec::with_capacity(ends.len());
+        for (i, ((&end, summary), diagrams)) in ends.iter().zip(&summaries).zip(&clipped).enumerate() {
+            let distance = distances[i];
+            let history: Vec<f64> = distances[i.saturating_sub(series.baseline)..i]
+                .iter()
+                .filter_map(|d| d.map(|d| d[3]))
+                .collect();
+            let distance_z = distance.and_then(|d| Self::spike_score(d[3], &history));
+
+            points.push(TopologyPoint {
+                timestamp: snapshots[end - 1].timestamp,
+                total_persistence_h1: summary.total_persistence_h1,
+                max_persistence_h1: summary.max_persistence_h1,
+                holes: summary.holes,
+                bottleneck_h0: distance.map(|d| d[0]),
+                bottleneck_h1: distance.map(|d| d[1]),
+                wasserstein_h0: distance.map(|d| d[2]),
+                wasserstein_h1: distance.map(|d| d[3]),
+                distance_z,
+                spike: distance_z.is_some_and(|z| z >= series.spike_z),
+                landscape_h0: persistence::persistence_landscape(&diagrams[0], series.landscape_levels, &grid),
+                landscape_h1: persistence::persistence_landscape(&diagrams[1], series.landscape_levels, &grid),
+                betti_h0: persistence::betti_curve(&diagrams[0], &grid),
+                betti_h1: persistence::betti_curve(&diagrams[1], &grid),
+            });
+        }
+
+        Ok(TopologySeries { grid, points })
+    }
+
+    /// Z-score of a distance against prior distances (None until two priors with spread)
+    fn spike_score(distance: f64, history: &[f64]) -> Option<f64> {
+        if history.len() < 2 {
+            return None;
+        }
+        let n = history.len() as f64;
+        let mean = history.iter().sum::<f64>() / n;
+        let std = (history.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+        (std > 0.0).then(|| (distance - mean) / std)
+    }
+
+    /// Curvature and voids of a liquidity surface, `surface[level][t]`. Heights are
+    /// scaled by the median non-empty depth so curvature is unit-free and a mostly
+    /// drained book still has a positive void threshold; derivatives are central
+    /// differences with edge cells clamped to their neighbours.
+    pub fn liquidity_curvature(
+        surface: &[Vec<f64>],
+        config: &LiquiditySurfaceConfig,
+    ) -> Result<LiquiditySurfaceCurvature, EngineError> {
+        let levels = surface.len();
+        let times = surface.first().map_or(0, Vec::len);
+        if levels == 0 || times == 0 || surface.iter().any(|row| row.len() != times) {
+            return Err(EngineError::InvalidConfig(
+                "liquidity surface must be a non-empty rectangular level × time grid".to_string(),
+            ));
+        }
+        if surface.iter().flatten().any(|v| !v.is_finite() || *v < 0.0) {
+            return Err(EngineError::InvalidConfig("liquidity must be finite and non-negative".to_string()));
+        }
+        if !(config.medium <= config.high && config.high <= config.critical) {
+            return Err(EngineError::InvalidConfig(format!(
+                "risk thresholds must be ordered, got {} / {} / {}",
+                config.medium, config.high, config.critical
+            )));
+        }
+
+        // Median of the non-empty cells: empty cells must not drag the reference to zero
+        let mut sorted: Vec<f64> = surface.iter().flatten().copied().filter(|&v| v > 0.0).collect();
+        sorted.sort_by(f64::total_cmp);
+        let median = sorted.get(sorted.len() / 2).copied().unwrap_or(0.0);
+        let scale = if median > 0.0 { median } else { 1.0 };
+        // A fully empty surface is one void
+        let threshold = if median > 0.0 { median * config.void_fraction } else { f64::MIN_POSITIVE };
+        let z = |i: usize, j: usize| surface[i][j] / scale;
+
+        let mut gaussian = vec![vec![0.0; times]; levels];
+        let mut mean = vec![vec![0.0; times]; levels];
+        for i in 0..levels {
+            let (up, down) = (i.saturating_sub(1), (i + 1).min(levels - 1));
+            for j in 0..times {
+                let (back, fwd) = (j.saturating_sub(1), (j + 1).min(times - 1));
+                let dx = (down - up).max(1) as f64;
+                let dy = (fwd - back).max(1) as f64;
+                let fx = (z(down, j) - z(up, j)) / dx;
+                let fy = (z(i, fwd) - z(i, back)) / dy;
+                let fxx = z(down, j) - 2.0 * z(i, j) + z(up, j);
+                let fyy = z(i, fwd) - 2.0 * z(i, j) + z(i, back);
+                let fxy = (z(down, fwd) - z(down, back) - z(up, fwd) + z(up, back)) / (dx * dy);
+
+                let g = 1.0 + fx * fx + fy * fy;
+                gaussian[i][j] = (fxx * fyy - fxy * fxy) / (g * g);
+                mean[i][j] = ((1.0 + fy * fy) * fxx - 2.0 * fx * fy * fxy + (1.0 + fx * fx) * fyy) / (2.0 * g.powf(1.5));
+            }
+        }
+        let cells = (levels * times) as f64;
+        let curvature = mean.iter().flatten().map(|h| h.abs()).sum::<f64>() / cells;
+
+        let voids = Self::liquidity_voids(surface, threshold, config.min_void_cells, median);
+        let void_share = voids.iter().map(|v| v.cells).sum::<usize>() as f64 / cells;
+        let risk_level = if voids.is_empty() {
+            LiquidityRisk::Low
+        } else if void_share >= config.critical {
+            LiquidityRisk::Critical
+        } else if void_share >= config.high {
+            LiquidityRisk::High
+        } else if void_share >= config.medium {
+            LiquidityRisk::Medium
+        } else {
+            LiquidityRisk::Low
+        };
+
+        Ok(LiquiditySurfaceCurvature {
+            gaussian,
+            mean,
+            curvature,
+            holes: voids.len(),
+            void_share,
+            manifold_dimension: Self::profile_dimension(surface, levels, times),
+            voids,
+            risk_level,
+        })
+    }
+
+    /// Connected components of cells strictly below `threshold` (flood fill, 4-neighbour)
+    fn liquidity_voids(surface: &[Vec<f64>], threshold: f64, min_cells: usize, median: f64) -> Vec<LiquidityVoid> {
+        let (levels, times) = (surface.len(), surface[0].len());
+        let mut seen = vec![vec![false; times]; levels];
+        let mut voids = Vec::new();
+        for start_level in 0..levels {
+            for start_time in 0..times {
+                if seen[start_level][start_time] || surface[start_level][start_time] >= threshold {
+                    continue;
+                }
+                seen[start_level][start_time] = true;
+                let mut stack = vec![(start_level, start_time)];
+                let mut void = LiquidityVoid {
+                    cells: 0,
+                    level_start: start_level,
+                    level_end: start_level,
+                    time_start: start_time,
+                    time_end: start_time,
+                    centroid_level: 0.0,
+                    centroid_time: 0.0,
+                    depth: 0.0,
+                };
+                let mut liquidity = 0.0;
+                while let Some((i, j)) = stack.pop() {
+                    void.cells += 1;
+                    void.level_start = void.level_start.min(i);
+                    void.level_end = void.level_end.max(i);
+                    void.time_start = void.time_start.min(j);
+                    void.time_end = void.time_end.max(j);
+                    void.centroid_level += i as f64;
+                    void.centroid_time += j as f64;
+                    liquidity += surface[i][j];
+
+                    let neighbours = [
+                        (i.wrapping_sub(1), j),
+                        (i + 1, j),
+                        (i, j.wrapping_sub(1)),
+                        (i, j + 1),
+                    ];
+                    for (ni, nj) in neighbours {
+                        if ni < levels && nj < times && !seen[ni][nj] && surface[ni][nj] < threshold {
+                            seen[ni][nj] = true;
+                            stack.push((ni, nj));
+                        }
+                    }
+                }
+                if void.cells < min_cells {
+                    continue;
+                }
+                let n = void.cells as f64;
+                void.centroid_level /= n;
+                void.centroid_time /= n;
+                void.depth = if median > 0.0 { 1.0 - liquidity / n / median } else { 1.0 };
+                voids.push(void);
+            }
+        }
+        voids.sort_by_key(|v| std::cmp::Reverse(v.cells));
+        voids
+    }
+
+    /// Intrinsic dimension of the cloud of depth profiles (one point per time column):
+    /// Levina-Bickel MLE, else Two-NN when too few distinct profiles for k neighbours
+    fn profile_dimension(surface: &[Vec<f64>], levels: usize, times: usize) -> f64 {
+        let profiles: Vec<Vec<f64>> = (0..times).map(|j| (0..levels).map(|i| surface[i][j]).collect()).collect();
+        let estimate = dimension::estimate_dimension(&profiles, &DimensionConfig::default());
+        estimate.mle.or(estimate.two_nn).unwrap_or(0.0)
+    }
+
+    /// Market state features per snapshot: mid, spread, bid and ask volume, each
+    /// standardised within the window so no single unit dominates the distances
+    pub fn state_features(snapshots: &[OrderBookSnapshot]) -> Vec<Vec<f64>> {
+        let columns: [Vec<f64>; 4] = [
+            snapshots.iter().map(|s| (s.bid_price + s.ask_price) / 2.0).collect(),
+            snapshots.iter().map(|s| s.ask_price - s.bid_price).collect(),
+            snapshots.iter().map(|s| s.bid_volume).collect(),
+            snapshots.iter().map(|s| s.ask_volume).collect(),
+        ];
+        let columns = columns.map(|c| persistence::standardize(&c));
+        (0..snapshots.len())
+            .map(|t| columns.iter().map(|c| c[t]).collect())
+            .collect()
+    }
+
+    /// Levina-Bickel, correlation and two-NN dimension of the market state over sliding windows
+    pub fn intrinsic_dimension_series(
+        snapshots: &[OrderBookSnapshot],
+        config: &DimensionSeriesConfig,
+    ) -> Result<Vec<DimensionPoint>, EngineError> {
+        if config.window < 3 || config.step == 0 {
+            return Err(EngineError::InvalidConfig(format!(
+                "dimension series needs window >= 3 and step >= 1, got {} / {}",
+                config.window, config.step
+            )));
+        }
+        config.estimator.validate()?;
+        check_finite(snapshots)?;
+        if snapshots.len() < config.window {
+            return Ok(Vec::new());
+        }
+
+        let ends: Vec<usize> = (config.window..=snapshots.len()).step_by(config.step).collect();
+        Ok(ends
+            .par_iter()
+            .map(|&end| {
+                let window = &snapshots[end - config.window..end];
+                DimensionPoint {
+                    timestamp: window[window.len() - 1].timestamp,
+                    estimate: dimension::estimate_dimension(&Self::state_features(window), &config.estimator),
+                }
+            })
+            .collect())
+    }
+
+    /// Mid or OBI series of a snapshot run
+    pub fn source_series(snapshots: &[OrderBookSnapshot], source: EmbeddingSource) -> Vec<f64> {
+        snapshots
+            .iter()
+            .map(|s| match source {
+                EmbeddingSource::Mid => (s.bid_price + s.ask_price) / 2.0,
+                EmbeddingSource::Obi => {
+                    let total = s.bid_volume + s.ask_volume;
+                    if total > 0.0 { (s.bid_volume - s.ask_volume) / total } else { 0.0 }
+                }
+            })
+            .collect()
+    }
+
+    /// Takens delay (first AMI minimum) and dimension (FNN / Cao) for the mid or OBI series
+    pub fn select_embedding(
+        snapshots: &[OrderBookSnapshot],
+        source: EmbeddingSource,
+        config: &TakensConfig,
+    ) -> Result<TakensEmbedding, EngineError> {
+        takens::select_embedding(&Self::source_series(snapshots, source), config)
+    }
+
+    /// Apply automatic embedding selection when the config asks for it
+    fn resolve_embedding(snapshots: &[OrderBookSnapshot], config: &TdaConfig) -> Result<TdaConfig, EngineError> {
+        if !config.auto_embedding {
+            return Ok(*config);
+        }
+        let embedding = Self::select_embedding(snapshots, config.embedding_source, &config.takens)?;
+        Ok(config.with_embedding(&embedding))
+    }
+
+    /// Mapper graph of the market state features; node members index into `snapshots`
+    pub fn mapper_graph(
+        snapshots: &[OrderBookSnapshot],
+        lens: MapperLens,
+        config: &MapperConfig,
+    ) -> Result<MapperGraph, EngineError> {
+        let features = Self::state_features(snapshots);
+        let values = match lens {
+            MapperLens::Principal => mapper::principal_lens(&features),
+            MapperLens::Mid => Self::source_series(snapshots, EmbeddingSource::Mid),
+            MapperLens::Obi => Self::source_series(snapshots, EmbeddingSource::Obi),
+            MapperLens::Spread => snapshots.iter().map(|s| s.ask_price - s.bid_price).collect(),
+            MapperLens::Time => snapshots.iter().map(|s| s.timestamp as f64).collect(),
+        };
+        mapper::mapper(&features, &values, config)
+    }
+
+    /// Detects topological "holes": a persistent H1 loop in the embedded price/volume
+    /// dynamics. Returns true if a significant liquidity void is detected in any window;
+    /// series longer than `max_points` are scanned in half-overlapping windows that fit.
+    pub fn detect_holes(snapshots: &[OrderBookSnapshot], config: &TdaConfig) -> Result<bool, EngineError> {
+        check_finite(snapshots)?; // Up front, so the index is into the full series, not a window
+        let config = &Self::resolve_embedding(snapshots, config)?;
+        let window = config.max_points + config.dimension.saturating_sub(1) * config.delay;
+        if snapshots.len() <= window {
+            return Ok(Self::persistence(snapshots, config)?.holes > 0);
+        }
+
+        let last = snapshots.len() - window;
+        let mut starts: Vec<usize> = (0..=last).step_by((config.max_points / 2).max(1)).collect();
+        if starts.last() != Some(&last) {
+            starts.push(last);
+        }
+        starts
+            .par_iter()
+            .map(|&start| Self::persistence(&snapshots[start..start + window], config).map(|s| s.holes > 0))
+            .try_reduce(|| false, |a, b| Ok(a || b))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::physics::takens::DimensionMethod;
+
+    pub(crate) fn tick(timestamp: u64, mid: f64, volume: f64) -> OrderBookSnapshot {
+        OrderBookSnapshot {
+            timestamp,
+            bid_volume: volume / 2.0,
+            ask_volume: volume / 2.0,
+            bid_price: mid - 0.01,
+            ask_price: mid + 0.01,
+            last_trade_price: None,
+            symbol: None,
+            venue: None,
+        }
+    }
+
+    /// Price and volume oscillating out of phase with the given period: a loop when embedded
+    pub(crate) fn oscillating(t: u64, period: f64) -> OrderBookSnapshot {
+        let phase = 2.0 * std::f64::consts::PI * t as f64 / period;
+        tick(t, 100.0 + phase.sin(), 50.0 + 10.0 * phase.cos())
+    }
+
+    #[test]
+    fn test_persistence_finds_cycles_not_drift() {
+        // Price and volume oscillating out of phase: a loop in the embedding
+        let cycle: Vec<OrderBookSnapshot> = (0..60)
+            .map(|t| oscillating(t, 15.0)).collect();
+        let summary = TopologicalAnalyzer::persistence(&cycle, &TdaConfig::default()).unwrap();
+        assert_eq!(summary.points, 58);
+        assert!(summary.holes >= 1);
+        assert!(summary.max_persistence_h1 >= 0.5);
+        assert!(TopologicalAnalyzer::detect_holes(&cycle, &TdaConfig::default()).unwrap());
+
+        // Steady drift with constant volume: a line, no loops
+        let drift: Vec<OrderBookSnapshot> = (0..60).map(|t| tick(t, 100.0 + 0.01 * t as f64, 50.0)).collect();
+        let summary = TopologicalAnalyzer::persistence(&drift, &TdaConfig::default()).unwrap();
+        assert_eq!(summary.holes, 0);
+        assert!(!TopologicalAnalyzer::detect_holes(&drift, &TdaConfig::default()).unwrap());
+
+        let tiny = TdaConfig { max_points: 10, ..Default::default() };
+        assert!(TopologicalAnalyzer::persistence(&drift, &tiny).is_err());
+        let mut gap = drift.clone();
+        gap[7].bid_volume = f64::NAN;
+        assert!(matches!(
+            TopologicalAnalyzer::persistence(&gap, &TdaConfig::default()),
+            Err(EngineError::InvalidSnapshot { index: 7, error: ValidationError::NonFinite })
+        ));
+
+        // Past max_points the series is windowed rather than reported as hole-free
+        let long_cycle: Vec<OrderBookSnapshot> = (0..500)
+            .map(|t| oscillating(t, 15.0)).collect();
+        assert!(TopologicalAnalyzer::persistence(&long_cycle, &TdaConfig::default()).is_err());
+        assert!(TopologicalAnalyzer::detect_holes(&long_cycle, &TdaConfig::default()).unwrap());
+        let mut gap = long_cycle.clone();
+        gap[450].ask_price = f64::INFINITY;
+        assert!(matches!(
+            TopologicalAnalyzer::detect_holes(&gap, &TdaConfig::default()),
+            Err(EngineError::InvalidSnapshot { index: 450, .. })
+        ));
+        assert!(TopologicalAnalyzer::detect_holes(&drift, &TdaConfig { max_points: 20, ..Default::default() }).is_ok());
+
+        // Automatic embedding picks a quarter-period-ish delay for the cycle and feeds the filtration
+        let auto = TdaConfig { auto_embedding: true, ..Default::default() };
+        let summary = TopologicalAnalyzer::persistence(&cycle, &auto).unwrap();
+        let chosen = TopologicalAnalyzer::select_embedding(&cycle, EmbeddingSource::Mid, &TakensConfig::default()).unwrap();
+        assert_eq!((summary.dimension, summary.delay), (chosen.dimension, chosen.delay));
+        assert!(chosen.delay > 1);
+        assert_eq!(summary.points, 60 - (chosen.dimension - 1) * chosen.delay);
+        assert!(summary.holes >= 1);
+
+        // The search runs on the configured source with the configured criterion
+        let takens = TakensConfig { max_dimension: 4, method: DimensionMethod::Cao, ..Default::default() };
+        let summary = TopologicalAnalyzer::persistence(&cycle, &TdaConfig { takens, ..auto }).unwrap();
+        let chosen = TopologicalAnalyzer::select_embedding(&cycle, EmbeddingSource::Mid, &takens).unwrap();
+        assert_eq!((summary.dimension, summary.delay), (chosen.dimension, chosen.delay));
+        let flat_obi = TdaConfig { embedding_source: EmbeddingSource::Obi, ..auto };
+        assert!(TopologicalAnalyzer::persistence(&cycle, &flat_obi).is_err()); // Balanced book: constant OBI
+    }
+
+    #[test]
+    fn test_liquidity_curvature_localises_voids() {
+        // 10 levels × 20 snapshots of flat depth: no curvature, no voids
+        let flat = vec![vec![100.0; 20]; 10];
+        let out = TopologicalAnalyzer::liquidity_curvature(&flat, &LiquiditySurfaceConfig::default()).unwrap();
+        assert_eq!(out.curvature, 0.0);
+        assert_eq!(out.holes, 0);
+        assert_eq!(out.risk_level, LiquidityRisk::Low);
+        assert_eq!(out.manifold_dimension, 0.0);
+
+        // Levels 3-4 drained for snapshots 10-14, plus one thin cell far away
+        let mut surface = flat.clone();
+        for row in &mut surface[3..5] {
+            for v in &mut row[10..15] {
+                *v = 5.0;
+            }
+        }
+        surface[8][2] = 0.0;
+        let out = TopologicalAnalyzer::liquidity_curvature(&surface, &LiquiditySurfaceConfig::default()).unwrap();
+        assert_eq!(out.holes, 2);
+        let void = &out.voids[0];
+        assert_eq!((void.cells, void.level_start, void.level_end), (10, 3, 4));
+        assert_eq!((void.time_start, void.time_end), (10, 14));
+        assert!((void.centroid_time - 12.0).abs() < 1e-12);
+        assert!((void.depth - 0.95).abs() < 1e-12);
+        assert!((out.void_share - 11.0 / 200.0).abs() < 1e-12);
+        assert_eq!(out.risk_level, LiquidityRisk::Medium);
+
+        // The void floor is a bowl: positive mean curvature at its centre, zero far away
+        assert!(out.mean[3][12] > 0.0);
+        assert_eq!(out.mean[0][0], 0.0);
+        assert!(out.curvature > 0.0);
+        assert!(out.manifold_dimension >= 1.0);
+
+        // Depth profiles driven by one travelling wave trace a curve: dimension ≈ 1
+        let wave: Vec<Vec<f64>> = (0..10)
+            .map(|i| (0..80).map(|t| 100.0 + 40.0 * (0.5 * i as f64 - 0.07 * t as f64).sin()).collect())
+            .collect();
+        let out = TopologicalAnalyzer::liquidity_curvature(&wave, &LiquiditySurfaceConfig::default()).unwrap();
+        assert!((out.manifold_dimension - 1.0).abs() < 0.3, "wave dimension {}", out.manifold_dimension);
+
+        let strict = LiquiditySurfaceConfig { min_void_cells: 2, medium: 0.01, high: 0.05, critical: 0.5, ..Default::default() };
+        let out = TopologicalAnalyzer::liquidity_curvature(&surface, &strict).unwrap();
+        assert_eq!(out.holes, 1);
+        assert_eq!(out.risk_level, LiquidityRisk::High); // 10/200 reaches HIGH's floor
+        assert!(TopologicalAnalyzer::liquidity_curvature(&[vec![1.0], vec![]], &strict).is_err());
+
+        // Mostly drained book: the void threshold comes from the non-empty cells, not a zero median
+        let mut drained = vec![vec![0.0; 20]; 10];
+        drained[0] = vec![100.0; 20];
+        drained[9] = vec![100.0; 20];
+        let out = TopologicalAnalyzer::liquidity_curvature(&drained, &LiquiditySurfaceConfig::default()).unwrap();
+        assert_eq!(out.holes, 1);
+        assert_eq!(out.voids[0].cells, 160);
+        assert_eq!(out.voids[0].depth, 1.0);
+        assert_eq!(out.risk_level, LiquidityRisk::Critical);
+        let empty = TopologicalAnalyzer::liquidity_curvature(&vec![vec![0.0; 4]; 3], &LiquiditySurfaceConfig::default()).unwrap();
+        assert_eq!((empty.holes, empty.risk_level), (1, LiquidityRisk::Critical));
+    }
+
+    #[test]
+    fn test_intrinsic_dimension_series_windows() {
+        // Mid and volume both driven by one oscillator at irregular phases: a closed
+        // curve in feature space (evenly spaced samples would defeat the two-NN ratio)
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        let snapshots: Vec<OrderBookSnapshot> = (0..200)
+            .map(|t| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                let phase = 2.0 * std::f64::consts::PI * (state >> 11) as f64 / (1u64 << 53) as f64;
+                tick(t, 100.0 + phase.sin(), 50.0 + 10.0 * phase.cos())
+            })
+            .collect();
+        let config = DimensionSeriesConfig { window: 100, step: 50, ..Default::default() };
+        let series = TopologicalAnalyzer::intrinsic_dimension_series(&snapshots, &config).unwrap();
+
+        assert_eq!(series.len(), 3); // Windows ending at 100, 150, 200
+        assert_eq!(series[2].timestamp, 199);
+        for point in &series {
+            assert_eq!(point.estimate.points, 100);
+            let d = point.estimate.two_nn.unwrap();
+            assert!((d - 1.0).abs() < 0.3, "two-NN {}", d);
+        }
+
+        let bad = DimensionSeriesConfig { window: 2, ..Default::default() };
+        assert!(TopologicalAnalyzer::intrinsic_dimension_series(&snapshots, &bad).is_err());
+        let mut gap = snapshots.clone();
+        gap[120].bid_price = f64::NAN;
+        assert!(matches!(
+            TopologicalAnalyzer::intrinsic_dimension_series(&gap, &config),
+            Err(EngineError::InvalidSnapshot { index: 120, error: ValidationError::NonFinite })
+        ));
+    }
+
+    #[test]
+    fn test_mapper_graph_separates_book_states() {
+        // Two regimes: tight deep book around 100, then thin book around 90
+        let mut snapshots: Vec<OrderBookSnapshot> = (0..40).map(|t| tick(t, 100.0 + 0.01 * (t % 5) as f64, 100.0)).collect();
+        snapshots.extend((40..80).map(|t| tick(t, 90.0 + 0.01 * (t % 5) as f64, 10.0)));
+
+        let config = MapperConfig { intervals: 4, cluster_eps: Some(0.5), ..Default::default() };
+        let graph = TopologicalAnalyzer::mapper_graph(&snapshots, MapperLens::Mid, &config).unwrap();
+        assert_eq!(graph.components, 2);
+        assert!(graph.nodes.iter().all(|n| n.members.iter().all(|&i| i < 40) || n.members.iter().all(|&i| i >= 40)));
+
+        let graph = TopologicalAnalyzer::mapper_graph(&snapshots, MapperLens::Principal, &config).unwrap();
+        assert_eq!(graph.components, 2);
+        assert!(MapperLens::parse("pca").is_ok() && MapperLens::parse("volume").is_err());
+    }
+
+    #[test]
+    fn test_topology_series_flags_regime_break() {
+        // Quiet price/volume cycle, then a one-way crash with volume draining
+        let mut snapshots: Vec<OrderBookSnapshot> = (0..120)
+            .map(|t| oscillating(t, 12.0)).collect();
+        snapshots.extend((120..140).map(|t| tick(t, 100.0 - 2.0 * (t - 119) as f64, 50.0 / (t - 118) as f64)));
+
+        let config = TdaConfig::default();
+        let series = TopologySeriesConfig { window: 30, step: 5, baseline: 10, ..Default::default() };
+        let out = TopologicalAnalyzer::topology_series(&snapshots, &config, &series).unwrap();
+
+        assert_eq!(out.points.len(), 23); // Windows ending at 30, 35, .., 140
+        assert_eq!(out.grid.len(), series.resolution);
+        assert_eq!(out.points[0].timestamp, 29);
+        assert!(out.points[0].wasserstein_h1.is_none());
+        for p in &out.points {
+            assert_eq!(p.landscape_h1.len(), series.landscape_levels * series.resolution);
+            assert_eq!(p.betti_h0.len(), series.resolution);
+            assert!(p.betti_h0[0] >= 1.0 && p.betti_h0[0] <= 28.0); // Distinct embedded points at 0
+        }
+
+        // Steady cycle: diagrams barely move; the crash windows jump
+        let calm = out.points[1..19].iter().filter_map(|p| p.wasserstein_h1).fold(0.0, f64::max);
+        let crash = out.points[19..].iter().filter_map(|p| p.wasserstein_h1).fold(0.0, f64::max);
+        assert!(crash > 2.0 * calm, "crash {} vs calm {}", crash, calm);
+        assert!(out.points[19..].iter().any(|p| p.spike));
+        assert!(!out.points[..19].iter().any(|p| p.spike));
+
+        // Causal: appending snapshots leaves earlier points (and the grid) unchanged
+        let prefix = TopologicalAnalyzer::topology_series(&snapshots[..100], &config, &series).unwrap();
+        assert_eq!(prefix.grid, out.grid);
+        for (a, b) in prefix.points.iter().zip(&out.points) {
+            assert_eq!(a.landscape_h1, b.landscape_h1);
+            assert_eq!(a.betti_h1, b.betti_h1);
+            assert_eq!(a.wasserstein_h1, b.wasserstein_h1);
+        }
+    }
+
+    #[test]
+    fn test_topology_series_survives_flat_open() {
+        // Constant quotes at the session open, then the cycle starts
+        let snapshots: Vec<OrderBookSnapshot> = (0..90)
+            .map(|t| match t {
+                0..=29 => tick(t, 100.0, 50.0),
+                _ => oscillating(t, 12.0),
+            })
+            .collect();
+        let series = TopologySeriesConfig { window: 30, step: 10, ..Default::default() };
+        let out = TopologicalAnalyzer::topology_series(&snapshots, &TdaConfig::default(), &series).unwrap();
+
+        // The horizon comes from the first non-flat window, so the grid is not all zeros
+        assert!(out.grid.last().is_some_and(|&h| h > 0.0));
+        assert_eq!(out.points[0].betti_h0[0], 1.0); // The flat window is a single point
+        assert!(out.points[0].betti_h0.iter().all(|&b| b <= 1.0));
+        assert!(out.points[6].landscape_h1.iter().any(|&v| v > 0.0));
+        assert!(out.points[6].betti_h0.windows(2).any(|w| w[1] < w[0]));
     }
 }
@@ -0,0 +1,462 @@
+// TRADES.rs - Trade Tape and Aggressor Classification
+// COMPLEXITY: O(n + q) per tape (n trades, q quotes), single ordered pass
+// DETERMINISTIC: Sequential, input order defines tick-rule history
+
+use crate::physics::obi_engine::{EngineError, OrderBookSnapshot};
+use crate::physics::validation::{self, ValidationError};
+use serde::{Deserialize, Serialize};
+
+/// Executed trade from the tape
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Trade {
+    pub timestamp: u64,
+    pub price: f64,
+    pub volume: f64,
+    #[serde(default)]
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub venue: Option<String>,
+}
+
+/// Which side initiated (crossed the spread for) a trade
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggressor {
+    Buy,
+    Sell,
+    Unknown, // No price history or quote to decide from
+}
+
+impl Aggressor {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Aggressor::Buy => "BUY",
+            Aggressor::Sell => "SELL",
+            Aggressor::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+/// Aggressor classification algorithm
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClassificationMethod {
+    TickRule,   // Up-tick = buy, down-tick = sell, zero-tick repeats the last decision
+    #[default]
+    LeeReady,   // Quote rule against the prevailing mid, tick rule at the mid
+    BulkVolume, // Easley/López de Prado/O'Hara BVC: per-bar buy fraction Φ(ΔP / σ_ΔP)
+}
+
+impl ClassificationMethod {
+    /// Parse "tick" | "lee-ready" | "bvc" (case-insensitive, common aliases accepted)
+    pub fn parse(name: &str) -> Result<Self, EngineError> {
+        match name.to_ascii_lowercase().replace(['_', ' '], "-").as_str() {
+            "tick" | "tick-rule" => Ok(ClassificationMethod::TickRule),
+            "lee-ready" | "leeready" | "lr" => Ok(ClassificationMethod::LeeReady),
+            "bvc" | "bulk-volume" => Ok(ClassificationMethod::BulkVolume),
+            other => Err(EngineError::InvalidConfig(format!(
+                "unknown trade classification '{}' (expected tick | lee-ready | bvc)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Trade classifier configuration
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct TradeClassifierConfig {
+    pub method: ClassificationMethod,
+    pub quote_lag_ms: u64, // Lee-Ready: use the last quote at or before (trade time - lag)
+    pub bar_ms: u64,       // BVC: time bar width
+}
+
+impl Default for TradeClassifierConfig {
+    fn default() -> Self {
+        TradeClassifierConfig {
+            method: ClassificationMethod::LeeReady,
+            quote_lag_ms: 0,
+            bar_ms: 60_000,
+        }
+    }
+}
+
+/// Trade with its aggressor and signed volume
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClassifiedTrade {
+    pub timestamp: u64,
+    pub price: f64,
+    pub volume: f64,
+    pub aggressor: Aggressor,
+    pub buy_volume: f64,    // BVC splits a trade fractionally; other methods are all-or-nothing
+    pub sell_volume: f64,
+    pub signed_volume: f64, // buy_volume - sell_volume
+}
+
+/// Signed trade flow aggregated into a time bucket
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TradeFlowBucket {
+    pub bucket_start: u64,
+    pub trades: usize,
+    pub buy_volume: f64,
+    pub sell_volume: f64,
+    pub signed_volume: f64,
+    pub trade_imbalance: f64, // (buy - sell) / (buy + sell), the trade-side analogue of OBI
+}
+
+/// Classified tape plus its bucketed signed-volume series
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TradeFlow {
+    pub trades: Vec<ClassifiedTrade>,
+    pub buckets: Vec<TradeFlowBucket>,
+}
+
+/// Reject tapes (and Lee-Ready quotes) the classifiers cannot order or sign.
+/// Bad rows are `InvalidSnapshot` errors indexed into `trades`, or into `quotes` for quote errors;
+/// a trade has no side yet, so a negative trade volume is reported on both sides.
+pub fn validate_tape(
+    trades: &[Trade],
+    quotes: &[OrderBookSnapshot],
+    config: &TradeClassifierConfig,
+) -> Result<(), EngineError> {
+    if config.method == ClassificationMethod::BulkVolume && config.bar_ms == 0 {
+        return Err(EngineError::InvalidConfig("bulk volume classification needs bar_ms > 0".to_string()));
+    }
+
+    let reject = |index: usize, error: ValidationError| Err(EngineError::InvalidSnapshot { index, error });
+    let out_of_order = |timestamp: u64, previous: u64| ValidationError::Stale {
+        timestamp,
+        reference: previous,
+    };
+
+    for (i, trade) in trades.iter().enumerate() {
+        if !trade.price.is_finite() || !trade.volume.is_finite() {
+            return reject(i, ValidationError::NonFinite);
+        }
+        if trade.volume < 0.0 {
+            return reject(i, ValidationError::NegativeVolume {
+                bid_volume: trade.volume,
+                ask_volume: trade.volume,
+            });
+        }
+        if i > 0 && trade.timestamp < trades[i - 1].timestamp {
+            return reject(i, out_of_order(trade.timestamp, trades[i - 1].timestamp));
+        }
+    }
+
+    // A zero, non-finite or crossed quote would become the prevailing mid
+    for (i, quote) in quotes.iter().enumerate() {
+        validation::validate_snapshot(quote).or_else(|error| reject(i, error))?;
+        if i > 0 && quote.timestamp < quotes[i - 1].timestamp {
+            return reject(i, out_of_order(quote.timestamp, quotes[i - 1].timestamp));
+        }
+    }
+    Ok(())
+}
+
+/// Standard normal CDF (Abramowitz-Stegun 7.1.26 erf, |error| < 1.5e-7)
+pub fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * z);
+    let poly = t
+        * (0.254_829_592 + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-z * z).exp();
+
+    if x >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+/// Tick rule state: last non-zero price change decides zero ticks
+struct TickRule {
+    last_price: Option<f64>,
+    last_side: Aggressor,
+}
+
+impl TickRule {
+    fn new() -> Self {
+        TickRule {
+            last_price: None,
+            last_side: Aggressor::Unknown,
+        }
+    }
+
+    fn classify(&mut self, price: f64) -> Aggressor {
+        if let Some(last) = self.last_price {
+            if price > last {
+                self.last_side = Aggressor::Buy;
+            } else if price < last {
+                self.last_side = Aggressor::Sell;
+            }
+        }
+        self.last_price = Some(price);
+        self.last_side
+    }
+}
+
+fn classified(trade: &Trade, aggressor: Aggressor, buy_fraction: f64) -> ClassifiedTrade {
+    let buy_volume = trade.volume * buy_fraction;
+    let sell_volume = trade.volume - buy_volume;
+
+    ClassifiedTrade {
+        timestamp: trade.timestamp,
+        price: trade.price,
+        volume: trade.volume,
+        aggressor,
+        buy_volume,
+        sell_volume,
+        signed_volume: buy_volume - sell_volume,
+    }
+}
+
+/// Split volume by a discrete aggressor (Unknown counts half to each side)
+fn discrete(trade: &Trade, aggressor: Aggressor) -> ClassifiedTrade {
+    let buy_fraction = match aggressor {
+        Aggressor::Buy => 1.0,
+        Aggressor::Sell => 0.0,
+        Aggressor::Unknown => 0.5,
+    };
+    classified(trade, aggressor, buy_fraction)
+}
+
+fn classify_tick_rule(trades: &[Trade]) -> Vec<ClassifiedTrade> {
+    let mut tick = TickRule::new();
+    trades.iter().map(|t| discrete(t, tick.classify(t.price))).collect()
+}
+
+/// Lee-Ready: above the prevailing mid = buy, below = sell, at the mid = tick rule.
+/// `quotes` must be in timestamp order; trades before the first quote use the tick rule.
+fn classify_lee_ready(trades: &[Trade], quotes: &[OrderBookSnapshot], lag_ms: u64) -> Vec<ClassifiedTrade> {
+    let mut tick = TickRule::new();
+    let mut next_quote = 0;
+    let mut mid: Option<f64> = None;
+
+    trades
+        .iter()
+        .map(|trade| {
+            let cutoff = trade.timestamp.saturating_sub(lag_ms);
+            while next_quote < quotes.len() && quotes[next_quote].timestamp <= cutoff {
+                let q = &quotes[next_quote];
+                mid = Some((q.bid_price + q.ask_price) / 2.0);
+                next_quote += 1;
+            }
+
+            // Tick rule always advances so the at-mid fallback has history
+            let by_tick = tick.classify(trade.price);
+            let aggressor = match mid {
+                Some(m) if trade.price > m => Aggressor::Buy,
+                Some(m) if trade.price < m => Aggressor::Sell,
+                _ => by_tick,
+            };
+            discrete(trade, aggressor)
+        })
+        .collect()
+}
+
+/// Bulk volume classification over `bar_ms` time bars: every trade in a bar gets
+/// buy fraction Φ(ΔP / σ), ΔP the bar's close-to-close change, σ its stddev over the tape.
+/// The first bar has no previous close and splits its volume evenly.
+fn classify_bulk_volume(trades: &[Trade], bar_ms: u64) -> Vec<ClassifiedTrade> {
+    let bar_ms = bar_ms.max(1);
+
+    // (bar id, first index, end index, close price)
+    let mut bars: Vec<(u64, usize, usize, f64)> = Vec::new();
+    for (i, trade) in trades.iter().enumerate() {
+        let bar = trade.timestamp / bar_ms;
+        match bars.last_mut() {
+            Some(last) if last.0 == bar => {
+                last.2 = i + 1;
+                last.3 = trade.price;
+            }
+            _ => bars.push((bar, i, i + 1, trade.price)),
+        }
+    }
+
+    let changes: Vec<f64> = bars.windows(2).map(|pair| pair[1].3 - pair[0].3).collect();
+
+    let n = changes.len() as f64;
+    let mean = changes.iter().sum::<f64>() / n.max(1.0);
+    let sigma = (changes.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0)).sqrt();
+
+    let mut out = Vec::with_capacity(trades.len());
+    for (b, &(_, start, end, _)) in bars.iter().enumerate() {
+        let buy_fraction = match b.checked_sub(1) {
+            Some(previous) if sigma > 0.0 => normal_cdf(changes[previous] / sigma),
+            _ => 0.5,
+        };
+        let aggressor = if buy_fraction > 0.5 {
+            Aggressor::Buy
+        } else if buy_fraction < 0.5 {
+            Aggressor::Sell
+        } else {
+            Aggressor::Unknown
+        };
+        out.extend(trades[start..end].iter().map(|t| classified(t, aggressor, buy_fraction)));
+    }
+    out
+}
+
+/// Classify a tape (in time order) with the configured method
+pub fn classify_trades(
+    trades: &[Trade],
+    quotes: &[OrderBookSnapshot],
+    config: &TradeClassifierConfig,
+) -> Vec<ClassifiedTrade> {
+    match config.method {
+        ClassificationMethod::TickRule => classify_tick_rule(trades),
+        ClassificationMethod::LeeReady => classify_lee_ready(trades, quotes, config.quote_lag_ms),
+        ClassificationMethod::BulkVolume => classify_bulk_volume(trades, config.bar_ms),
+    }
+}
+
+/// Aggregate signed volume into `bucket_ms` time buckets (0 = one bucket per trade)
+pub fn aggregate_trade_flow(trades: &[ClassifiedTrade], bucket_ms: u64) -> Vec<TradeFlowBucket> {
+    let mut buckets: Vec<TradeFlowBucket> = Vec::new();
+
+    for (i, trade) in trades.iter().enumerate() {
+        let start = if bucket_ms > 0 {
+            trade.timestamp - trade.timestamp % bucket_ms
+        } else {
+            trade.timestamp
+        };
+
+        let same_bucket = bucket_ms > 0 && i > 0 && buckets.last().is_some_and(|b| b.bucket_start == start);
+        if !same_bucket {
+            buckets.push(TradeFlowBucket {
+                bucket_start: start,
+                trades: 0,
+                buy_volume: 0.0,
+                sell_volume: 0.0,
+                signed_volume: 0.0,
+                trade_imbalance: 0.0,
+            });
+        }
+
+        let bucket = buckets.last_mut().expect("bucket pushed above");
+        bucket.trades += 1;
+        bucket.buy_volume += trade.buy_volume;
+        bucket.sell_volume += trade.sell_volume;
+        bucket.signed_volume += trade.signed_volume;
+        let total = bucket.buy_volume + bucket.sell_volume;
+        bucket.trade_imbalance = if total > 0.0 {
+            (bucket.buy_volume - bucket.sell_volume) / total
+        } else {
+            0.0
+        };
+    }
+
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(timestamp: u64, price: f64, volume: f64) -> Trade {
+        Trade {
+            timestamp,
+            price,
+            volume,
+            symbol: None,
+            venue: None,
+        }
+    }
+
+    fn quote(timestamp: u64, bid_price: f64, ask_price: f64) -> OrderBookSnapshot {
+        OrderBookSnapshot {
+            timestamp,
+            bid_volume: 1.0,
+            ask_volume: 1.0,
+            bid_price,
+            ask_price,
+            last_trade_price: None,
+            symbol: None,
+            venue: None,
+        }
+    }
+
+    fn sides(classified: &[ClassifiedTrade]) -> Vec<Aggressor> {
+        classified.iter().map(|t| t.aggressor).collect()
+    }
+
+    #[test]
+    fn test_tick_rule_lee_ready_and_bvc() {
+        use Aggressor::*;
+        let tape = vec![
+            trade(1_000, 100.0, 1.0),
+            trade(2_000, 102.0, 2.0), // up-tick
+            trade(3_000, 102.0, 1.0), // zero-tick after up
+            trade(4_000, 101.0, 3.0), // down-tick
+            trade(5_000, 101.0, 1.0), // zero-tick after down
+        ];
+
+        let tick = classify_trades(&tape, &[], &TradeClassifierConfig {
+            method: ClassificationMethod::TickRule,
+            ..Default::default()
+        });
+        assert_eq!(sides(&tick), vec![Unknown, Buy, Buy, Sell, Sell]);
+        assert_eq!(tick[1].signed_volume, 2.0);
+        assert_eq!(tick[3].signed_volume, -3.0);
+        assert_eq!(tick[0].signed_volume, 0.0);
+
+        // Mid 101 from t=500, then 101.5 from t=3500
+        let quotes = vec![quote(500, 100.0, 102.0), quote(3_500, 101.0, 102.0)];
+        let lr = classify_trades(&tape, &quotes, &TradeClassifierConfig::default());
+        // t=1000 below mid; t=2000/3000 above; t=4000/5000 below 101.5
+        assert_eq!(sides(&lr), vec![Sell, Buy, Buy, Sell, Sell]);
+
+        // With a 1s quote lag the t=4000 trade still sees the old mid (at mid -> tick rule: down)
+        let lagged = classify_trades(&tape, &quotes, &TradeClassifierConfig {
+            quote_lag_ms: 1_000,
+            ..Default::default()
+        });
+        assert_eq!(lagged[3].aggressor, Sell);
+        assert_eq!(lagged[0].aggressor, Unknown); // No quote yet, no tick history
+
+        // BVC over 2s bars: rising bar leans buy, falling bar leans sell, volume conserved
+        let bvc = classify_trades(&tape, &[], &TradeClassifierConfig {
+            method: ClassificationMethod::BulkVolume,
+            bar_ms: 2_000,
+            ..Default::default()
+        });
+        assert_eq!(bvc[0].aggressor, Unknown); // First bar has no previous close
+        assert_eq!(bvc[1].aggressor, Buy);
+        assert_eq!(bvc[3].aggressor, Sell);
+        for t in &bvc {
+            assert!((t.buy_volume + t.sell_volume - t.volume).abs() < 1e-12);
+        }
+
+        // 2s buckets: [1000], [2000, 3000], [4000, 5000]
+        let flow = aggregate_trade_flow(&tick, 2_000);
+        assert_eq!(flow.len(), 3);
+        assert_eq!((flow[1].bucket_start, flow[1].trades), (2_000, 2));
+        assert_eq!(flow[1].signed_volume, 3.0);
+        assert_eq!(flow[2].trade_imbalance, -1.0);
+
+        // Lee-Ready quotes must be in timestamp order and signable
+        let config = TradeClassifierConfig::default();
+        assert!(validate_tape(&tape, &quotes, &config).is_ok());
+        let unordered = vec![quotes[1].clone(), quotes[0].clone()];
+        assert!(matches!(
+            validate_tape(&tape, &unordered, &config),
+            Err(EngineError::InvalidSnapshot { index: 1, error: ValidationError::Stale { .. } })
+        ));
+        for (bad, expected) in [
+            (quote(600, 100.0, 0.0), "NON_POSITIVE_PRICE"),
+            (quote(600, f64::NAN, 102.0), "NON_FINITE"),
+            (quote(600, 103.0, 102.0), "CROSSED"),
+        ] {
+            let error = validate_tape(&tape, &[quotes[0].clone(), bad], &config).unwrap_err();
+            assert!(matches!(error, EngineError::InvalidSnapshot { index: 1, .. }));
+            assert!(error.to_string().contains(expected), "{}", error);
+        }
+        assert!(matches!(
+            validate_tape(&[trade(1, f64::INFINITY, 1.0)], &[], &config),
+            Err(EngineError::InvalidSnapshot { index: 0, error: ValidationError::NonFinite })
+        ));
+
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-9);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-4);
+        assert!(ClassificationMethod::parse("Lee_Ready").is_ok());
+        assert!(ClassificationMethod::parse("vpin").is_err());
+    }
+}
@@ -0,0 +1,390 @@
+// VALIDATION.rs - Order Book Snapshot Sanity Checks
+// COMPLEXITY: O(n) per batch (O(n * L) for depth books), single sequential pass (staleness depends on order)
+// DETERMINISTIC: Pure comparisons, no floating-point accumulation
+
+use crate::physics::obi_columns::OrderBookColumns;
+use crate::physics::obi_engine::{DepthSnapshot, EngineError, OrderBookSnapshot, PriceLevel};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Why a snapshot was rejected
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ValidationError {
+    Crossed { bid: f64, ask: f64 },                // bid > ask
+    NegativeVolume { bid_volume: f64, ask_volume: f64 },
+    NonFinite,                                     // NaN / ±inf in a price or volume
+    NonPositivePrice { bid: f64, ask: f64 },       // Zero or negative quote (zero mid)
+    Stale { timestamp: u64, reference: u64 },      // Out of order, or older than max_age_ms
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::Crossed { bid, ask } => write!(f, "CROSSED: bid {} > ask {}", bid, ask),
+            ValidationError::NegativeVolume { bid_volume, ask_volume } => {
+                write!(f, "NEGATIVE_VOLUME: bid {} / ask {}", bid_volume, ask_volume)
+            }
+            ValidationError::NonFinite => write!(f, "NON_FINITE: price or volume is NaN/inf"),
+            ValidationError::NonPositivePrice { bid, ask } => {
+                write!(f, "NON_POSITIVE_PRICE: bid {} / ask {}", bid, ask)
+            }
+            ValidationError::Stale { timestamp, reference } => {
+                write!(f, "STALE: timestamp {} behind {}", timestamp, reference)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// What a batch does with invalid snapshots
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationMode {
+    #[default]
+    Strict, // Reject the whole batch on the first invalid snapshot
+    Skip,   // Drop invalid snapshots from the results
+    Flag,   // Keep every snapshot, attach the error to its result
+}
+
+impl ValidationMode {
+    /// Parse "strict" | "skip" | "flag" (case-insensitive)
+    pub fn parse(name: &str) -> Result<Self, EngineError> {
+        match name.to_ascii_lowercase().as_str() {
+            "strict" => Ok(ValidationMode::Strict),
+            "skip" => Ok(ValidationMode::Skip),
+            "flag" => Ok(ValidationMode::Flag),
+            other => Err(EngineError::InvalidConfig(format!(
+                "unknown validation mode '{}' (expected strict | skip | flag)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Per-batch validation configuration
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct ValidationConfig {
+    pub mode: ValidationMode,
+    pub max_age_ms: Option<u64>, // Older than reference - max_age_ms => Stale (None = no age limit)
+    pub now_ms: Option<u64>,     // Age reference (None = newest timestamp in the batch)
+}
+
+/// Validation counters returned with every batch
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ValidationCounts {
+    pub total: usize,
+    pub valid: usize,
+    pub crossed: usize,
+    pub negative_volume: usize,
+    pub non_finite: usize,
+    pub non_positive_price: usize,
+    pub stale: usize,
+}
+
+impl ValidationCounts {
+    fn record(&mut self, outcome: &Result<(), ValidationError>) {
+        self.total += 1;
+        match outcome {
+            Ok(()) => self.valid += 1,
+            Err(ValidationError::Crossed { .. }) => self.crossed += 1,
+            Err(ValidationError::NegativeVolume { .. }) => self.negative_volume += 1,
+            Err(ValidationError::NonFinite) => self.non_finite += 1,
+            Err(ValidationError::NonPositivePrice { .. }) => self.non_positive_price += 1,
+            Err(ValidationError::Stale { .. }) => self.stale += 1,
+        }
+    }
+
+    pub fn invalid(&self) -> usize {
+        self.total - self.valid
+    }
+}
+
+/// Check a single snapshot's prices and volumes (no staleness)
+pub fn validate_snapshot(snapshot: &OrderBookSnapshot) -> Result<(), ValidationError> {
+    let fields = [
+        snapshot.bid_price,
+        snapshot.ask_price,
+        snapshot.bid_volume,
+        snapshot.ask_volume,
+    ];
+    if fields.iter().any(|v| !v.is_finite()) {
+        return Err(ValidationError::NonFinite);
+    }
+
+    if snapshot.bid_price <= 0.0 || snapshot.ask_price <= 0.0 {
+        return Err(ValidationError::NonPositivePrice {
+            bid: snapshot.bid_price,
+            ask: snapshot.ask_price,
+        });
+    }
+
+    if snapshot.bid_volume < 0.0 || snapshot.ask_volume < 0.0 {
+        return Err(ValidationError::NegativeVolume {
+            bid_volume: snapshot.bid_volume,
+            ask_volume: snapshot.ask_volume,
+        });
+    }
+
+    // Locked (bid == ask) is allowed, crossed is not
+    if snapshot.bid_price > snapshot.ask_price {
+        return Err(ValidationError::Crossed {
+            bid: snapshot.bid_price,
+            ask: snapshot.ask_price,
+        });
+    }
+
+    Ok(())
+}
+
+/// Check a depth book: the top of book as `validate_snapshot`, then every deeper level
+pub fn validate_depth_snapshot(snapshot: &DepthSnapshot) -> Result<(), ValidationError> {
+    let top = snapshot.top_of_book();
+    validate_snapshot(&top)?;
+
+    let check = |level: &PriceLevel, quote: (f64, f64), volumes: (f64, f64)| {
+        if !level.price.is_finite() || !level.volume.is_finite() {
+            Err(ValidationError::NonFinite)
+        } else if level.price <= 0.0 {
+            Err(ValidationError::NonPositivePrice { bid: quote.0, ask: quote.1 })
+        } else if level.volume < 0.0 {
+            Err(ValidationError::NegativeVolume {
+                bid_volume: volumes.0,
+                ask_volume: volumes.1,
+            })
+        } else {
+            Ok(())
+        }
+    };
+
+    for level in snapshot.bids.iter().skip(1) {
+        check(level, (level.price, top.ask_price), (level.volume, top.ask_volume))?;
+    }
+    for level in snapshot.asks.iter().skip(1) {
+        check(level, (top.bid_price, level.price), (top.bid_volume, level.volume))?;
+    }
+
+    Ok(())
+}
+
+/// Validate a batch in order. A snapshot is stale if it goes back in time relative
+/// to the last valid snapshot, or is older than `max_age_ms` before the reference time.
+pub fn validate_batch(
+    snapshots: &[OrderBookSnapshot],
+    config: &ValidationConfig,
+) -> (Vec<Result<(), ValidationError>>, ValidationCounts) {
+    validate_sequence(snapshots, |s| s.timestamp, validate_snapshot, config)
+}
+
+/// `validate_batch` for depth books, checking every level
+pub fn validate_depth_batch(
+    snapshots: &[DepthSnapshot],
+    config: &ValidationConfig,
+) -> (Vec<Result<(), ValidationError>>, ValidationCounts) {
+    validate_sequence(snapshots, |s| s.timestamp, validate_depth_snapshot, config)
+}
+
+/// Row-by-row `validate_snapshot` over columnar input. Columns carry no timestamps,
+/// so rows are never stale.
+pub fn validate_columns(input: &OrderBookColumns<'_>) -> (Vec<Result<(), ValidationError>>, ValidationCounts) {
+    let mut counts = ValidationCounts::default();
+
+    let outcomes = (0..input.len())
+        .map(|i| {
+            let outcome = validate_snapshot(&OrderBookSnapshot {
+                timestamp: 0,
+                bid_volume: input.bid_volume[i],
+                ask_volume: input.ask_volume[i],
+                bid_price: input.bid_price[i],
+                ask_price: input.ask_price[i],
+                last_trade_price: None,
+                symbol: None,
+                venue: None,
+            });
+            counts.record(&outcome);
+            outcome
+        })
+        .collect();
+
+    (outcomes, counts)
+}
+
+fn validate_sequence<T>(
+    items: &[T],
+    timestamp_of: impl Fn(&T) -> u64,
+    check: impl Fn(&T) -> Result<(), ValidationError>,
+    config: &ValidationConfig,
+) -> (Vec<Result<(), ValidationError>>, ValidationCounts) {
+    let reference = config
+        .now_ms
+        .unwrap_or_else(|| items.iter().map(&timestamp_of).max().unwrap_or(0));
+
+    let mut counts = ValidationCounts::default();
+    let mut last_valid: Option<u64> = None;
+
+    let outcomes = items
+        .iter()
+        .map(|item| {
+            let timestamp = timestamp_of(item);
+            let outcome = check(item).and_then(|()| {
+                if let Some(previous) = last_valid.filter(|&p| timestamp < p) {
+                    return Err(ValidationError::Stale {
+                        timestamp,
+                        reference: previous,
+                    });
+                }
+                match config.max_age_ms {
+                    Some(max_age) if reference.saturating_sub(timestamp) > max_age => {
+                        Err(ValidationError::Stale { timestamp, reference })
+                    }
+                    _ => Ok(()),
+                }
+            });
+
+            if outcome.is_ok() {
+                last_valid = Some(timestamp);
+            }
+            counts.record(&outcome);
+            outcome
+        })
+        .collect();
+
+    (outcomes, counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::obi_engine::{DepthObiConfig, EngineConfig, ObiConfig, OfiConfig, PhysicsEngine};
+
+    fn book(timestamp: u64, bid_price: f64, ask_price: f64, bid_volume: f64, ask_volume: f64) -> OrderBookSnapshot {
+        OrderBookSnapshot {
+            timestamp,
+            bid_volume,
+            ask_volume,
+            bid_price,
+            ask_price,
+            last_trade_price: None,
+            symbol: None,
+            venue: None,
+        }
+    }
+
+    #[test]
+    fn test_validation_modes_and_counters() {
+        let snapshots = vec![
+            book(1_000, 100.0, 100.1, 10.0, 5.0),      // valid
+            book(1_001, 100.2, 100.1, 10.0, 5.0),      // crossed
+            book(1_002, 100.0, 100.1, -1.0, 5.0),      // negative volume
+            book(1_003, f64::NAN, 100.1, 10.0, 5.0),   // non-finite
+            book(1_004, 0.0, 0.0, 10.0, 5.0),          // zero mid
+            book(999, 100.0, 100.1, 10.0, 5.0),        // back in time
+            book(1_005, 100.0, 100.1, 5.0, 10.0),      // valid
+        ];
+        let engine = PhysicsEngine::new(EngineConfig::default()).unwrap();
+        let obi_config = ObiConfig::default();
+
+        // Strict: first bad snapshot rejects the batch, with its index
+        let strict = ValidationConfig::default();
+        match engine.calculate_obi_batch_validated(&snapshots, &obi_config, &strict) {
+            Err(EngineError::InvalidSnapshot { index, error }) => {
+                assert_eq!(index, 1);
+                assert!(matches!(error, ValidationError::Crossed { .. }));
+            }
+            other => panic!("expected InvalidSnapshot, got {:?}", other.map(|b| b.counts)),
+        }
+
+        // Skip: only the valid snapshots survive, mapped back to their input index
+        let skip = ValidationConfig { mode: ValidationMode::Skip, ..Default::default() };
+        let batch = engine.calculate_obi_batch_validated(&snapshots, &obi_config, &skip).unwrap();
+        assert_eq!(batch.indices, vec![0, 6]);
+        assert_eq!(batch.results.len(), 2);
+        assert_eq!(
+            batch.counts,
+            ValidationCounts {
+                total: 7,
+                valid: 2,
+                crossed: 1,
+                negative_volume: 1,
+                non_finite: 1,
+                non_positive_price: 1,
+                stale: 1,
+            }
+        );
+
+        // Flag: every snapshot has a result, invalid ones carry the error
+        let flag = ValidationConfig { mode: ValidationMode::Flag, ..Default::default() };
+        let batch = engine.calculate_obi_batch_validated(&snapshots, &obi_config, &flag).unwrap();
+        assert_eq!(batch.results.len(), 7);
+        assert!(batch.results[0].validation.is_none());
+        assert!(matches!(batch.results[5].validation, Some(ValidationError::Stale { reference: 1_000, .. })));
+        assert_eq!(batch.counts.invalid(), 5);
+
+        // Age limit against an explicit clock
+        let aged = ValidationConfig {
+            mode: ValidationMode::Skip,
+            max_age_ms: Some(2),
+            now_ms: Some(1_005),
+        };
+        let batch = engine.calculate_obi_batch_validated(&snapshots, &obi_config, &aged).unwrap();
+        assert_eq!(batch.indices, vec![6]);
+        assert_eq!(batch.counts.stale, 2);
+
+        // OFI: skipped snapshots drop out of the tick pairing, flagged ones keep their slot
+        let ofi_config = OfiConfig::default();
+        let batch = engine.calculate_ofi_batch_validated(&snapshots, &ofi_config, &skip).unwrap();
+        assert_eq!(batch.indices, vec![0, 6]);
+        let paired = engine.calculate_ofi_batch(&[snapshots[0].clone(), snapshots[6].clone()], &ofi_config);
+        assert_eq!(batch.results[1].ofi, paired[1].ofi);
+        let batch = engine.calculate_ofi_batch_validated(&snapshots, &ofi_config, &flag).unwrap();
+        assert_eq!(batch.results.len(), 7);
+        assert!(matches!(batch.results[1].validation, Some(ValidationError::Crossed { .. })));
+        assert!(engine.calculate_ofi_batch_validated(&snapshots, &ofi_config, &strict).is_err());
+
+        // Columns: row checks only, so the back-in-time row is valid
+        let column = |f: fn(&OrderBookSnapshot) -> f64| -> Vec<f64> { snapshots.iter().map(f).collect() };
+        let (bid_price, bid_volume) = (column(|s| s.bid_price), column(|s| s.bid_volume));
+        let (ask_price, ask_volume) = (column(|s| s.ask_price), column(|s| s.ask_volume));
+        let columns = OrderBookColumns::new(&bid_price, &bid_volume, &ask_price, &ask_volume).unwrap();
+        let batch = engine.calculate_obi_columns_validated(columns, ValidationMode::Skip).unwrap();
+        assert_eq!(batch.indices, vec![0, 5, 6]);
+        assert_eq!(batch.columns.obi.len(), 3);
+        assert_eq!(batch.counts.stale, 0);
+        let batch = engine.calculate_obi_columns_validated(columns, ValidationMode::Flag).unwrap();
+        assert_eq!(batch.columns.obi.len(), 7);
+        assert_eq!(batch.flagged.iter().map(|&(i, _)| i).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert!(matches!(
+            engine.calculate_obi_columns_validated(columns, ValidationMode::Strict),
+            Err(EngineError::InvalidSnapshot { index: 1, .. })
+        ));
+
+        // Depth: every level is checked, not just the top of book
+        let level = |price: f64, volume: f64| PriceLevel { price, volume };
+        let depth_book = |timestamp: u64, deep_bid: PriceLevel, deep_ask: PriceLevel| DepthSnapshot {
+            timestamp,
+            bids: vec![level(100.0, 5.0), deep_bid],
+            asks: vec![level(100.1, 5.0), deep_ask],
+            last_trade_price: None,
+            symbol: None,
+            venue: None,
+        };
+        let depth_books = vec![
+            depth_book(1, level(99.9, 3.0), level(100.2, 3.0)),
+            depth_book(2, level(99.9, 3.0), level(100.2, f64::INFINITY)),
+            depth_book(3, level(99.9, -2.0), level(100.2, 3.0)),
+            depth_book(4, level(99.9, 3.0), level(100.2, 4.0)),
+        ];
+        let depth = DepthObiConfig::default();
+        let batch = engine
+            .calculate_depth_obi_batch_validated(&depth_books, &obi_config, &depth, &skip)
+            .unwrap();
+        assert_eq!(batch.indices, vec![0, 3]);
+        assert_eq!((batch.counts.non_finite, batch.counts.negative_volume), (1, 1));
+        assert!(matches!(
+            engine.calculate_depth_obi_batch_validated(&depth_books, &obi_config, &depth, &strict),
+            Err(EngineError::InvalidSnapshot { index: 1, error: ValidationError::NonFinite })
+        ));
+
+        assert!(ValidationMode::parse("FLAG").is_ok());
+        assert!(ValidationMode::parse("lenient").is_err());
+    }
+}
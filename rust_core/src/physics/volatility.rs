@@ -0,0 +1,403 @@
+// VOLATILITY.rs - Range-Based Volatility, Realised Variance and Jump Detection
+// COMPLEXITY: O(n) per series (n snapshots or bars); rolling windows via prefix sums
+// DETERMINISTIC: Sequential sums in bar order (f64, last-ulp platform dependent via ln)
+
+use crate::physics::obi_engine::{EngineError, OrderBookSnapshot};
+use crate::physics::validation::ValidationError;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::{LN_2, PI};
+
+/// μ_{4/3} = E|Z|^{4/3} = 2^{2/3} Γ(7/6) / Γ(1/2), for tripower quarticity
+const MU_4_3: f64 = 0.830_860_6;
+
+/// Asymptotic variance constant of the BNS ratio jump statistic, (π/2)² + π - 5
+const BNS_THETA: f64 = PI * PI / 4.0 + PI - 5.0;
+
+/// OHLC bar (timestamp = bar start)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct OhlcBar {
+    pub timestamp: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+/// Volatility configuration
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct VolatilityConfig {
+    pub bar_ms: u64,       // Mid-price bar width when building bars from snapshots
+    pub window: usize,     // Bars per rolling estimate
+    pub jump_z: f64,       // BNS z above which a window is flagged as containing a jump
+}
+
+impl Default for VolatilityConfig {
+    fn default() -> Self {
+        VolatilityConfig {
+            bar_ms: 60_000,
+            window: 20,
+            jump_z: 3.09, // One-sided 99.9%
+        }
+    }
+}
+
+/// Volatility estimates over a run of bars. Range estimators are per-bar volatilities
+/// (sqrt of the mean per-bar variance); realised measures are window totals in log-return².
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VolatilityEstimate {
+    pub timestamp: u64, // Start of the last bar in the window
+    pub bars: usize,
+    pub parkinson: f64,
+    pub garman_klass: f64,
+    pub rogers_satchell: f64,
+    pub yang_zhang: Option<f64>,  // Needs two bars with a preceding close
+    pub realized_variance: f64,   // Σ r²
+    pub bipower_variation: f64,   // (π/2) Σ |r_i||r_{i-1}|, robust to jumps
+    pub jump_variation: f64,      // max(RV - BV, 0)
+    pub jump_z: Option<f64>,      // Barndorff-Nielsen-Shephard ratio statistic
+    pub jump: bool,               // jump_z > config.jump_z
+}
+
+fn check_bar(bar: &OhlcBar) -> Result<(), ValidationError> {
+    let prices = [bar.open, bar.high, bar.low, bar.close];
+    if prices.iter().any(|p| !p.is_finite()) {
+        return Err(ValidationError::NonFinite);
+    }
+    if prices.iter().any(|&p| p <= 0.0) {
+        return Err(ValidationError::NonPositivePrice { bid: bar.low, ask: bar.high });
+    }
+    let (body_low, body_high) = (bar.open.min(bar.close), bar.open.max(bar.close));
+    if bar.low > body_low {
+        return Err(ValidationError::Crossed { bid: bar.low, ask: body_low });
+    }
+    if body_high > bar.high {
+        return Err(ValidationError::Crossed { bid: body_high, ask: bar.high });
+    }
+    Ok(())
+}
+
+/// Bars plus the log returns that feed realised variance, each tagged with its bar
+#[derive(Debug, Clone, Default)]
+pub struct BarSeries {
+    pub bars: Vec<OhlcBar>,
+    returns: Vec<(usize, f64)>,
+}
+
+impl BarSeries {
+    /// Mid-price bars of `bar_ms`; realised measures use tick-to-tick mid returns.
+    /// Snapshots with a non-positive or non-finite mid are skipped.
+    pub fn from_snapshots(snapshots: &[OrderBookSnapshot], bar_ms: u64) -> Result<Self, EngineError> {
+        if bar_ms == 0 {
+            return Err(EngineError::InvalidConfig("volatility bar_ms must be positive".to_string()));
+        }
+
+        let mut series = BarSeries::default();
+        let mut last_mid: Option<f64> = None;
+        let mut last_bucket: Option<u64> = None;
+
+        for snapshot in snapshots {
+            let mid = (snapshot.bid_price + snapshot.ask_price) / 2.0;
+            if !(mid.is_finite() && mid > 0.0) {
+                continue;
+            }
+
+            let bucket = snapshot.timestamp / bar_ms;
+            if last_bucket != Some(bucket) {
+                last_bucket = Some(bucket);
+                series.bars.push(OhlcBar {
+                    timestamp: bucket * bar_ms,
+                    open: mid,
+                    high: mid,
+                    low: mid,
+                    close: mid,
+                });
+            } else {
+                let bar = series.bars.last_mut().expect("bar pushed for this bucket");
+                bar.high = bar.high.max(mid);
+                bar.low = bar.low.min(mid);
+                bar.close = mid;
+            }
+
+            if let Some(previous) = last_mid {
+                series.returns.push((series.bars.len() - 1, (mid / previous).ln()));
+            }
+            last_mid = Some(mid);
+        }
+
+        Ok(series)
+    }
+
+    /// Pre-built bars; realised measures use close-to-close returns.
+    /// A bar whose low/high does not bracket its open and close is rejected as Crossed,
+    /// reporting the pair that crosses.
+    pub fn from_bars(bars: &[OhlcBar]) -> Result<Self, EngineError> {
+        for (index, bar) in bars.iter().enumerate() {
+            if let Err(error) = check_bar(bar) {
+                return Err(EngineError::InvalidSnapshot { index, error });
+            }
+        }
+
+        Ok(BarSeries {
+            bars: bars.to_vec(),
+            returns: bars
+                .windows(2)
+                .enumerate()
+                .map(|(i, w)| (i + 1, (w[1].close / w[0].close).ln()))
+                .collect(),
+        })
+    }
+}
+
+/// Per-bar terms, summed over windows with prefix sums
+#[derive(Debug, Clone, Copy, Default)]
+struct BarTerms {
+    parkinson: f64,
+    garman_klass: f64,
+    rogers_satchell: f64,
+    open_jump: f64,       // ln(O_i / C_{i-1})
+    open_jump_sq: f64,
+    open_count: f64,      // 1 when the bar has a preceding close
+    open_close: f64,      // ln(C_i / O_i)
+    open_close_sq: f64,
+    rv: f64,              // Σ r² of returns ending in this bar
+    bv: f64,              // Σ |r_i||r_{i-1}|
+    tq: f64,              // Σ |r_i r_{i-1} r_{i-2}|^{4/3}
+    returns: f64,
+}
+
+impl std::ops::AddAssign for BarTerms {
+    fn add_assign(&mut self, o: Self) {
+        self.parkinson += o.parkinson;
+        self.garman_klass += o.garman_klass;
+        self.rogers_satchell += o.rogers_satchell;
+        self.open_jump += o.open_jump;
+        self.open_jump_sq += o.open_jump_sq;
+        self.open_count += o.open_count;
+        self.open_close += o.open_close;
+        self.open_close_sq += o.open_close_sq;
+        self.rv += o.rv;
+        self.bv += o.bv;
+        self.tq += o.tq;
+        self.returns += o.returns;
+    }
+}
+
+impl std::ops::Sub for BarTerms {
+    type Output = Self;
+
+    fn sub(self, o: Self) -> Self {
+        BarTerms {
+            parkinson: self.parkinson - o.parkinson,
+            garman_klass: self.garman_klass - o.garman_klass,
+            rogers_satchell: self.rogers_satchell - o.rogers_satchell,
+            open_jump: self.open_jump - o.open_jump,
+            open_jump_sq: self.open_jump_sq - o.open_jump_sq,
+            open_count: self.open_count - o.open_count,
+            open_close: self.open_close - o.open_close,
+            open_close_sq: self.open_close_sq - o.open_close_sq,
+            rv: self.rv - o.rv,
+            bv: self.bv - o.bv,
+            tq: self.tq - o.tq,
+            returns: self.returns - o.returns,
+        }
+    }
+}
+
+fn bar_terms(series: &BarSeries) -> Vec<BarTerms> {
+    let mut terms: Vec<BarTerms> = series
+        .bars
+        .iter()
+        .enumerate()
+        .map(|(i, bar)| {
+            let hl = (bar.high / bar.low).ln();
+            let co = (bar.close / bar.open).ln();
+            let (hc, ho) = ((bar.high / bar.close).ln(), (bar.high / bar.open).ln());
+            let (lc, lo) = ((bar.low / bar.close).ln(), (bar.low / bar.open).ln());
+            let open_jump = if i > 0 { (bar.open / series.bars[i - 1].close).ln() } else { 0.0 };
+
+            BarTerms {
+                parkinson: hl * hl / (4.0 * LN_2),
+                garman_klass: 0.5 * hl * hl - (2.0 * LN_2 - 1.0) * co * co,
+                rogers_satchell: hc * ho + lc * lo,
+                open_jump,
+                open_jump_sq: open_jump * open_jump,
+                open_count: if i > 0 { 1.0 } else { 0.0 },
+                open_close: co,
+                open_close_sq: co * co,
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    let r = &series.returns;
+    for (k, &(bar, ret)) in r.iter().enumerate() {
+        let t = &mut terms[bar];
+        t.rv += ret * ret;
+        t.returns += 1.0;
+        if k >= 1 {
+            t.bv += ret.abs() * r[k - 1].1.abs();
+        }
+        if k >= 2 {
+            t.tq += (ret * r[k - 1].1 * r[k - 2].1).abs().powf(4.0 / 3.0);
+        }
+    }
+
+    terms
+}
+
+/// Sample variance from Σx, Σx², n (None below two observations)
+fn sample_variance(sum: f64, sum_sq: f64, n: f64) -> Option<f64> {
+    (n >= 2.0).then(|| ((sum_sq - sum * sum / n) / (n - 1.0)).max(0.0))
+}
+
+fn estimate(t: &BarTerms, bars: usize, timestamp: u64, jump_z: f64) -> VolatilityEstimate {
+    let n = bars as f64;
+
+    // Yang-Zhang: overnight + k * open-to-close + (1 - k) * Rogers-Satchell
+    let yang_zhang = match (
+        sample_variance(t.open_jump, t.open_jump_sq, t.open_count),
+        sample_variance(t.open_close, t.open_close_sq, n),
+    ) {
+        (Some(open_var), Some(close_var)) => {
+            let k = 0.34 / (1.34 + (n + 1.0) / (n - 1.0));
+            Some((open_var + k * close_var + (1.0 - k) * t.rogers_satchell / n).max(0.0).sqrt())
+        }
+        _ => None,
+    };
+
+    let bipower_variation = PI / 2.0 * t.bv;
+    let m = t.returns;
+    let jump_z_stat = (m >= 3.0 && bipower_variation > 0.0 && t.rv > 0.0).then(|| {
+        let tripower = m * MU_4_3.powi(-3) * (m / (m - 2.0)) * t.tq;
+        let relative_jump = (t.rv - bipower_variation) / t.rv;
+        relative_jump / (BNS_THETA / m * (tripower / (bipower_variation * bipower_variation)).max(1.0)).sqrt()
+    });
+
+    VolatilityEstimate {
+        timestamp,
+        bars,
+        parkinson: (t.parkinson / n).sqrt(),
+        garman_klass: (t.garman_klass / n).max(0.0).sqrt(),
+        rogers_satchell: (t.rogers_satchell / n).max(0.0).sqrt(),
+        yang_zhang,
+        realized_variance: t.rv,
+        bipower_variation,
+        jump_variation: (t.rv - bipower_variation).max(0.0),
+        jump_z: jump_z_stat,
+        jump: jump_z_stat.is_some_and(|z| z > jump_z),
+    }
+}
+
+/// One estimate over the whole series (None for an empty series)
+pub fn estimate_volatility(series: &BarSeries, config: &VolatilityConfig) -> Option<VolatilityEstimate> {
+    let last = series.bars.last()?;
+    let mut total = BarTerms::default();
+    for t in bar_terms(series) {
+        total += t;
+    }
+    Some(estimate(&total, series.bars.len(), last.timestamp, config.jump_z))
+}
+
+/// Estimate over each trailing window of `config.window` bars, one per bar from the
+/// window-th bar on. Return products pair each return with its predecessor on the tick grid.
+pub fn rolling_volatility(series: &BarSeries, config: &VolatilityConfig) -> Result<Vec<VolatilityEstimate>, EngineError> {
+    if config.window == 0 {
+        return Err(EngineError::InvalidConfig("volatility window must be at least 1 bar".to_string()));
+    }
+
+    let mut prefix = vec![BarTerms::default()];
+    for t in bar_terms(series) {
+        let mut next = *prefix.last().expect("prefix starts non-empty");
+        next += t;
+        prefix.push(next);
+    }
+
+    Ok((config.window..=series.bars.len())
+        .map(|end| {
+            let window = prefix[end] - prefix[end - config.window];
+            estimate(&window, config.window, series.bars[end - 1].timestamp, config.jump_z)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(timestamp: u64, open: f64, high: f64, low: f64, close: f64) -> OhlcBar {
+        OhlcBar { timestamp, open, high, low, close }
+    }
+
+    #[test]
+    fn test_range_estimators_and_jump_detection() {
+        let config = VolatilityConfig { window: 2, ..Default::default() };
+
+        // Flat bars: every estimator is zero
+        let flat = BarSeries::from_bars(&[bar(0, 100.0, 100.0, 100.0, 100.0); 3]).unwrap();
+        let est = estimate_volatility(&flat, &config).unwrap();
+        assert_eq!((est.parkinson, est.garman_klass, est.rogers_satchell), (0.0, 0.0, 0.0));
+        assert_eq!(est.yang_zhang, Some(0.0));
+
+        // Single bar: Parkinson matches the closed form
+        let one = BarSeries::from_bars(&[bar(0, 100.0, 110.0, 100.0, 105.0)]).unwrap();
+        let est = estimate_volatility(&one, &config).unwrap();
+        let hl = (1.1f64).ln();
+        assert!((est.parkinson - (hl * hl / (4.0 * LN_2)).sqrt()).abs() < 1e-12);
+        assert_eq!(est.yang_zhang, None);
+
+        // Rolling over bars equals the batch estimate of the same bars
+        let bars = vec![
+            bar(0, 100.0, 101.0, 99.0, 100.5),
+            bar(60_000, 100.7, 102.0, 100.1, 101.5),
+            bar(120_000, 101.2, 101.8, 99.5, 99.9),
+        ];
+        let series = BarSeries::from_bars(&bars).unwrap();
+        let rolling = rolling_volatility(&series, &config).unwrap();
+        assert_eq!(rolling.len(), 2);
+        let tail = BarSeries::from_bars(&bars[1..]).unwrap();
+        let batch = estimate_volatility(&tail, &config).unwrap();
+        assert!((rolling[1].parkinson - batch.parkinson).abs() < 1e-12);
+        assert!((rolling[1].rogers_satchell - batch.rogers_satchell).abs() < 1e-12);
+        assert_eq!(rolling[1].timestamp, 120_000);
+        // The rolling window sees bar 1's overnight gap, the standalone tail does not
+        assert!(rolling[1].yang_zhang.is_some());
+
+        // Mid ticks: small alternating moves, then one 2% jump
+        let mut mids: Vec<f64> = (0..60).map(|i| 100.0 * (1.0 + 0.0005 * ((i % 2) as f64 - 0.5))).collect();
+        mids.extend((0..20).map(|i| 102.0 * (1.0 + 0.0005 * ((i % 2) as f64 - 0.5))));
+        let snapshots: Vec<OrderBookSnapshot> = mids
+            .iter()
+            .enumerate()
+            .map(|(i, &mid)| OrderBookSnapshot {
+                timestamp: i as u64 * 1_000,
+                bid_volume: 1.0,
+                ask_volume: 1.0,
+                bid_price: mid - 0.01,
+                ask_price: mid + 0.01,
+                last_trade_price: None,
+                symbol: None,
+                venue: None,
+            })
+            .collect();
+        let series = BarSeries::from_snapshots(&snapshots, 10_000).unwrap();
+        assert_eq!(series.bars.len(), 8);
+        let est = estimate_volatility(&series, &config).unwrap();
+        assert!(est.realized_variance > est.bipower_variation);
+        assert!(est.jump_variation > 0.0);
+        assert!(est.jump);
+
+        // Without the jump, RV and BV agree and nothing is flagged
+        let calm = BarSeries::from_snapshots(&snapshots[..60], 10_000).unwrap();
+        assert!(!estimate_volatility(&calm, &config).unwrap().jump);
+
+        assert!(matches!(
+            BarSeries::from_bars(&[bar(0, 100.0, 101.0, 99.0, 100.0), bar(1, 100.0, 99.0, 98.0, 100.0)]),
+            Err(EngineError::InvalidSnapshot { index: 1, error: ValidationError::Crossed { bid, ask } }) if bid == 100.0 && ask == 99.0
+        ));
+        assert!(matches!(
+            BarSeries::from_bars(&[bar(0, f64::NAN, 101.0, 99.0, 100.0)]),
+            Err(EngineError::InvalidSnapshot { index: 0, error: ValidationError::NonFinite })
+        ));
+        assert!(BarSeries::from_snapshots(&snapshots, 0).is_err());
+    }
+}
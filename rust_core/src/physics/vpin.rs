@@ -0,0 +1,368 @@
+// VPIN.rs - Volume-Synchronised Probability of Informed Trading
+// COMPLEXITY: O(n + b * w) per tape (n trades, b buckets, w = window), O(w + h) memory
+// DETERMINISTIC: Sequential bucket fill in trade order
+
+use crate::physics::obi_engine::{EngineError, OrderBookSnapshot};
+use crate::physics::trades::{self, normal_cdf, ClassifiedTrade, Trade, TradeClassifierConfig};
+use crate::physics::validation::ValidationError;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Most buckets a single trade may complete; larger prints are rejected
+pub const MAX_BUCKETS_PER_TRADE: f64 = 100_000.0;
+
+/// Toxicity alert from the VPIN CDF (same scale as Omega's riskLevel)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VpinAlert {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl VpinAlert {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            VpinAlert::Low => "LOW",
+            VpinAlert::Medium => "MEDIUM",
+            VpinAlert::High => "HIGH",
+            VpinAlert::Critical => "CRITICAL",
+        }
+    }
+}
+
+/// VPIN configuration
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct VpinConfig {
+    pub bucket_volume: f64, // Volume per bucket (0 = auto in batch: tape volume / (window + cdf_window))
+    pub window: usize,      // Buckets averaged per VPIN value
+    pub cdf_window: usize,  // Past VPIN values the log-normal CDF is fitted to
+    pub medium: f64,        // CDF alert levels
+    pub high: f64,
+    pub critical: f64,
+}
+
+impl Default for VpinConfig {
+    fn default() -> Self {
+        VpinConfig {
+            bucket_volume: 0.0,
+            window: 50,
+            cdf_window: 250,
+            medium: 0.8,
+            high: 0.9,
+            critical: 0.99,
+        }
+    }
+}
+
+impl VpinConfig {
+    fn validate(&self) -> Result<(), EngineError> {
+        if !(self.bucket_volume.is_finite() && self.bucket_volume > 0.0) {
+            return Err(EngineError::InvalidConfig(format!(
+                "VPIN bucket_volume must be positive, got {}",
+                self.bucket_volume
+            )));
+        }
+        if self.window == 0 || self.cdf_window < 2 {
+            return Err(EngineError::InvalidConfig(
+                "VPIN needs window >= 1 and cdf_window >= 2".to_string(),
+            ));
+        }
+        if !(0.0 < self.medium && self.medium <= self.high && self.high <= self.critical && self.critical <= 1.0) {
+            return Err(EngineError::InvalidConfig(format!(
+                "VPIN alert levels must satisfy 0 < medium <= high <= critical <= 1, got {} / {} / {}",
+                self.medium, self.high, self.critical
+            )));
+        }
+        Ok(())
+    }
+
+    fn alert(&self, cdf: Option<f64>) -> VpinAlert {
+        match cdf {
+            Some(c) if c >= self.critical => VpinAlert::Critical,
+            Some(c) if c >= self.high => VpinAlert::High,
+            Some(c) if c >= self.medium => VpinAlert::Medium,
+            _ => VpinAlert::Low,
+        }
+    }
+}
+
+/// One completed volume bucket with the VPIN over the trailing window
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VpinPoint {
+    pub bucket: usize,          // 0-based bucket index
+    pub start_timestamp: u64,   // First trade contributing to the bucket
+    pub timestamp: u64,         // Trade that completed the bucket
+    pub buy_volume: f64,
+    pub sell_volume: f64,
+    pub vpin: Option<f64>,      // Σ|V_B - V_S| / (window * bucket_volume), None until `window` buckets exist
+    pub cdf: Option<f64>,       // Log-normal CDF of vpin against the preceding `cdf_window` values
+    pub alert: VpinAlert,
+}
+
+/// Streaming VPIN: feed classified trades in order, collect completed buckets
+pub struct VpinEstimator {
+    config: VpinConfig,
+    bucket: usize,
+    bucket_start: Option<u64>,
+    buy: f64,
+    sell: f64,
+    imbalances: VecDeque<f64>, // |V_B - V_S| of the last `window` buckets
+    history: VecDeque<f64>,    // ln VPIN of the last `cdf_window` values
+}
+
+impl VpinEstimator {
+    pub fn new(config: VpinConfig) -> Result<Self, EngineError> {
+        config.validate()?;
+        Ok(VpinEstimator {
+            config,
+            bucket: 0,
+            bucket_start: None,
+            buy: 0.0,
+            sell: 0.0,
+            imbalances: VecDeque::with_capacity(config.window),
+            history: VecDeque::with_capacity(config.cdf_window),
+        })
+    }
+
+    /// Add one trade; a large trade may complete several buckets (volume split pro rata).
+    /// Rejects non-finite or negative volume and trades completing over `MAX_BUCKETS_PER_TRADE` buckets.
+    pub fn push(&mut self, trade: &ClassifiedTrade) -> Result<Vec<VpinPoint>, EngineError> {
+        let error = if !trade.volume.is_finite() {
+            Some(ValidationError::NonFinite)
+        } else if trade.volume < 0.0 {
+            Some(ValidationError::NegativeVolume {
+                bid_volume: trade.buy_volume,
+                ask_volume: trade.sell_volume,
+            })
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            return Err(EngineError::InvalidSnapshot { index: 0, error });
+        }
+        let mut completed = Vec::new();
+        if trade.volume == 0.0 {
+            return Ok(completed);
+        }
+
+        let buy_fraction = (trade.buy_volume / trade.volume).clamp(0.0, 1.0);
+        let size = self.config.bucket_volume;
+        let space = size - (self.buy + self.sell);
+        // Relative tolerance so fractional splits do not leave a dust bucket
+        let dust = size * 1e-12;
+
+        // Whole buckets after the open one are identical, so count them
+        // instead of subtracting bucket by bucket
+        let take = trade.volume.min(space);
+        let remaining = trade.volume - take;
+        let mut whole = (remaining / size).floor();
+        let mut rest = remaining - whole * size;
+        if size - rest <= dust {
+            whole += 1.0;
+            rest = 0.0;
+        }
+        if whole + 1.0 > MAX_BUCKETS_PER_TRADE {
+            return Err(EngineError::InvalidConfig(format!(
+                "VPIN trade of {} completes more than {} buckets of {}; raise bucket_volume",
+                trade.volume, MAX_BUCKETS_PER_TRADE, size
+            )));
+        }
+
+        // Top up the open bucket
+        self.bucket_start.get_or_insert(trade.timestamp);
+        self.buy += take * buy_fraction;
+        self.sell += take * (1.0 - buy_fraction);
+        if space - take > dust {
+            return Ok(completed);
+        }
+
+        completed.reserve(whole as usize + 1);
+        completed.push(self.close_bucket(trade.timestamp));
+        for _ in 0..whole as usize {
+            self.bucket_start = Some(trade.timestamp);
+            self.buy = size * buy_fraction;
+            self.sell = size * (1.0 - buy_fraction);
+            completed.push(self.close_bucket(trade.timestamp));
+        }
+        if rest > 0.0 {
+            self.bucket_start = Some(trade.timestamp);
+            self.buy = rest * buy_fraction;
+            self.sell = rest * (1.0 - buy_fraction);
+        }
+
+        Ok(completed)
+    }
+
+    fn close_bucket(&mut self, timestamp: u64) -> VpinPoint {
+        if self.imbalances.len() == self.config.window {
+            self.imbalances.pop_front();
+        }
+        self.imbalances.push_back((self.buy - self.sell).abs());
+
+        let vpin = (self.imbalances.len() == self.config.window).then(|| {
+            let total: f64 = self.imbalances.iter().sum();
+            (total / (self.config.window as f64 * self.config.bucket_volume)).min(1.0)
+        });
+
+        let cdf = vpin.and_then(|v| {
+            let cdf = self.lognormal_cdf(v);
+            if self.history.len() == self.config.cdf_window {
+                self.history.pop_front();
+            }
+            self.history.push_back(v.max(1e-12).ln());
+            cdf
+        });
+
+        let point = VpinPoint {
+            bucket: self.bucket,
+            start_timestamp: self.bucket_start.unwrap_or(timestamp),
+            timestamp,
+            buy_volume: self.buy,
+            sell_volume: self.sell,
+            vpin,
+            cdf,
+            alert: self.config.alert(cdf),
+        };
+
+        self.bucket += 1;
+        self.bucket_start = None;
+        self.buy = 0.0;
+        self.sell = 0.0;
+        point
+    }
+
+    /// Φ((ln v - μ) / σ) with μ, σ of the stored log-VPIN history (None until it has spread)
+    fn lognormal_cdf(&self, vpin: f64) -> Option<f64> {
+        let n = self.history.len();
+        if n < 2 {
+            return None;
+        }
+        let mean = self.history.iter().sum::<f64>() / n as f64;
+        let variance = self.history.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+        let sigma = variance.sqrt();
+        if sigma <= 0.0 {
+            return None;
+        }
+        Some(normal_cdf((vpin.max(1e-12).ln() - mean) / sigma))
+    }
+}
+
+/// VPIN over a classified tape. `bucket_volume` 0 resolves to tape volume / (window + cdf_window),
+/// so the last bucket's VPIN is scored against a full CDF history; a tape without volume has no buckets.
+pub fn vpin_series(trades: &[ClassifiedTrade], config: &VpinConfig) -> Result<Vec<VpinPoint>, EngineError> {
+    let mut config = *config;
+    if config.bucket_volume == 0.0 {
+        let volume: f64 = trades.iter().map(|t| t.volume).sum();
+        if volume == 0.0 {
+            return Ok(Vec::new());
+        }
+        config.bucket_volume = volume / (config.window + config.cdf_window) as f64;
+    }
+
+    let mut estimator = VpinEstimator::new(config)?;
+    let mut points = Vec::new();
+    for trade in trades {
+        points.extend(estimator.push(trade)?);
+    }
+    Ok(points)
+}
+
+/// Validate and classify a raw tape, then compute its VPIN series
+pub fn tape_vpin(
+    trades: &[Trade],
+    quotes: &[OrderBookSnapshot],
+    classifier: &TradeClassifierConfig,
+    config: &VpinConfig,
+) -> Result<Vec<VpinPoint>, EngineError> {
+    trades::validate_tape(trades, quotes, classifier)?;
+    vpin_series(&trades::classify_trades(trades, quotes, classifier), config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::trades::Aggressor;
+
+    fn flow(timestamp: u64, volume: f64, buy_share: f64) -> ClassifiedTrade {
+        ClassifiedTrade {
+            timestamp,
+            price: 100.0,
+            volume,
+            aggressor: Aggressor::Unknown,
+            buy_volume: volume * buy_share,
+            sell_volume: volume * (1.0 - buy_share),
+            signed_volume: volume * (2.0 * buy_share - 1.0),
+        }
+    }
+
+    #[test]
+    fn test_vpin_buckets_and_alerts() {
+        let config = VpinConfig {
+            bucket_volume: 10.0,
+            window: 2,
+            cdf_window: 20,
+            ..Default::default()
+        };
+
+        // A 25-lot splits across three buckets; the third is still open
+        let mut estimator = VpinEstimator::new(config).unwrap();
+        let points = estimator.push(&flow(1, 25.0, 1.0)).unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].vpin, None); // Window not yet full
+        assert_eq!(points[1].vpin, Some(1.0)); // One-sided flow is fully toxic
+        let points = estimator.push(&flow(2, 5.0, 0.0)).unwrap();
+        // Bucket 2: 5 buy + 5 sell, window = [10, 0] -> 0.5
+        assert_eq!((points[0].bucket, points[0].start_timestamp, points[0].timestamp), (2, 1, 2));
+        assert_eq!(points[0].vpin, Some(0.5));
+
+        // Mildly imbalanced calm tape, then a one-sided burst
+        let mut tape: Vec<ClassifiedTrade> = (0..40)
+            .map(|i| flow(i, 10.0, [0.55, 0.4, 0.5][i as usize % 3]))
+            .collect();
+        tape.extend((40..44).map(|i| flow(i, 10.0, 1.0)));
+        let series = vpin_series(&tape, &config).unwrap();
+        assert_eq!(series.len(), 44);
+
+        let calm = &series[30];
+        assert!(calm.vpin.unwrap() < 0.2);
+        assert!(calm.alert < VpinAlert::High);
+        let burst = &series[41]; // Window fully one-sided
+        assert_eq!(burst.vpin, Some(1.0));
+        assert!(burst.cdf.unwrap() > 0.99);
+        assert_eq!(burst.alert, VpinAlert::Critical);
+
+        // Auto bucket size: 440 lots / (5 + 20) buckets
+        let auto = vpin_series(&tape, &VpinConfig { window: 5, cdf_window: 20, ..Default::default() }).unwrap();
+        assert_eq!(auto.len(), 25);
+
+        // Omega's call: default config with auto buckets still yields a CDF-scored alert
+        let mut tape: Vec<ClassifiedTrade> = (0..2000)
+            .map(|i| flow(i, 10.0, [0.55, 0.4, 0.5][i as usize % 3]))
+            .collect();
+        tape.extend((2000..2400).map(|i| flow(i, 10.0, 1.0)));
+        let series = vpin_series(&tape, &VpinConfig::default()).unwrap();
+        assert_eq!(series.len(), 300);
+        let last = series.last().unwrap();
+        assert!(last.cdf.is_some());
+        assert_eq!(last.alert, VpinAlert::Critical);
+        assert!(series[200].cdf.is_some() && series[200].alert < VpinAlert::High);
+
+        // Oversized and non-finite prints are rejected instead of looping bucket by bucket
+        let mut estimator = VpinEstimator::new(VpinConfig { bucket_volume: 1.0, ..config }).unwrap();
+        assert!(estimator.push(&flow(1, 1e9, 0.5)).is_err());
+        assert!(estimator.push(&flow(1, 1e300, 0.5)).is_err());
+        assert!(matches!(
+            estimator.push(&flow(1, f64::INFINITY, 0.5)),
+            Err(EngineError::InvalidSnapshot { index: 0, error: ValidationError::NonFinite })
+        ));
+        assert_eq!(estimator.push(&flow(1, 3.5, 0.5)).unwrap().len(), 3);
+
+        // Automatic bucket size on an empty or volume-free tape: no buckets, no error
+        assert!(vpin_series(&[], &VpinConfig::default()).unwrap().is_empty());
+        assert!(vpin_series(&[flow(1, 0.0, 0.5), flow(2, 0.0, 0.5)], &VpinConfig::default()).unwrap().is_empty());
+
+        assert!(VpinEstimator::new(VpinConfig::default()).is_err());
+        assert!(VpinEstimator::new(VpinConfig { bucket_volume: 1.0, high: 0.5, ..Default::default() }).is_err());
+    }
+}
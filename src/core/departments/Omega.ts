@@ -8,6 +8,8 @@
  * ║   - Mempool monitoring (Whale transaction detection)                          ║
  * ║   - TDA (Topological Data Analysis) integration                               ║
  * ║   - Liquidity curvature calculation                                           ║
+ * ║   - Order-flow toxicity (VPIN) early warning                                  ║
+ * ║   - Persistence-diagram distance crash precursor                              ║
  * ║                                                                               ║
  * ║   © 2026 QAntum Empire | VortexAI Architecture                                ║
  * ║                                                                               ║
@@ -36,6 +38,35 @@ export interface LiquidityCurvature {
     riskLevel: 'LOW' | 'MEDIUM' | 'HIGH' | 'CRITICAL';
 }
 
+export interface TradePrint {
+    price: number;
+    volume: number;
+    timestamp: number;
+}
+
+export interface FlowToxicity {
+    vpin: number; // Volume-synchronised probability of informed trading (0-1)
+    cdf: number | null; // VPIN percentile against its recent history
+    buckets: number;
+    riskLevel: 'LOW' | 'MEDIUM' | 'HIGH' | 'CRITICAL';
+}
+
+export interface BookTick {
+    bidVolume: number;
+    askVolume: number;
+    bidPrice: number;
+    askPrice: number;
+    timestamp: number;
+}
+
+export interface TopologicalShift {
+    timestamp: number | null; // Last window's closing snapshot
+    distance: number | null; // H1 Wasserstein distance to the previous window
+    zScore: number | null; // Distance against the trailing baseline
+    spike: boolean; // Crash precursor
+    windows: number;
+}
+
 export interface MempoolSnapshot {
     pendingTxCount: number;
     whaleCount: number;
@@ -52,6 +83,7 @@ export class OmegaEngine {
     private rustMempool: any = null;
     private rustTDA: any = null;
     private mempoolHistory: MempoolSnapshot[] = [];
+    private mempoolConnected = false;
     private whaleThreshold = 100; // ETH
 
     constructor() {
@@ -74,15 +106,42 @@ export class OmegaEngine {
         }
     }
 
+    /**
+     * Subscribe the Rust mempool listener to an Ethereum node (ws:// or wss://).
+     * Until this succeeds, scanMempool uses the simulated TypeScript fallback.
+     */
+    connectMempool(url: string): boolean {
+        if (!this.rustMempool?.connect_mempool) {
+            console.warn('[Omega] ⚠️ Rust mempool bridge not loaded; staying on simulated mempool');
+            return false;
+        }
+
+        // Buffer only whales: scans discard anything smaller, so it would just crowd the buffer
+        this.rustMempool.connect_mempool({ url, whaleThresholdEth: this.whaleThreshold });
+        this.mempoolConnected = true;
+        console.log(`[Omega] 🐋 Mempool subscribed: ${url}`);
+        return true;
+    }
+
     /**
      * Scan mempool for whale transactions
      */
     async scanMempool(): Promise<WhaleTransaction[]> {
         console.log('[Omega] 🐋 Scanning mempool for whale transactions...');
 
-        if (this.rustMempool?.scan_mempool) {
-            // Use Rust implementation
-            return this.rustMempool.scan_mempool(this.whaleThreshold);
+        if (this.mempoolConnected && this.rustMempool?.scan_mempool) {
+            // Use Rust implementation (drains whales buffered since the last scan)
+            const whales = this.rustMempool.scan_mempool(this.whaleThreshold);
+            return whales.map((tx: any) => ({
+                hash: tx.hash,
+                from: tx.from,
+                to: tx.to,
+                value: tx.valueEth,
+                gasPrice: tx.gasPriceGwei,
+                timestamp: tx.timestamp,
+                isWhale: true,
+                confidence: tx.valueEth / this.whaleThreshold,
+            }));
         }
 
         // TypeScript fallback (simulated)
@@ -183,11 +242,140 @@ export class OmegaEngine {
         return {
             curvature,
             holes,
-            manifoldDimension: 2, // Simplified
+            manifoldDimension: this.estimateProfileDimension(liquidityData),
             riskLevel,
         };
     }
 
+    /**
+     * Intrinsic dimension of the depth profiles, one point per time column of
+     * liquidityData[level][t]: Levina-Bickel MLE (k = 10), else Two-NN (Facco et al.)
+     * when too few distinct profiles; 0 when undefined. Mirrors the Rust path.
+     */
+    private estimateProfileDimension(liquidityData: number[][]): number {
+        const k = 10;
+        const times = liquidityData[0]?.length ?? 0;
+        const profiles = Array.from({ length: times }, (_, t) => liquidityData.map((row) => row[t]));
+
+        // Sorted distances to the other profiles, skipping duplicates
+        const neighbours = profiles.map((p) =>
+            profiles
+                .map((q) => Math.sqrt(p.reduce((sum, v, i) => sum + Math.pow(v - q[i], 2), 0)))
+                .filter((d) => d > 0)
+                .sort((x, y) => x - y)
+        );
+
+        // Levina-Bickel: inverse of the mean inverse local estimate (MacKay & Ghahramani)
+        const inverses = neighbours
+            .filter((row) => row.length >= k)
+            .map((row) => row.slice(0, k - 1).reduce((sum, t) => sum + Math.log(row[k - 1] / t), 0) / (k - 1));
+        const meanInverse = inverses.reduce((sum, v) => sum + v, 0) / Math.max(inverses.length, 1);
+        if (inverses.length > 0 && meanInverse > 0) {
+            return 1 / meanInverse;
+        }
+
+        const logRatios = neighbours.filter((row) => row.length >= 2).map((row) => Math.log(row[1] / row[0]));
+        const total = logRatios.reduce((sum, v) => sum + v, 0);
+        return total > 0 ? logRatios.length / total : 0;
+    }
+
+    /**
+     * Order-flow toxicity (VPIN) as a flash-crash early warning
+     */
+    async assessFlowToxicity(trades: TradePrint[]): Promise<FlowToxicity> {
+        console.log('[Omega] ☣️ Measuring order-flow toxicity (VPIN)...');
+
+        if (this.rustTDA?.calculate_vpin) {
+            // Use Rust implementation (tick rule; auto bucket size leaves window + CDF history buckets)
+            const points = this.rustTDA.calculate_vpin(trades, null, { method: 'tick' }, null);
+            const last = [...points].reverse().find((p: any) => p.vpin != null);
+            return {
+                vpin: last?.vpin ?? 0,
+                cdf: last?.cdf ?? null,
+                buckets: points.length,
+                riskLevel: last?.alert ?? 'LOW',
+            };
+        }
+
+        // TypeScript fallback
+        return this.assessFlowToxicityTS(trades);
+    }
+
+    /**
+     * TypeScript fallback for VPIN: tick-rule signs, 50 equal-volume buckets,
+     * risk from the raw VPIN level (no CDF history)
+     */
+    private assessFlowToxicityTS(trades: TradePrint[]): FlowToxicity {
+        const totalVolume = trades.reduce((sum, t) => sum + t.volume, 0);
+        const bucketVolume = totalVolume / 50;
+        if (bucketVolume <= 0) {
+            return { vpin: 0, cdf: null, buckets: 0, riskLevel: 'LOW' };
+        }
+
+        let lastPrice: number | null = null;
+        let side = 0;
+        let filled = 0;
+        let signed = 0;
+        let imbalance = 0;
+        let buckets = 0;
+
+        for (const trade of trades) {
+            if (lastPrice !== null && trade.price !== lastPrice) {
+                side = trade.price > lastPrice ? 1 : -1;
+            }
+            lastPrice = trade.price;
+
+            let remaining = trade.volume;
+            while (remaining > 0) {
+                const take = Math.min(remaining, bucketVolume - filled);
+                filled += take;
+                signed += side * take;
+                remaining -= take;
+                if (filled >= bucketVolume * (1 - 1e-12)) {
+                    imbalance += Math.abs(signed);
+                    buckets++;
+                    filled = 0;
+                    signed = 0;
+                }
+            }
+        }
+
+        const vpin = buckets > 0 ? imbalance / (buckets * bucketVolume) : 0;
+
+        let riskLevel: 'LOW' | 'MEDIUM' | 'HIGH' | 'CRITICAL' = 'LOW';
+        if (vpin > 0.8) riskLevel = 'CRITICAL';
+        else if (vpin > 0.6) riskLevel = 'HIGH';
+        else if (vpin > 0.4) riskLevel = 'MEDIUM';
+
+        console.log(`[Omega] ☣️ VPIN: ${vpin.toFixed(4)}, Buckets: ${buckets}, Risk: ${riskLevel}`);
+
+        return { vpin, cdf: null, buckets, riskLevel };
+    }
+
+    /**
+     * Topological regime change: a spike in the distance between consecutive
+     * persistence diagrams of the order book is a crash precursor
+     */
+    async detectTopologicalShift(ticks: BookTick[]): Promise<TopologicalShift> {
+        console.log('[Omega] 🕳️ Tracking persistence-diagram distance...');
+
+        if (this.rustTDA?.calculate_topology_series) {
+            // Use Rust implementation (50-tick windows, every 5 ticks)
+            const series = await this.rustTDA.calculate_topology_series(ticks, null, { step: 5 });
+            const last = series.points[series.points.length - 1];
+            return {
+                timestamp: last?.timestamp ?? null,
+                distance: last?.wassersteinH1 ?? null,
+                zScore: last?.distanceZ ?? null,
+                spike: last?.spike ?? false,
+                windows: series.points.length,
+            };
+        }
+
+        // No TypeScript fallback: Rips persistence is too heavy for the event loop
+        return { timestamp: null, distance: null, zScore: null, spike: false, windows: 0 };
+    }
+
     /**
      * Update mempool snapshot
      */
@@ -206,6 +394,7 @@ export class OmegaEngine {
     getStats() {
         return {
             rustMempoolActive: !!this.rustMempool,
+            mempoolConnected: this.mempoolConnected,
             rustTDAActive: !!this.rustTDA,
             mode: this.rustMempool && this.rustTDA ? 'RUST' : 'TYPESCRIPT_FALLBACK',
             historySize: this.mempoolHistory.length,
@@ -214,7 +403,8 @@ export class OmegaEngine {
     }
 
     /**
-     * Set whale threshold
+     * Set whale threshold (a live Rust mempool keeps its connect-time buffer threshold
+     * until connectMempool is called again)
      */
     setWhaleThreshold(threshold: number): void {
         this.whaleThreshold = threshold;